@@ -2,7 +2,9 @@ use std::fs;
 use std::hint::black_box;
 use criterion::{criterion_group, criterion_main, Criterion, Throughput};
 use myjson::lexer::lexer;
-use myjson::{parse, parse_bytes, stringify};
+use myjson::{parse, parse_bytes, parse_bytes_in, parse_bytes_lazy, parse_bytes_to_tape, stringify};
+use myjson::arena::Bump;
+use myjson::types::JSONValue;
 
 fn lexer_benchmark(c: &mut Criterion) {
     let mut group = c.benchmark_group("lex");
@@ -61,6 +63,98 @@ fn byte_parser_benchmark(c: &mut Criterion) {
     });
 }
 
+// Collects every string in a document into one array, so parsing it mostly exercises string scanning
+fn strings_only(input: &str) -> String {
+    fn collect(value: &JSONValue, strings: &mut Vec<JSONValue>) {
+        match value {
+            JSONValue::String { .. } => strings.push(value.clone()),
+            JSONValue::Array { data } => data.iter().for_each(|value| collect(value, strings)),
+            JSONValue::Object { data } => data.iter().for_each(|(key, value)| {
+                strings.push(JSONValue::String { string: key.as_str().into() });
+                collect(value, strings)
+            }),
+            _ => {}
+        }
+    }
+    let mut strings = Vec::new();
+    collect(&parse_bytes(input.as_bytes()).unwrap(), &mut strings);
+    stringify(&JSONValue::Array { data: strings })
+}
+
+fn string_scan_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("byte_parse_strings");
+    let citm_catalog = strings_only(&fs::read_to_string("data/citm_catalog.json").unwrap());
+    group.throughput(Throughput::Bytes(citm_catalog.len() as u64));
+    group.bench_function("citm_catalog.json", |b| {
+        b.iter(|| parse_bytes(black_box(citm_catalog.as_bytes())));
+    });
+    let twitter = strings_only(&fs::read_to_string("data/twitter.json").unwrap());
+    group.throughput(Throughput::Bytes(twitter.len() as u64));
+    group.bench_function("twitter.json", |b| {
+        b.iter(|| parse_bytes(black_box(twitter.as_bytes())));
+    });
+}
+
+fn tape_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tape");
+    let canada = fs::read_to_string("data/canada.json").unwrap();
+    group.throughput(Throughput::Bytes(canada.len() as u64));
+    group.bench_function("canada.json", |b| {
+        b.iter(|| parse_bytes_to_tape(black_box(canada.as_bytes())));
+    });
+    let citm_catalog = fs::read_to_string("data/citm_catalog.json").unwrap();
+    group.throughput(Throughput::Bytes(citm_catalog.len() as u64));
+    group.bench_function("citm_catalog.json", |b| {
+        b.iter(|| parse_bytes_to_tape(black_box(citm_catalog.as_bytes())));
+    });
+    let twitter = fs::read_to_string("data/twitter.json").unwrap();
+    group.throughput(Throughput::Bytes(twitter.len() as u64));
+    group.bench_function("twitter.json", |b| {
+        b.iter(|| parse_bytes_to_tape(black_box(twitter.as_bytes())));
+    });
+}
+
+fn arena_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("arena");
+    let mut arena = Bump::new();
+    let canada = fs::read_to_string("data/canada.json").unwrap();
+    group.throughput(Throughput::Bytes(canada.len() as u64));
+    group.bench_function("canada.json", |b| {
+        b.iter(|| {
+            arena.reset();
+            parse_bytes_in(&arena, black_box(canada.as_bytes())).is_ok()
+        });
+    });
+    let citm_catalog = fs::read_to_string("data/citm_catalog.json").unwrap();
+    group.throughput(Throughput::Bytes(citm_catalog.len() as u64));
+    group.bench_function("citm_catalog.json", |b| {
+        b.iter(|| {
+            arena.reset();
+            parse_bytes_in(&arena, black_box(citm_catalog.as_bytes())).is_ok()
+        });
+    });
+    let twitter = fs::read_to_string("data/twitter.json").unwrap();
+    group.throughput(Throughput::Bytes(twitter.len() as u64));
+    group.bench_function("twitter.json", |b| {
+        b.iter(|| {
+            arena.reset();
+            parse_bytes_in(&arena, black_box(twitter.as_bytes())).is_ok()
+        });
+    });
+}
+
+fn lazy_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lazy");
+    let twitter = fs::read_to_string("data/twitter.json").unwrap();
+    group.throughput(Throughput::Bytes(twitter.len() as u64));
+    group.bench_function("twitter.json", |b| {
+        b.iter(|| {
+            let root = parse_bytes_lazy(black_box(twitter.as_bytes())).unwrap();
+            root.get_path(&["search_metadata", "count"]).unwrap().unwrap().as_f64().unwrap()
+        });
+    });
+}
+
 fn simd_parse_reference(c: &mut Criterion) {
     let mut group = c.benchmark_group("simd");
     let mut canada = fs::read_to_string("data/canada.json").unwrap();
@@ -145,5 +239,5 @@ fn stringify_benchmark(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, lexer_benchmark, parser_benchmark, stringify_benchmark, simd_parse_reference, serde_parse_reference, byte_parser_benchmark);
+criterion_group!(benches, lexer_benchmark, parser_benchmark, stringify_benchmark, simd_parse_reference, serde_parse_reference, byte_parser_benchmark, string_scan_benchmark, tape_benchmark, lazy_benchmark, arena_benchmark);
 criterion_main!(benches);
\ No newline at end of file
@@ -0,0 +1,60 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use myjson::{parse_bytes, parse_bytes_interned};
+use myjson::intern::KeyInterner;
+
+// Counts live bytes and allocation calls so the retained size of a parsed document can be reported
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+// Returns the bytes still held by the parsed value and the number of allocations made while parsing
+fn measure<T>(parse: impl FnOnce() -> T) -> (usize, usize) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let value = parse();
+    let retained = ALLOCATED.load(Ordering::Relaxed) - before;
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+    drop(value);
+    (retained, allocations)
+}
+
+fn main() {
+    println!("{:<20} {:>14} {:>12} {:>14} {:>12}", "", "retained", "allocations", "interned", "allocations");
+    for file in ["canada.json", "citm_catalog.json", "twitter.json"] {
+        let input = fs::read(format!("data/{file}")).unwrap();
+        let (plain, plain_allocations) = measure(|| parse_bytes(&input).unwrap());
+        // The interner's own table is counted too, since it lives as long as the keys it shares
+        let (interned, interned_allocations) = measure(|| {
+            let mut interner = KeyInterner::new();
+            let value = parse_bytes_interned(&input, &mut interner).unwrap();
+            (interner, value)
+        });
+        println!("{file:<20} {plain:>14} {plain_allocations:>12} {interned:>14} {interned_allocations:>12}");
+    }
+}
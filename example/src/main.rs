@@ -1,13 +1,209 @@
-use std::io;
-use std::io::Read;
-use myjson::{parse, stringify};
-
-fn main() -> (){
-    let mut buffer = String::new();
-    let stdin = io::stdin();
-    let mut handle = stdin.lock();
-    
-    handle.read_to_string(&mut buffer).unwrap();
-    let obj = parse(buffer.chars()).unwrap();
-    println!("{}", stringify(&obj));
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+use std::{env, fs};
+use myjson::{parse_bytes_with_dialect, parse_document, parse_recovering, stringify, Query};
+use myjson::spans::Position;
+use myjson::types::{Dialect, JSONValue};
+
+const USAGE: &str = "usage: myjson <command> [options] [files...]
+       myjson query <filter> [options] [files...]
+
+Reads stdin when no files are given.
+
+commands:
+  fmt         pretty print, keeping comments
+  minify      remove all whitespace and comments
+  validate    report every syntax error with its position
+  query       run a jq filter such as `.servers[] | select(.port > 100) | .host`
+
+options:
+  --indent N        indent by N spaces (default 2)
+  --tab             indent with tabs
+  --dialect NAME    json, jsonc or json5 (default: from the file extension, otherwise json)
+  -i, --in-place    rewrite the files instead of printing them
+  --check           with fmt, only report files that are not formatted
+  -c, --compact     with query, print each result on one line
+  -r, --raw-output  with query, print strings without quotes";
+
+enum Command {
+    Fmt,
+    Minify,
+    Validate,
+    Query(Query),
+}
+
+struct Options {
+    command: Command,
+    indent: String,
+    dialect: Option<Dialect>,
+    in_place: bool,
+    check: bool,
+    compact: bool,
+    raw_output: bool,
+    files: Vec<String>,
+}
+
+// Exit codes: 1 when an input is invalid or not formatted, 2 for usage and I/O errors
+enum Failure {
+    Invalid,
+    Error(String),
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let name = args.next().ok_or("missing command")?;
+    // The command is set once the arguments are read, since the filter of a query may follow options
+    let mut options = Options { command: Command::Fmt, indent: "  ".to_string(), dialect: None, in_place: false, check: false, compact: false, raw_output: false, files: Vec::new() };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--indent" => {
+                let width = args.next().and_then(|width| width.parse().ok()).ok_or("--indent needs a number")?;
+                options.indent = " ".repeat(width);
+            }
+            "--tab" => options.indent = "\t".to_string(),
+            "--dialect" => options.dialect = Some(match args.next().as_deref() {
+                Some("json") => Dialect::Json,
+                Some("jsonc") => Dialect::Jsonc,
+                Some("json5") => Dialect::Json5,
+                _ => return Err("--dialect needs one of json, jsonc or json5".to_string()),
+            }),
+            "-i" | "--in-place" => options.in_place = true,
+            "--check" => options.check = true,
+            "-c" | "--compact" => options.compact = true,
+            "-r" | "--raw-output" => options.raw_output = true,
+            "--" => options.files.extend(args.by_ref()),
+            _ if arg.starts_with('-') && arg != "-" => return Err(format!("unknown option `{arg}`")),
+            _ => options.files.push(arg),
+        }
+    }
+    options.command = match name.as_str() {
+        "fmt" => Command::Fmt,
+        "minify" => Command::Minify,
+        "validate" => Command::Validate,
+        "query" if options.files.is_empty() => return Err("query needs a filter".to_string()),
+        "query" => Command::Query(Query::compile(&options.files.remove(0)).map_err(|error| format!("invalid filter: {error}"))?),
+        _ => return Err(format!("unknown command `{name}`")),
+    };
+    let is_query = matches!(options.command, Command::Query(_));
+    if options.in_place && (options.files.is_empty() || matches!(options.command, Command::Validate) || is_query) {
+        return Err("--in-place needs files to rewrite and only applies to fmt and minify".to_string());
+    }
+    if options.check && !matches!(options.command, Command::Fmt) {
+        return Err("--check only applies to fmt".to_string());
+    }
+    if (options.compact || options.raw_output) && !is_query {
+        return Err("--compact and --raw-output only apply to query".to_string());
+    }
+    Ok(options)
+}
+
+fn dialect_of(name: &str) -> Dialect {
+    if name.ends_with(".jsonc") {
+        Dialect::Jsonc
+    } else if name.ends_with(".json5") {
+        Dialect::Json5
+    } else {
+        Dialect::Json
+    }
+}
+
+// Prints every syntax error as `name:line:column: message`
+fn report_errors(name: &str, text: &str, dialect: Dialect) -> Result<(), Failure> {
+    let diagnostics = parse_recovering(text, dialect).1;
+    for diagnostic in &diagnostics {
+        let Position { line, column } = Position::of(text, diagnostic.span.start);
+        eprintln!("{name}:{line}:{column}: {}", diagnostic.message);
+    }
+    if diagnostics.is_empty() { Ok(()) } else { Err(Failure::Invalid) }
+}
+
+fn run(options: &Options, name: &str, text: &str) -> Result<Option<String>, Failure> {
+    let dialect = options.dialect.unwrap_or_else(|| dialect_of(name));
+    match &options.command {
+        Command::Validate => return report_errors(name, text, dialect).map(|_| None),
+        Command::Query(query) => return run_query(options, query, name, text, dialect).map(Some),
+        _ => {}
+    }
+    let Ok(document) = parse_document(text, dialect) else {
+        report_errors(name, text, dialect)?;
+        eprintln!("{name}: invalid");
+        return Err(Failure::Invalid)
+    };
+    let output = match options.command {
+        Command::Fmt => document.format(&options.indent),
+        _ => document.minify() + "\n",
+    };
+    if options.check {
+        if output == text {
+            return Ok(None)
+        }
+        eprintln!("{name}: not formatted");
+        return Err(Failure::Invalid)
+    }
+    Ok(Some(output))
+}
+
+// Prints every result of the query on its own line
+fn run_query(options: &Options, query: &Query, name: &str, text: &str, dialect: Dialect) -> Result<String, Failure> {
+    let Ok(input) = parse_bytes_with_dialect(text.as_bytes(), dialect) else {
+        report_errors(name, text, dialect)?;
+        eprintln!("{name}: invalid");
+        return Err(Failure::Invalid)
+    };
+    let results = query.run(&input).map_err(|error| {
+        eprintln!("{name}: {error}");
+        Failure::Invalid
+    })?;
+    let mut output = String::new();
+    for result in results {
+        match result {
+            JSONValue::String { string } if options.raw_output => output.push_str(&string),
+            result if options.compact => output.push_str(&stringify(&result)),
+            result => output.push_str(&format!("{result:#}")),
+        }
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+fn process(options: &Options, name: &str) -> Result<(), Failure> {
+    let mut text = String::new();
+    if name == "-" {
+        io::stdin().read_to_string(&mut text).map_err(|error| Failure::Error(format!("stdin: {error}")))?;
+    } else {
+        text = fs::read_to_string(name).map_err(|error| Failure::Error(format!("{name}: {error}")))?;
+    }
+    let Some(output) = run(options, name, &text)? else {
+        return Ok(())
+    };
+    if options.in_place {
+        if output != text {
+            fs::write(name, output).map_err(|error| Failure::Error(format!("{name}: {error}")))?;
+        }
+        return Ok(())
+    }
+    io::stdout().write_all(output.as_bytes()).map_err(|error| Failure::Error(format!("stdout: {error}")))
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("myjson: {message}\n\n{USAGE}");
+            return ExitCode::from(2)
+        }
+    };
+    let files = if options.files.is_empty() { vec!["-".to_string()] } else { options.files.clone() };
+    // Every file is processed even after a failure, so that all problems are reported at once
+    let mut code = ExitCode::SUCCESS;
+    for name in &files {
+        match process(&options, name) {
+            Ok(()) => {}
+            Err(Failure::Invalid) => code = ExitCode::FAILURE,
+            Err(Failure::Error(message)) => {
+                eprintln!("myjson: {message}");
+                return ExitCode::from(2)
+            }
+        }
+    }
+    code
 }
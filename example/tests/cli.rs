@@ -0,0 +1,99 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn myjson(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_myjson"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    // The tool may exit without reading its input, for instance on a usage error
+    if let Err(error) = child.stdin.take().unwrap().write_all(stdin.as_bytes()) {
+        assert_eq!(std::io::ErrorKind::BrokenPipe, error.kind());
+    }
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+fn stderr(output: &Output) -> &str {
+    std::str::from_utf8(&output.stderr).unwrap()
+}
+
+#[test]
+fn fmt() {
+    let output = myjson(&["fmt"], r#"{"b":1,"a":[true]}"#);
+    assert!(output.status.success());
+    assert_eq!("{\n  \"b\": 1,\n  \"a\": [\n    true\n  ]\n}\n", stdout(&output));
+    let output = myjson(&["fmt", "--indent", "4", "--dialect", "jsonc"], "[1, // one\n2]");
+    assert_eq!("[\n    1, // one\n    2\n]\n", stdout(&output));
+    assert_eq!("[\n\t1\n]\n", stdout(&myjson(&["fmt", "--tab"], "[1]")));
+}
+
+#[test]
+fn minify() {
+    let output = myjson(&["minify", "--dialect", "json5"], "// c\n{ b: 0x1, a: [ 'x', ], }");
+    assert!(output.status.success());
+    assert_eq!("{b:0x1,a:['x']}\n", stdout(&output));
+}
+
+#[test]
+fn validate() {
+    let output = myjson(&["validate"], "{\"a\": 1}");
+    assert!(output.status.success());
+    assert_eq!("", stderr(&output));
+    let output = myjson(&["validate"], "{\n  \"a\" 1,\n  \"b\": [\n}");
+    assert_eq!(Some(1), output.status.code());
+    assert_eq!("-:2:7: expected `:`\n-:4:1: expected `]`\n", stderr(&output));
+}
+
+#[test]
+fn query() {
+    let input = r#"{"servers": [{"host": "a", "up": true}, {"host": "b", "up": false}]}"#;
+    let output = myjson(&["query", ".servers[] | select(.up)"], input);
+    assert!(output.status.success());
+    assert_eq!("{\n  \"host\": \"a\",\n  \"up\": true\n}\n", stdout(&output));
+    assert_eq!("a\nb\n", stdout(&myjson(&["query", "-r", ".servers[].host"], input)));
+    assert_eq!("[\"a\",\"b\"]\n", stdout(&myjson(&["query", "--compact", "[.servers[].host]"], input)));
+
+    let output = myjson(&["query", ".servers.host"], input);
+    assert_eq!(Some(1), output.status.code());
+    assert_eq!("-: cannot index array with string (\"host\")\n", stderr(&output));
+    let output = myjson(&["query", ".servers["], input);
+    assert_eq!(Some(2), output.status.code());
+    assert!(stderr(&output).starts_with("myjson: invalid filter: syntax error at 9"));
+}
+
+#[test]
+fn files_in_place_and_check() {
+    let dir = std::env::temp_dir().join(format!("myjson-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("config.jsonc");
+    let path = file.to_str().unwrap();
+    std::fs::write(&file, "// settings\n{\"a\":1,}").unwrap();
+
+    let output = myjson(&["fmt", "--check", path], "");
+    assert_eq!(Some(1), output.status.code());
+    assert_eq!(format!("{path}: not formatted\n"), stderr(&output));
+
+    // The dialect comes from the file extension
+    assert!(myjson(&["fmt", "-i", path], "").status.success());
+    assert_eq!("// settings\n{\n  \"a\": 1,\n}\n", std::fs::read_to_string(&file).unwrap());
+    assert!(myjson(&["fmt", "--check", path], "").status.success());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn usage_errors() {
+    for args in [&[][..], &["lint"], &["fmt", "--indent"], &["fmt", "--dialect", "yaml"], &["validate", "-i", "a.json"], &["fmt", "-i"], &["minify", "--check"], &["query"], &["query", ".", "-i", "a.json"], &["fmt", "-r"]] {
+        let output = myjson(args, "");
+        assert_eq!(Some(2), output.status.code(), "{args:?}");
+        assert!(stderr(&output).contains("usage: myjson"));
+    }
+    assert_eq!(Some(2), myjson(&["fmt", "/nonexistent/file.json"], "").status.code());
+}
@@ -0,0 +1,532 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::ext::IdentExt;
+use syn::{parse_macro_input, parse_quote, Attribute, Data, DeriveInput, Error, Fields, Generics, Ident, LitStr, Path};
+
+// Derives `myjson::ToJson` and `myjson::FromJson`. Supported attributes:
+//   container: #[json(tag = "...")], #[json(tag = "...", content = "...")], #[json(untagged)], on enums only
+//   variant:   #[json(rename = "...")]
+//   field:     #[json(rename = "...")], #[json(default)], #[json(default = "path")], #[json(skip)], #[json(flatten)]
+// Newtype variants of internally tagged enums must hold a `myjson::ToJsonObject`, such as a struct with named fields.
+
+#[derive(Default)]
+struct ContainerAttrs {
+    tag: Option<String>,
+    content: Option<String>,
+    untagged: bool,
+}
+
+#[derive(Default)]
+struct FieldAttrs {
+    rename: Option<String>,
+    default: Option<Option<Path>>, // `Some(None)` uses `Default::default()`
+    skip: bool,
+    flatten: bool,
+}
+
+enum Tagging {
+    External,
+    Internal(String),
+    Adjacent(String, String),
+    Untagged,
+}
+
+fn parse_container_attrs(input: &DeriveInput) -> syn::Result<ContainerAttrs> {
+    let mut container = ContainerAttrs::default();
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("json")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("tag") {
+                container.tag = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("content") {
+                container.content = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("untagged") {
+                container.untagged = true;
+            } else {
+                return Err(meta.error("unknown json container attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    let tagged = container.tag.is_some() || container.content.is_some() || container.untagged;
+    if tagged && !matches!(input.data, Data::Enum(_)) {
+        return Err(Error::new_spanned(&input.ident, "`tag`, `content` and `untagged` only apply to enums"));
+    }
+    Ok(container)
+}
+
+fn parse_field_attrs(attrs: &[Attribute]) -> syn::Result<FieldAttrs> {
+    let mut field = FieldAttrs::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("json")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                field.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("default") {
+                if meta.input.peek(syn::Token![=]) {
+                    field.default = Some(Some(meta.value()?.parse::<LitStr>()?.parse()?));
+                } else {
+                    field.default = Some(None);
+                }
+            } else if meta.path.is_ident("skip") {
+                field.skip = true;
+            } else if meta.path.is_ident("flatten") {
+                field.flatten = true;
+            } else {
+                return Err(meta.error("unknown json field attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(field)
+}
+
+fn parse_variant_name(ident: &Ident, attrs: &[Attribute]) -> syn::Result<String> {
+    let attrs = parse_field_attrs(attrs)?;
+    if attrs.default.is_some() || attrs.skip || attrs.flatten {
+        return Err(Error::new_spanned(ident, "only `rename` is supported on enum variants"));
+    }
+    Ok(attrs.rename.unwrap_or_else(|| ident.unraw().to_string()))
+}
+
+fn tagging(container: &ContainerAttrs, input: &DeriveInput) -> syn::Result<Tagging> {
+    match (&container.tag, &container.content, container.untagged) {
+        (None, None, false) => Ok(Tagging::External),
+        (Some(tag), None, false) => Ok(Tagging::Internal(tag.clone())),
+        (Some(tag), Some(content), false) => Ok(Tagging::Adjacent(tag.clone(), content.clone())),
+        (None, None, true) => Ok(Tagging::Untagged),
+        _ => Err(Error::new_spanned(&input.ident, "invalid combination of `tag`, `content` and `untagged`"))
+    }
+}
+
+struct NamedField {
+    ident: Ident,
+    name: String,
+    attrs: FieldAttrs,
+}
+
+fn named_fields(fields: &syn::FieldsNamed) -> syn::Result<Vec<NamedField>> {
+    fields.named.iter()
+        .map(|field| {
+            let ident = field.ident.clone().unwrap();
+            let attrs = parse_field_attrs(&field.attrs)?;
+            let name = attrs.rename.clone().unwrap_or_else(|| ident.unraw().to_string());
+            Ok(NamedField { ident, name, attrs })
+        })
+        .collect()
+}
+
+fn add_bounds(generics: &Generics, bound: TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(#bound));
+    }
+    generics
+}
+
+// Pushes the members of the fields onto `__data`, a `Vec<(String, JSONValue)>`
+fn members_to_json(fields: &[NamedField], accessors: &[TokenStream2]) -> TokenStream2 {
+    let pushes = fields.iter().zip(accessors).filter(|(field, _)| !field.attrs.skip).map(|(field, accessor)| {
+        let name = &field.name;
+        if field.attrs.flatten {
+            // Flattened values which are not objects have no members to contribute
+            quote! {
+                if let ::myjson::types::JSONValue::Object { data } = ::myjson::ToJson::to_json(#accessor) {
+                    __data.extend(data);
+                }
+            }
+        } else {
+            quote! {
+                __data.push((::myjson::types::JSONKey::from(#name), ::myjson::ToJson::to_json(#accessor)));
+            }
+        }
+    });
+    quote! { #(#pushes)* }
+}
+
+fn object_from_members() -> TokenStream2 {
+    quote! { ::myjson::types::JSONValue::Object { data: __data.into_iter().collect() } }
+}
+
+fn array_to_json(accessors: &[TokenStream2]) -> TokenStream2 {
+    quote! { ::myjson::types::JSONValue::Array { data: vec![#(::myjson::ToJson::to_json(#accessors)),*] } }
+}
+
+fn default_value(default: &Option<Path>) -> TokenStream2 {
+    match default {
+        Some(path) => quote! { #path() },
+        None => quote! { ::core::default::Default::default() },
+    }
+}
+
+// Builds the fields from `__data`, the members of the object `__value`
+fn fields_from_members(fields: &[NamedField]) -> TokenStream2 {
+    let fields = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let name = &field.name;
+        let value = if field.attrs.skip {
+            default_value(field.attrs.default.as_ref().unwrap_or(&None))
+        } else if field.attrs.flatten {
+            quote! { ::myjson::FromJson::from_json(__value)? }
+        } else {
+            let missing = match &field.attrs.default {
+                Some(default) => default_value(default),
+                // Missing fields are treated as null so that `Option`s default to `None`
+                None => quote! {
+                    ::myjson::FromJson::from_json(&::myjson::types::JSONValue::Null).map_err(|_| {
+                        ::myjson::convert::ConversionError::new(::myjson::convert::ConversionErrorKind::MissingField(#name.to_string()))
+                    })?
+                },
+            };
+            quote! {
+                match __data.get(#name) {
+                    ::core::option::Option::Some(value) => ::myjson::FromJson::from_json(value)
+                        .map_err(|err| err.at(::myjson::types::PathSegment::Key(#name.to_string())))?,
+                    ::core::option::Option::None => #missing,
+                }
+            }
+        };
+        quote! { #ident: #value }
+    });
+    quote! { #(#fields),* }
+}
+
+fn expect_object() -> TokenStream2 {
+    quote! {
+        let ::myjson::types::JSONValue::Object { data: __data } = __value else {
+            return ::core::result::Result::Err(::myjson::convert::ConversionError::expected("object", __value))
+        };
+    }
+}
+
+// Converts the array `__value` into the given constructor
+fn tuple_from_array(constructor: TokenStream2, len: usize) -> TokenStream2 {
+    let elements = (0..len).map(|i| quote! {
+        ::myjson::FromJson::from_json(&__elements[#i]).map_err(|err| err.at(::myjson::types::PathSegment::Index(#i)))?
+    });
+    quote! {
+        let ::myjson::types::JSONValue::Array { data: __elements } = __value else {
+            return ::core::result::Result::Err(::myjson::convert::ConversionError::expected("array", __value))
+        };
+        if __elements.len() != #len {
+            return ::core::result::Result::Err(::myjson::convert::ConversionError::new(
+                ::myjson::convert::ConversionErrorKind::WrongLength { expected: #len, found: __elements.len() }
+            ));
+        }
+        ::core::result::Result::Ok(#constructor(#(#elements),*))
+    }
+}
+
+fn expand_to_json(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let container = parse_container_attrs(input)?;
+    // Structs with named fields always convert to objects, so they also implement `ToJsonObject`
+    let mut to_json_members = None;
+    let body = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let fields = named_fields(fields)?;
+                let accessors: Vec<_> = fields.iter().map(|field| { let ident = &field.ident; quote! { &self.#ident } }).collect();
+                let members = members_to_json(&fields, &accessors);
+                to_json_members = Some(quote! {
+                    let mut __data = ::std::vec::Vec::new();
+                    #members
+                    __data
+                });
+                quote! {
+                    let __data = ::myjson::ToJsonObject::to_json_members(self);
+                    ::myjson::types::JSONValue::Object { data: __data.into_iter().collect() }
+                }
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote! { ::myjson::ToJson::to_json(&self.0) },
+            Fields::Unnamed(fields) => {
+                let accessors: Vec<_> = (0..fields.unnamed.len()).map(|i| { let i = syn::Index::from(i); quote! { &self.#i } }).collect();
+                array_to_json(&accessors)
+            }
+            Fields::Unit => quote! { ::myjson::types::JSONValue::Null },
+        },
+        Data::Enum(data) => {
+            let tagging = tagging(&container, input)?;
+            let arms = data.variants.iter().map(|variant| {
+                let ident = &variant.ident;
+                let name = parse_variant_name(ident, &variant.attrs)?;
+                let tag_member = |tag: &String| quote! { __data.push((::myjson::types::JSONKey::from(#tag), ::myjson::types::JSONValue::String { string: ::myjson::small_string::SmallString::from(#name) })); };
+                let (pattern, content) = match &variant.fields {
+                    Fields::Unit => (quote! { Self::#ident }, None),
+                    Fields::Unnamed(fields) => {
+                        let bindings: Vec<_> = (0..fields.unnamed.len()).map(|i| format_ident!("__field{}", i)).collect();
+                        let accessors: Vec<_> = bindings.iter().map(|binding| quote! { #binding }).collect();
+                        let content = if bindings.len() == 1 {
+                            let binding = &bindings[0];
+                            if let Tagging::Internal(_) = tagging {
+                                // The tag joins the members of the payload, so it has to be an object
+                                let ty = &fields.unnamed[0].ty;
+                                quote_spanned! { ty.span()=> <#ty as ::myjson::ToJsonObject>::to_json_members(#binding) }
+                            } else {
+                                quote! { ::myjson::ToJson::to_json(#binding) }
+                            }
+                        } else {
+                            if let Tagging::Internal(_) = tagging {
+                                return Err(Error::new_spanned(ident, "internally tagged enums cannot contain tuple variants"));
+                            }
+                            array_to_json(&accessors)
+                        };
+                        (quote! { Self::#ident(#(#bindings),*) }, Some(content))
+                    }
+                    Fields::Named(fields) => {
+                        let fields = named_fields(fields)?;
+                        let accessors: Vec<_> = fields.iter().map(|field| { let ident = &field.ident; quote! { #ident } }).collect();
+                        let bindings: Vec<_> = fields.iter().map(|field| {
+                            let ident = &field.ident;
+                            if field.attrs.skip { quote! { #ident: _ } } else { quote! { #ident } }
+                        }).collect();
+                        let members = members_to_json(&fields, &accessors);
+                        let object = object_from_members();
+                        let tag = match &tagging {
+                            Tagging::Internal(tag) => tag_member(tag),
+                            _ => quote! {},
+                        };
+                        let content = quote! {{
+                            let mut __data = ::std::vec::Vec::new();
+                            #tag
+                            #members
+                            #object
+                        }};
+                        (quote! { Self::#ident { #(#bindings),* } }, Some(content))
+                    }
+                };
+                let string = quote! { ::myjson::types::JSONValue::String { string: ::myjson::small_string::SmallString::from(#name) } };
+                let value = match (&tagging, content) {
+                    (Tagging::External, None) => string,
+                    (Tagging::External, Some(content)) => quote! {
+                        ::myjson::types::JSONValue::Object { data: [(::myjson::types::JSONKey::from(#name), #content)].into_iter().collect() }
+                    },
+                    (Tagging::Internal(tag), None) | (Tagging::Adjacent(tag, _), None) => quote! {
+                        ::myjson::types::JSONValue::Object { data: [(::myjson::types::JSONKey::from(#tag), #string)].into_iter().collect() }
+                    },
+                    (Tagging::Internal(_), Some(content)) if matches!(variant.fields, Fields::Named(_)) => content,
+                    (Tagging::Internal(tag), Some(content)) => quote! {{
+                        let mut __data = #content;
+                        __data.push((::myjson::types::JSONKey::from(#tag), #string));
+                        ::myjson::types::JSONValue::Object { data: __data.into_iter().collect() }
+                    }},
+                    (Tagging::Adjacent(tag, content_name), Some(content)) => quote! {
+                        ::myjson::types::JSONValue::Object { data: [(::myjson::types::JSONKey::from(#tag), #string), (::myjson::types::JSONKey::from(#content_name), #content)].into_iter().collect() }
+                    },
+                    (Tagging::Untagged, None) => quote! { ::myjson::types::JSONValue::Null },
+                    (Tagging::Untagged, Some(content)) => content,
+                };
+                Ok(quote! { #pattern => #value, })
+            }).collect::<syn::Result<Vec<_>>>()?;
+            if arms.is_empty() {
+                quote! { match *self {} }
+            } else {
+                quote! { match self { #(#arms)* } }
+            }
+        }
+        Data::Union(_) => return Err(Error::new_spanned(&input.ident, "unions are not supported")),
+    };
+
+    let name = &input.ident;
+    let generics = add_bounds(&input.generics, quote! { ::myjson::ToJson });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let to_json_object = to_json_members.map(|members| quote! {
+        impl #impl_generics ::myjson::ToJsonObject for #name #ty_generics #where_clause {
+            fn to_json_members(&self) -> ::std::vec::Vec<(::myjson::types::JSONKey, ::myjson::types::JSONValue)> {
+                #members
+            }
+        }
+    });
+    Ok(quote! {
+        impl #impl_generics ::myjson::ToJson for #name #ty_generics #where_clause {
+            fn to_json(&self) -> ::myjson::types::JSONValue {
+                #body
+            }
+        }
+
+        #to_json_object
+    })
+}
+
+// Converts `__value` into the given variant, evaluating to a `Result`
+fn variant_from_json(ident: &Ident, fields: &Fields) -> syn::Result<TokenStream2> {
+    let body = match fields {
+        Fields::Unit => quote! { ::core::result::Result::Ok(Self::#ident) },
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote! {
+            ::core::result::Result::Ok(Self::#ident(::myjson::FromJson::from_json(__value)?))
+        },
+        Fields::Unnamed(fields) => tuple_from_array(quote! { Self::#ident }, fields.unnamed.len()),
+        Fields::Named(fields) => {
+            let fields = named_fields(fields)?;
+            let expect_object = expect_object();
+            let fields = fields_from_members(&fields);
+            quote! {
+                #expect_object
+                ::core::result::Result::Ok(Self::#ident { #fields })
+            }
+        }
+    };
+    Ok(quote! {
+        (|| -> ::core::result::Result<Self, ::myjson::convert::ConversionError> { #body })()
+    })
+}
+
+fn expand_from_json(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let container = parse_container_attrs(input)?;
+    let name = &input.ident;
+    let body = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let fields = named_fields(fields)?;
+                let expect_object = expect_object();
+                let fields = fields_from_members(&fields);
+                quote! {
+                    #expect_object
+                    ::core::result::Result::Ok(Self { #fields })
+                }
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote! {
+                ::core::result::Result::Ok(Self(::myjson::FromJson::from_json(__value)?))
+            },
+            Fields::Unnamed(fields) => tuple_from_array(quote! { Self }, fields.unnamed.len()),
+            Fields::Unit => quote! {
+                match __value {
+                    ::myjson::types::JSONValue::Null => ::core::result::Result::Ok(Self),
+                    _ => ::core::result::Result::Err(::myjson::convert::ConversionError::expected("null", __value)),
+                }
+            },
+        },
+        Data::Enum(data) => {
+            let tagging = tagging(&container, input)?;
+            let variants = data.variants.iter()
+                .map(|variant| Ok((parse_variant_name(&variant.ident, &variant.attrs)?, variant_from_json(&variant.ident, &variant.fields)?, matches!(variant.fields, Fields::Unit))))
+                .collect::<syn::Result<Vec<_>>>()?;
+            let unknown_variant = quote! {
+                ::core::result::Result::Err(::myjson::convert::ConversionError::new(::myjson::convert::ConversionErrorKind::UnknownVariant(__tag.to_string())))
+            };
+            let missing = |field: &String| quote! {
+                ::myjson::convert::ConversionError::new(::myjson::convert::ConversionErrorKind::MissingField(#field.to_string()))
+            };
+            let get_tag = |tag: &String| {
+                let missing = missing(tag);
+                quote! {
+                    let __tag = match __data.get(#tag) {
+                        ::core::option::Option::Some(::myjson::types::JSONValue::String { string }) => string.as_str(),
+                        ::core::option::Option::Some(value) => return ::core::result::Result::Err(
+                            ::myjson::convert::ConversionError::expected("string", value).at(::myjson::types::PathSegment::Key(#tag.to_string()))
+                        ),
+                        ::core::option::Option::None => return ::core::result::Result::Err(#missing),
+                    };
+                }
+            };
+            let expect_object = expect_object();
+            match tagging {
+                Tagging::External => {
+                    let unit_arms = variants.iter().filter(|(_, _, is_unit)| *is_unit).map(|(name, from_json, _)| quote! { #name => #from_json, });
+                    let content_arms = variants.iter().filter(|(_, _, is_unit)| !*is_unit).map(|(name, from_json, _)| quote! {
+                        #name => {
+                            let __value = __content;
+                            #from_json.map_err(|err| err.at(::myjson::types::PathSegment::Key(#name.to_string())))
+                        }
+                    });
+                    quote! {
+                        match __value {
+                            ::myjson::types::JSONValue::String { string: __tag } => match __tag.as_str() {
+                                #(#unit_arms)*
+                                _ => #unknown_variant,
+                            },
+                            ::myjson::types::JSONValue::Object { data } if data.len() == 1 => {
+                                let (__tag, __content) = data.iter().next().unwrap();
+                                match &**__tag {
+                                    #(#content_arms)*
+                                    _ => #unknown_variant,
+                                }
+                            }
+                            _ => ::core::result::Result::Err(::myjson::convert::ConversionError::expected("enum", __value)),
+                        }
+                    }
+                }
+                Tagging::Internal(tag) => {
+                    let get_tag = get_tag(&tag);
+                    let arms = variants.iter().map(|(name, from_json, _)| quote! { #name => #from_json, });
+                    quote! {
+                        #expect_object
+                        #get_tag
+                        match __tag {
+                            #(#arms)*
+                            _ => #unknown_variant,
+                        }
+                    }
+                }
+                Tagging::Adjacent(tag, content) => {
+                    let get_tag = get_tag(&tag);
+                    let missing = missing(&content);
+                    let arms = variants.iter().map(|(name, from_json, is_unit)| {
+                        if *is_unit {
+                            quote! { #name => #from_json, }
+                        } else {
+                            quote! {
+                                #name => {
+                                    let __value = __data.get(#content).ok_or_else(|| #missing)?;
+                                    #from_json.map_err(|err| err.at(::myjson::types::PathSegment::Key(#content.to_string())))
+                                }
+                            }
+                        }
+                    });
+                    quote! {
+                        #expect_object
+                        #get_tag
+                        match __tag {
+                            #(#arms)*
+                            _ => #unknown_variant,
+                        }
+                    }
+                }
+                Tagging::Untagged => {
+                    let attempts = variants.iter().map(|(_, from_json, is_unit)| {
+                        if *is_unit {
+                            quote! {
+                                if let ::myjson::types::JSONValue::Null = __value {
+                                    return #from_json;
+                                }
+                            }
+                        } else {
+                            quote! {
+                                if let ::core::result::Result::Ok(value) = #from_json {
+                                    return ::core::result::Result::Ok(value);
+                                }
+                            }
+                        }
+                    });
+                    let message = format!("value does not match any variant of {name}");
+                    quote! {
+                        #(#attempts)*
+                        ::core::result::Result::Err(::myjson::convert::ConversionError::new(::myjson::convert::ConversionErrorKind::Custom(#message.to_string())))
+                    }
+                }
+            }
+        }
+        Data::Union(_) => return Err(Error::new_spanned(&input.ident, "unions are not supported")),
+    };
+
+    let generics = add_bounds(&input.generics, quote! { ::myjson::FromJson });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::myjson::FromJson for #name #ty_generics #where_clause {
+            fn from_json(__value: &::myjson::types::JSONValue) -> ::core::result::Result<Self, ::myjson::convert::ConversionError> {
+                #body
+            }
+        }
+    })
+}
+
+#[proc_macro_derive(ToJson, attributes(json))]
+pub fn derive_to_json(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_to_json(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+#[proc_macro_derive(FromJson, attributes(json))]
+pub fn derive_from_json(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_from_json(&input).unwrap_or_else(Error::into_compile_error).into()
+}
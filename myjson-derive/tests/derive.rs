@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+use myjson::parse_bytes;
+use myjson::convert::{ConversionError, ConversionErrorKind, FromJson, ToJson};
+use myjson::types::{JSONValue, PathSegment};
+use myjson_derive::{FromJson, ToJson};
+
+fn json(input: &str) -> JSONValue {
+    parse_bytes(input.as_bytes()).unwrap()
+}
+
+fn assert_round_trip<T: ToJson + FromJson + PartialEq + std::fmt::Debug>(value: T, expected: &str) {
+    assert_eq!(json(expected), value.to_json());
+    assert_eq!(Ok(value), T::from_json(&json(expected)));
+}
+
+fn assert_from_json<T: FromJson + PartialEq + std::fmt::Debug>(expected: T, input: &str) {
+    assert_eq!(Ok(expected), T::from_json(&json(input)));
+}
+
+fn assert_from_json_fail<T: FromJson + std::fmt::Debug>(input: &str, path: Vec<PathSegment>, kind: ConversionErrorKind) {
+    assert_eq!(ConversionError { path, kind }, T::from_json(&json(input)).unwrap_err());
+}
+
+#[derive(ToJson, FromJson, PartialEq, Debug)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(ToJson, FromJson, PartialEq, Debug)]
+struct User {
+    #[json(rename = "userName")]
+    name: String,
+    email: Option<String>,
+    #[json(default)]
+    admin: bool,
+    #[json(default = "default_tags")]
+    tags: Vec<String>,
+    #[json(skip)]
+    session: Option<u32>,
+    #[json(flatten)]
+    position: Point,
+    r#type: u8,
+}
+
+fn default_tags() -> Vec<String> {
+    vec!["new".to_string()]
+}
+
+#[derive(ToJson, FromJson, PartialEq, Debug)]
+struct Meters(f64);
+
+#[derive(ToJson, FromJson, PartialEq, Debug)]
+struct Pair(u8, String);
+
+#[derive(ToJson, FromJson, PartialEq, Debug)]
+struct Unit;
+
+#[derive(ToJson, FromJson, PartialEq, Debug)]
+struct Wrapper<T> {
+    inner: T,
+}
+
+#[derive(ToJson, FromJson, PartialEq, Debug)]
+enum External {
+    Unit,
+    #[json(rename = "new_type")]
+    NewType(u8),
+    Tuple(u8, bool),
+    Struct { a: u8 },
+}
+
+#[derive(ToJson, FromJson, PartialEq, Debug)]
+#[json(tag = "type")]
+enum Internal {
+    Unit,
+    NewType(Point),
+    Struct { a: u8, #[json(skip)] b: u8 },
+}
+
+#[derive(ToJson)]
+#[json(tag = "kind")]
+enum InternalPayloads {
+    Boxed(Box<Point>),
+    Map(BTreeMap<String, u8>),
+}
+
+#[derive(ToJson, FromJson, PartialEq, Debug)]
+#[json(tag = "t", content = "c")]
+enum Adjacent {
+    Unit,
+    NewType(u8),
+    Tuple(u8, bool),
+    Struct { a: u8 },
+}
+
+#[derive(ToJson, FromJson, PartialEq, Debug)]
+#[json(untagged)]
+enum Untagged {
+    Unit,
+    Number(f64),
+    Point(Point),
+    List(Vec<Untagged>),
+}
+
+#[test]
+fn derive_struct() {
+    assert_round_trip(Point { x: 1, y: -2 }, r#"{"x": 1, "y": -2}"#);
+    assert_round_trip(Meters(1.5), "1.5");
+    assert_round_trip(Pair(1, "a".to_string()), r#"[1, "a"]"#);
+    assert_round_trip(Unit, "null");
+    assert_round_trip(Wrapper { inner: vec![true] }, r#"{"inner": [true]}"#);
+    assert_round_trip(BTreeMap::from([("a".to_string(), Point { x: 0, y: 0 })]), r#"{"a": {"x": 0, "y": 0}}"#);
+}
+
+#[test]
+fn derive_field_attributes() {
+    let user = User {
+        name: "a".to_string(),
+        email: None,
+        admin: true,
+        tags: vec![],
+        session: None,
+        position: Point { x: 1, y: 2 },
+        r#type: 3,
+    };
+    assert_round_trip(user, r#"{"userName": "a", "email": null, "admin": true, "tags": [], "x": 1, "y": 2, "type": 3}"#);
+    assert_from_json(User {
+        name: "b".to_string(),
+        email: None,
+        admin: false,
+        tags: vec!["new".to_string()],
+        session: None,
+        position: Point { x: 1, y: 2 },
+        r#type: 0,
+    }, r#"{"userName": "b", "x": 1, "y": 2, "type": 0, "session": 4}"#);
+}
+
+#[test]
+fn derive_struct_errors() {
+    assert_from_json_fail::<Point>(r#"{"x": 1}"#, vec![], ConversionErrorKind::MissingField("y".to_string()));
+    assert_from_json_fail::<Point>(r#"{"x": 1, "y": "2"}"#, vec![PathSegment::Key("y".to_string())], ConversionErrorKind::ExpectedType { expected: "integer", found: "string" });
+    assert_from_json_fail::<Point>("[]", vec![], ConversionErrorKind::ExpectedType { expected: "object", found: "array" });
+    assert_from_json_fail::<Pair>(r#"[1]"#, vec![], ConversionErrorKind::WrongLength { expected: 2, found: 1 });
+    assert_from_json_fail::<Wrapper<Point>>(r#"{"inner": {"x": true, "y": 1}}"#, vec![PathSegment::Key("inner".to_string()), PathSegment::Key("x".to_string())], ConversionErrorKind::ExpectedType { expected: "integer", found: "boolean" });
+}
+
+#[test]
+fn derive_external_enum() {
+    assert_round_trip(External::Unit, r#""Unit""#);
+    assert_round_trip(External::NewType(1), r#"{"new_type": 1}"#);
+    assert_round_trip(External::Tuple(1, true), r#"{"Tuple": [1, true]}"#);
+    assert_round_trip(External::Struct { a: 1 }, r#"{"Struct": {"a": 1}}"#);
+    assert_from_json_fail::<External>(r#""Other""#, vec![], ConversionErrorKind::UnknownVariant("Other".to_string()));
+    assert_from_json_fail::<External>(r#"{"Struct": {}}"#, vec![PathSegment::Key("Struct".to_string())], ConversionErrorKind::MissingField("a".to_string()));
+}
+
+#[test]
+fn derive_internal_enum() {
+    assert_round_trip(Internal::Unit, r#"{"type": "Unit"}"#);
+    assert_round_trip(Internal::NewType(Point { x: 1, y: 2 }), r#"{"type": "NewType", "x": 1, "y": 2}"#);
+    assert_round_trip(Internal::Struct { a: 1, b: 0 }, r#"{"type": "Struct", "a": 1}"#);
+    assert_from_json_fail::<Internal>(r#"{"a": 1}"#, vec![], ConversionErrorKind::MissingField("type".to_string()));
+    assert_from_json_fail::<Internal>(r#"{"type": 1}"#, vec![PathSegment::Key("type".to_string())], ConversionErrorKind::ExpectedType { expected: "string", found: "number" });
+    assert_eq!(json(r#"{"kind": "Boxed", "x": 1, "y": 2}"#), InternalPayloads::Boxed(Box::new(Point { x: 1, y: 2 })).to_json());
+    assert_eq!(json(r#"{"kind": "Map", "a": 1}"#), InternalPayloads::Map(BTreeMap::from([("a".to_string(), 1)])).to_json());
+}
+
+#[test]
+fn derive_adjacent_enum() {
+    assert_round_trip(Adjacent::Unit, r#"{"t": "Unit"}"#);
+    assert_round_trip(Adjacent::NewType(1), r#"{"t": "NewType", "c": 1}"#);
+    assert_round_trip(Adjacent::Tuple(1, false), r#"{"t": "Tuple", "c": [1, false]}"#);
+    assert_round_trip(Adjacent::Struct { a: 1 }, r#"{"t": "Struct", "c": {"a": 1}}"#);
+    assert_from_json_fail::<Adjacent>(r#"{"t": "NewType"}"#, vec![], ConversionErrorKind::MissingField("c".to_string()));
+    assert_from_json_fail::<Adjacent>(r#"{"t": "NewType", "c": "x"}"#, vec![PathSegment::Key("c".to_string())], ConversionErrorKind::ExpectedType { expected: "integer", found: "string" });
+}
+
+#[test]
+fn derive_untagged_enum() {
+    assert_round_trip(Untagged::Unit, "null");
+    assert_round_trip(Untagged::Number(1.0), "1");
+    assert_round_trip(Untagged::Point(Point { x: 1, y: 2 }), r#"{"x": 1, "y": 2}"#);
+    assert_round_trip(Untagged::List(vec![Untagged::Unit, Untagged::Number(2.0)]), "[null, 2]");
+    assert!(Untagged::from_json(&json(r#""a""#)).is_err());
+}
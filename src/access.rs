@@ -0,0 +1,121 @@
+use std::ops::{Index, IndexMut};
+use rustc_hash::FxHashMap;
+use crate::types::{JSONKey, JSONValue};
+
+// Returned by indexing on missing keys and elements so lookups can be chained
+static NULL: JSONValue = JSONValue::Null;
+
+impl JSONValue {
+    pub fn is_null(&self) -> bool {
+        matches!(self, JSONValue::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JSONValue::True => Some(true),
+            JSONValue::False => Some(false),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JSONValue::Number { number } => Some(*number),
+            _ => None,
+        }
+    }
+
+    // Only numbers without a fractional part that fit in an i64 are returned
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            JSONValue::Number { number } if number.fract() == 0.0 && *number >= i64::MIN as f64 && *number < i64::MAX as f64 => Some(*number as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JSONValue::String { string } => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<JSONValue>> {
+        match self {
+            JSONValue::Array { data } => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn as_array_mut(&mut self) -> Option<&mut Vec<JSONValue>> {
+        match self {
+            JSONValue::Array { data } => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&FxHashMap<JSONKey, JSONValue>> {
+        match self {
+            JSONValue::Object { data } => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn as_object_mut(&mut self) -> Option<&mut FxHashMap<JSONKey, JSONValue>> {
+        match self {
+            JSONValue::Object { data } => Some(data),
+            _ => None,
+        }
+    }
+
+    // Looks up a member of an object, returning None for missing keys and non-objects
+    pub fn get(&self, key: &str) -> Option<&JSONValue> {
+        self.as_object()?.get(key)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut JSONValue> {
+        self.as_object_mut()?.get_mut(key)
+    }
+}
+
+impl Index<&str> for JSONValue {
+    type Output = JSONValue;
+
+    fn index(&self, key: &str) -> &JSONValue {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+impl Index<usize> for JSONValue {
+    type Output = JSONValue;
+
+    fn index(&self, index: usize) -> &JSONValue {
+        self.as_array().and_then(|data| data.get(index)).unwrap_or(&NULL)
+    }
+}
+
+// Null is turned into an empty object and missing keys are inserted as null, so nested members
+// can be assigned directly. Indexing any other kind of value panics.
+impl IndexMut<&str> for JSONValue {
+    fn index_mut(&mut self, key: &str) -> &mut JSONValue {
+        if self.is_null() {
+            *self = JSONValue::Object { data: FxHashMap::default() };
+        }
+        match self {
+            JSONValue::Object { data } => data.entry(key.into()).or_insert(JSONValue::Null),
+            _ => panic!("cannot index into non-object JSON value with key {key:?}"),
+        }
+    }
+}
+
+impl IndexMut<usize> for JSONValue {
+    fn index_mut(&mut self, index: usize) -> &mut JSONValue {
+        match self {
+            JSONValue::Array { data } => {
+                let len = data.len();
+                data.get_mut(index).unwrap_or_else(|| panic!("index {index} out of bounds for JSON array of length {len}"))
+            }
+            _ => panic!("cannot index into non-array JSON value with index {index}"),
+        }
+    }
+}
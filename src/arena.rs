@@ -0,0 +1,166 @@
+use crate::byte_parser::{ByteParser, Sink};
+use crate::convert::ToJson;
+use crate::types::{JSONValue, ParseError};
+use crate::types::ParseError::Unknown;
+
+pub use bumpalo::Bump;
+
+// A JSON value whose strings, arrays and objects all live in a `Bump` arena, so a whole document
+// is freed at once when the arena is dropped or reset. Object members keep their document order.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArenaValue<'a> {
+    False,
+    True,
+    Null,
+    Object { data: &'a [(&'a str, ArenaValue<'a>)] },
+    Array { data: &'a [ArenaValue<'a>] },
+    Number { number: f64 },
+    String { string: &'a str },
+}
+
+impl<'a> ArenaValue<'a> {
+    pub fn is_null(&self) -> bool {
+        matches!(self, ArenaValue::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ArenaValue::True => Some(true),
+            ArenaValue::False => Some(false),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ArenaValue::Number { number } => Some(*number),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&'a str> {
+        match self {
+            ArenaValue::String { string } => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&'a [ArenaValue<'a>]> {
+        match self {
+            ArenaValue::Array { data } => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&'a [(&'a str, ArenaValue<'a>)]> {
+        match self {
+            ArenaValue::Object { data } => Some(data),
+            _ => None,
+        }
+    }
+
+    // Finds a member by a linear scan. With duplicate keys the last one wins, as in `parse_bytes`.
+    pub fn get(&self, key: &str) -> Option<&'a ArenaValue<'a>> {
+        self.as_object()?.iter().rev().find(|(member, _)| *member == key).map(|(_, value)| value)
+    }
+}
+
+impl ToJson for ArenaValue<'_> {
+    fn to_json(&self) -> JSONValue {
+        match self {
+            ArenaValue::False => JSONValue::False,
+            ArenaValue::True => JSONValue::True,
+            ArenaValue::Null => JSONValue::Null,
+            ArenaValue::Object { data } => JSONValue::Object { data: data.iter().map(|(key, value)| ((*key).into(), value.to_json())).collect() },
+            ArenaValue::Array { data } => JSONValue::Array { data: data.iter().map(ToJson::to_json).collect() },
+            ArenaValue::Number { number } => JSONValue::Number { number: *number },
+            ArenaValue::String { string } => JSONValue::String { string: (*string).into() },
+        }
+    }
+}
+
+// Elements and members are gathered on shared stacks and copied into the arena once their
+// container is closed, so the arena only ever holds the final slices.
+struct ArenaBuilder<'a> {
+    arena: &'a Bump,
+    string: Vec<u8>,
+    elements: Vec<ArenaValue<'a>>,
+    members: Vec<(&'a str, ArenaValue<'a>)>,
+}
+
+impl<'a> ArenaBuilder<'a> {
+    fn parse_string(&mut self, parser: &mut ByteParser) -> Result<&'a str, ParseError> {
+        self.string.clear();
+        parser.parse_string_bytes(&mut self.string)?;
+        let string = std::str::from_utf8(&self.string).or(Err(Unknown))?;
+        Ok(self.arena.alloc_str(string))
+    }
+}
+
+impl<'a> Sink for ArenaBuilder<'a> {
+    type Value = ArenaValue<'a>;
+    type Key = &'a str;
+
+    fn null(&mut self) -> ArenaValue<'a> {
+        ArenaValue::Null
+    }
+
+    fn boolean(&mut self, value: bool) -> ArenaValue<'a> {
+        if value { ArenaValue::True } else { ArenaValue::False }
+    }
+
+    fn number(&mut self, number: f64) -> ArenaValue<'a> {
+        ArenaValue::Number { number }
+    }
+
+    fn string(&mut self, parser: &mut ByteParser) -> Result<ArenaValue<'a>, ParseError> {
+        Ok(ArenaValue::String { string: self.parse_string(parser)? })
+    }
+
+    fn key(&mut self, parser: &mut ByteParser) -> Result<&'a str, ParseError> {
+        self.parse_string(parser)
+    }
+
+    fn begin_array(&mut self) -> usize {
+        self.elements.len()
+    }
+
+    fn element(&mut self, value: ArenaValue<'a>) {
+        self.elements.push(value);
+    }
+
+    fn end_array(&mut self, start: usize) -> ArenaValue<'a> {
+        let data = self.arena.alloc_slice_copy(&self.elements[start..]);
+        self.elements.truncate(start);
+        ArenaValue::Array { data }
+    }
+
+    fn begin_object(&mut self) -> usize {
+        self.members.len()
+    }
+
+    fn member(&mut self, key: &'a str, value: ArenaValue<'a>) {
+        self.members.push((key, value));
+    }
+
+    fn end_object(&mut self, start: usize) -> ArenaValue<'a> {
+        let data = self.arena.alloc_slice_copy(&self.members[start..]);
+        self.members.truncate(start);
+        ArenaValue::Object { data }
+    }
+}
+
+pub fn parse_bytes_in<'a>(arena: &'a Bump, bytes: &[u8]) -> Result<ArenaValue<'a>, ParseError> {
+    let mut parser = ByteParser::with_structural_index(bytes);
+    let mut builder = ArenaBuilder {
+        arena,
+        string: Vec::new(),
+        elements: Vec::new(),
+        members: Vec::new(),
+    };
+    let value = parser.walk(&mut builder)?;
+    if parser.head < bytes.len() {
+        return Err(Unknown)
+    }
+    Ok(value)
+}
@@ -1,19 +1,136 @@
+use std::str::FromStr;
 use rustc_hash::FxHashMap;
-use crate::types::{JSONValue, ParseError};
+use crate::json5;
+use crate::types::{Dialect, JSONKey, JSONValue, ParseError};
 use crate::types::ParseError::Unknown;
+use crate::structural::{structural_index, Classifier};
+#[cfg(feature = "interning")]
+use crate::intern::KeyInterner;
+use crate::small_string::SmallString;
 
-struct ByteParser<'a> {
-    data: &'a [u8],
-    head: usize
+const ONES: u64 = 0x0101_0101_0101_0101;
+const HIGHS: u64 = 0x8080_8080_8080_8080;
+
+// Sets the high bit of the first byte of the word below `limit`. Bytes after it may also be
+// marked, so only the lowest marked byte is meaningful.
+fn bytes_below(word: u64, limit: u8) -> u64 {
+    word.wrapping_sub(ONES * limit as u64) & !word & HIGHS
+}
+
+// Finds the first quote, backslash or control character, checking eight bytes at a time
+fn find_string_special(data: &[u8]) -> Option<usize> {
+    let mut chunks = data.chunks_exact(8);
+    for (i, chunk) in chunks.by_ref().enumerate() {
+        let word = u64::from_le_bytes(chunk.try_into().unwrap());
+        let mask = bytes_below(word ^ (ONES * 0x22), 1) | bytes_below(word ^ (ONES * 0x5c), 1) | bytes_below(word, 0x20);
+        if mask != 0 {
+            return Some(i * 8 + (mask.trailing_zeros() / 8) as usize)
+        }
+    }
+    let offset = data.len() - chunks.remainder().len();
+    chunks.remainder()
+        .iter()
+        .position(|&byte| byte < 0x20 || byte == 0x22 || byte == 0x5c)
+        .map(|position| offset + position)
+}
+
+// Receives the values found by `ByteParser::walk`. `begin_array` and `begin_object` return a mark
+// that is handed back to the matching `end_*` call once every element or member has been passed on.
+pub(crate) trait Sink {
+    type Value;
+    type Key;
+
+    fn null(&mut self) -> Self::Value;
+    fn boolean(&mut self, value: bool) -> Self::Value;
+    fn number(&mut self, number: f64) -> Self::Value;
+    // Strings and keys are read by the sink, with `head` just past the opening quote
+    fn string(&mut self, parser: &mut ByteParser) -> Result<Self::Value, ParseError>;
+    fn key(&mut self, parser: &mut ByteParser) -> Result<Self::Key, ParseError>;
+    fn begin_array(&mut self) -> usize;
+    fn element(&mut self, value: Self::Value);
+    fn end_array(&mut self, mark: usize) -> Self::Value;
+    fn begin_object(&mut self) -> usize;
+    fn member(&mut self, key: Self::Key, value: Self::Value);
+    fn end_object(&mut self, mark: usize) -> Self::Value;
+}
+
+pub(crate) struct ByteParser<'a> {
+    pub(crate) data: &'a [u8],
+    pub(crate) head: usize,
+    structurals: Option<Vec<u32>>, // Positions found by the structural indexer, if it was run
+    cursor: usize, // The first entry of `structurals` that may still be at or after `head`
+    #[cfg(feature = "interning")]
+    interner: Option<&'a mut KeyInterner>,
+    dialect: Dialect,
+    unterminated_comment: Option<usize>, // Where a block comment that runs to the end of the input starts
+    string: Vec<u8>, // Reused while parsing strings and object keys
 }
 
 impl <'a> ByteParser<'a> {
-    fn new(data: &'a [u8]) -> Self {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
         ByteParser {
             data,
-            head: 0
+            head: 0,
+            structurals: None,
+            cursor: 0,
+            #[cfg(feature = "interning")]
+            interner: None,
+            dialect: Dialect::Json,
+            unterminated_comment: None,
+            string: Vec::new(),
         }
     }
+
+    pub(crate) fn with_structural_index(data: &'a [u8]) -> Self {
+        // Positions are stored as u32 to halve the size of the index
+        if data.len() > u32::MAX as usize {
+            return Self::new(data);
+        }
+        let structurals = structural_index(data, Classifier::detect());
+        ByteParser {
+            data,
+            head: 0,
+            structurals: Some(structurals),
+            cursor: 0,
+            #[cfg(feature = "interning")]
+            interner: None,
+            dialect: Dialect::Json,
+            unterminated_comment: None,
+            string: Vec::new(),
+        }
+    }
+
+    // The structural index only understands strict JSON, so other dialects are parsed without it
+    pub(crate) fn with_dialect(data: &'a [u8], dialect: Dialect) -> Self {
+        if dialect == Dialect::Json {
+            return Self::with_structural_index(data)
+        }
+        ByteParser { dialect, ..Self::new(data) }
+    }
+
+    // The first indexed position at or after `head`
+    fn next_structural(&mut self) -> Option<usize> {
+        let structurals = self.structurals.as_ref()?;
+        while self.cursor < structurals.len() && (structurals[self.cursor] as usize) < self.head {
+            self.cursor += 1;
+        }
+        structurals.get(self.cursor).map(|&position| position as usize)
+    }
+
+    // Copies a string without escapes in one go when the index knows where it ends
+    fn parse_plain_string(&mut self, bytes: &mut Vec<u8>) -> bool {
+        let Some(end) = self.next_structural() else {
+            return false
+        };
+        let body = &self.data[self.head..end];
+        if self.data[end] != 0x22 || find_string_special(body).is_some() {
+            return false
+        }
+        bytes.extend_from_slice(body);
+        self.head = end + 1;
+        self.consume_whitespace();
+        true
+    }
     
     fn parse_hex(&mut self) -> Result<u8, ParseError> {
         if self.head >= self.data.len() {
@@ -37,7 +154,7 @@ impl <'a> ByteParser<'a> {
         Ok(h1 << 12 | h2 << 8 | h3 << 4 | h4)
     }
     
-    fn assert_next_byte(&mut self, expected: u8) -> Result<(), ParseError> {
+    pub(crate) fn assert_next_byte(&mut self, expected: u8) -> Result<(), ParseError> {
         if Some(expected) == self.data.get(self.head).copied() {
             self.head += 1;
             Ok(())
@@ -46,15 +163,51 @@ impl <'a> ByteParser<'a> {
         }
     }
     
-    fn parse_string(&mut self) -> Result<String, ParseError> {
+    // Strings are unescaped into a reused buffer so that short ones never allocate and keys can be
+    // looked up in the interner before any allocation is made
+    fn parse_buffered<T>(&mut self, finish: impl FnOnce(&mut Self, &str) -> T) -> Result<T, ParseError> {
+        let mut bytes = std::mem::take(&mut self.string);
+        bytes.clear();
+        self.parse_string_bytes(&mut bytes)?;
+        let string = std::str::from_utf8(&bytes).or(Err(Unknown))?;
+        let res = finish(self, string);
+        self.string = bytes;
+        Ok(res)
+    }
+
+    fn parse_small_string(&mut self) -> Result<SmallString, ParseError> {
+        self.parse_buffered(|_, string| SmallString::from(string))
+    }
+
+    fn make_key(&mut self, key: &str) -> JSONKey {
+        #[cfg(feature = "interning")]
+        if let Some(interner) = &mut self.interner {
+            return interner.intern(key)
+        }
+        JSONKey::from(key)
+    }
+
+    pub(crate) fn parse_string(&mut self) -> Result<String, ParseError> {
         let mut bytes = Vec::new();
-        while self.head < self.data.len() {
+        self.parse_string_bytes(&mut bytes)?;
+        String::from_utf8(bytes).or(Err(Unknown))
+    }
+
+    // Appends the unescaped string to `bytes` without checking that it is valid UTF-8
+    pub(crate) fn parse_string_bytes(&mut self, bytes: &mut Vec<u8>) -> Result<(), ParseError> {
+        if self.parse_plain_string(bytes) {
+            return Ok(())
+        }
+        loop {
+            // Everything up to the next quote, backslash or control character is copied as is
+            let run = find_string_special(&self.data[self.head..]).ok_or(Unknown)?;
+            bytes.extend_from_slice(&self.data[self.head..self.head + run]);
+            self.head += run;
             match self.data[self.head] {
-                0x00..=0x1f => return Err(Unknown),
                 0x22 => {
                     self.head += 1;
                     self.consume_whitespace();
-                    return String::from_utf8(bytes).or(Err(Unknown))
+                    return Ok(())
                 },
                 0x5c => {
                     self.head += 1;
@@ -71,35 +224,168 @@ impl <'a> ByteParser<'a> {
                         0x6e => bytes.push(0x0a), // \n
                         0x72 => bytes.push(0x0d), // \r
                         0x74 => bytes.push(0x09), // \t
-                        0x75 => { // \uXXXX
-                            let c1 = self.parse_u16()?;
-                            let encoded = match c1 {
-                                0xd800..=0xdfff => {
-                                    self.assert_next_byte(0x5c)?; // \
-                                    self.assert_next_byte(0x75)?; // u
-                                    let c2 = self.parse_u16()?;
-                                    String::from_utf16(&[c1, c2]).or(Err(Unknown))?
-                                }
-                                _ => {
-                                    String::from_utf16(&[c1]).unwrap()
-                                }
-                            };
-                            for byte in encoded.bytes() {
-                                bytes.push(byte)
-                            }
+                        0x75 => self.parse_unicode_escape(bytes)?, // \uXXXX
+                        _ => return Err(Unknown)
+                    }
+                }
+                _ => return Err(Unknown), // Control characters must be escaped
+            }
+        }
+    }
+    
+    fn parse_unicode_escape(&mut self, bytes: &mut Vec<u8>) -> Result<(), ParseError> {
+        let c1 = self.parse_u16()?;
+        let encoded = match c1 {
+            0xd800..=0xdfff => {
+                self.assert_next_byte(0x5c)?; // \
+                self.assert_next_byte(0x75)?; // u
+                let c2 = self.parse_u16()?;
+                String::from_utf16(&[c1, c2]).or(Err(Unknown))?
+            }
+            _ => {
+                String::from_utf16(&[c1]).unwrap()
+            }
+        };
+        bytes.extend_from_slice(encoded.as_bytes());
+        Ok(())
+    }
+
+    // Decodes the character at `head` without consuming it
+    fn peek_char(&self) -> Option<char> {
+        let first = *self.data.get(self.head)?;
+        let len = match first {
+            0x00..=0x7f => return Some(first as char),
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            _ => 4,
+        };
+        let bytes = self.data.get(self.head..self.head + len)?;
+        std::str::from_utf8(bytes).ok()?.chars().next()
+    }
+
+    fn parse_json5_string_bytes(&mut self, bytes: &mut Vec<u8>) -> Result<(), ParseError> {
+        let quote = self.data[self.head];
+        self.head += 1;
+        loop {
+            let byte = *self.data.get(self.head).ok_or(Unknown)?;
+            self.head += 1;
+            match byte {
+                _ if byte == quote => {
+                    self.consume_whitespace();
+                    return Ok(())
+                }
+                0x5c => {
+                    let escaped = self.peek_char().ok_or(Unknown)?;
+                    self.head += escaped.len_utf8();
+                    if let Some(char) = json5::escape(escaped) {
+                        bytes.extend_from_slice(char.encode_utf8(&mut [0; 4]).as_bytes());
+                        continue
+                    }
+                    match escaped {
+                        'u' => self.parse_unicode_escape(bytes)?,
+                        'x' => {
+                            let char = (self.parse_hex()? << 4 | self.parse_hex()?) as char;
+                            bytes.extend_from_slice(char.encode_utf8(&mut [0; 4]).as_bytes());
                         }
+                        // `\0` must not be followed by a digit, as it would read as an octal escape
+                        '0' if !self.data.get(self.head).is_some_and(u8::is_ascii_digit) => bytes.push(0),
+                        // An escaped line break continues the string on the next line
+                        '\r' => if self.data.get(self.head) == Some(&0x0a) {
+                            self.head += 1;
+                        },
+                        '\n' | '\u{2028}' | '\u{2029}' => {}
                         _ => return Err(Unknown)
                     }
                 }
-                _ => {
-                    bytes.push(self.data[self.head]);
-                    self.head += 1;
-                },
+                0x0a | 0x0d => return Err(Unknown),
+                _ => bytes.push(byte)
             }
+        }
+    }
+
+    fn parse_json5_string(&mut self) -> Result<String, ParseError> {
+        let mut bytes = Vec::new();
+        self.parse_json5_string_bytes(&mut bytes)?;
+        String::from_utf8(bytes).or(Err(Unknown))
+    }
+
+    fn parse_json5_number(&mut self) -> Result<f64, ParseError> {
+        let start = self.head;
+        while self.head < self.data.len() && (self.data[self.head].is_ascii_alphanumeric() || matches!(self.data[self.head], 0x2b | 0x2d | 0x2e)) {
+            self.head += 1;
+        }
+        // Only ASCII bytes were taken
+        json5::parse_number(std::str::from_utf8(&self.data[start..self.head]).unwrap())
+    }
+
+    fn parse_identifier_char(&mut self) -> Result<Option<char>, ParseError> {
+        let Some(char) = self.peek_char() else {
+            return Ok(None)
         };
-        Err(Unknown)
+        if char != '\\' {
+            self.head += char.len_utf8();
+            return Ok(Some(char))
+        }
+        self.head += 1;
+        self.assert_next_byte(0x75)?; // u
+        Ok(Some(char::from_u32(self.parse_u16()? as u32).ok_or(Unknown)?))
     }
-    
+
+    fn parse_identifier(&mut self) -> Result<String, ParseError> {
+        let char = self.parse_identifier_char()?.ok_or(Unknown)?;
+        if !json5::is_identifier_start(char) {
+            return Err(Unknown)
+        }
+        let mut identifier = String::from(char);
+        while let Some(char) = self.peek_char() {
+            if char != '\\' && !json5::is_identifier_part(char) {
+                break
+            }
+            match self.parse_identifier_char()? {
+                Some(char) if json5::is_identifier_part(char) => identifier.push(char),
+                _ => return Err(Unknown)
+            }
+        }
+        self.consume_whitespace();
+        Ok(identifier)
+    }
+
+    pub(crate) fn parse_key(&mut self) -> Result<JSONKey, ParseError> {
+        if self.dialect == Dialect::Json5 {
+            return self.parse_json5_key()
+        }
+        self.assert_next_byte(0x22)?; // "
+        self.parse_buffered(Self::make_key)
+    }
+
+    fn parse_json5_key(&mut self) -> Result<JSONKey, ParseError> {
+        let key = match self.data.get(self.head).ok_or(Unknown)? {
+            0x22 | 0x27 => self.parse_json5_string()?,
+            _ => self.parse_identifier()?,
+        };
+        #[cfg(feature = "interning")]
+        if let Some(interner) = &mut self.interner {
+            return Ok(interner.intern(&key))
+        }
+        Ok(JSONKey::from(key))
+    }
+
+    // Strings, numbers and unquoted literals, starting at `head`
+    fn parse_json5_scalar(&mut self) -> Result<JSONValue, ParseError> {
+        match self.data[self.head] {
+            0x22 | 0x27 => Ok(JSONValue::String { string: self.parse_json5_string()?.into() }),
+            0x2b | 0x2d | 0x2e | 0x30..=0x39 => Ok(JSONValue::Number { number: self.parse_json5_number()? }),
+            _ => match self.parse_identifier()?.as_str() {
+                "true" => Ok(JSONValue::True),
+                "false" => Ok(JSONValue::False),
+                "null" => Ok(JSONValue::Null),
+                "Infinity" => Ok(JSONValue::Number { number: f64::INFINITY }),
+                "NaN" => Ok(JSONValue::Number { number: f64::NAN }),
+                _ => Err(Unknown)
+            }
+        }
+    }
+
     fn parse_int(&mut self) -> Result<(u64, i32), ParseError> {
         if self.head >= self.data.len() {
             return Err(Unknown)
@@ -214,7 +500,7 @@ impl <'a> ByteParser<'a> {
         Ok(sign * exp)
     }
     
-    fn parse_number(&mut self) -> Result<f64, ParseError> {
+    pub(crate) fn parse_number(&mut self) -> Result<f64, ParseError> {
         let sign = if self.data[self.head - 1] == 0x2d {
             -1.0
         } else {
@@ -248,7 +534,7 @@ impl <'a> ByteParser<'a> {
         }
     }
     
-    fn parse_true(&mut self) -> Result<(), ParseError> {
+    pub(crate) fn parse_true(&mut self) -> Result<(), ParseError> {
         self.assert_next_byte(0x72)?;
         self.assert_next_byte(0x75)?;
         self.assert_next_byte(0x65)?;
@@ -256,7 +542,7 @@ impl <'a> ByteParser<'a> {
         Ok(())
     }
     
-    fn parse_false(&mut self) -> Result<(), ParseError> {
+    pub(crate) fn parse_false(&mut self) -> Result<(), ParseError> {
         self.assert_next_byte(0x61)?;
         self.assert_next_byte(0x6c)?;
         self.assert_next_byte(0x73)?;
@@ -265,7 +551,7 @@ impl <'a> ByteParser<'a> {
         Ok(())
     }
     
-    fn parse_null(&mut self) -> Result<(), ParseError> {
+    pub(crate) fn parse_null(&mut self) -> Result<(), ParseError> {
         self.assert_next_byte(0x75)?;
         self.assert_next_byte(0x6c)?;
         self.assert_next_byte(0x6c)?;
@@ -293,6 +579,11 @@ impl <'a> ByteParser<'a> {
                 0x2c => {
                     self.head += 1;
                     self.consume_whitespace();
+                    if self.dialect.allows_trailing_commas() && self.data.get(self.head) == Some(&0x5d) {
+                        self.head += 1;
+                        self.consume_whitespace();
+                        return Ok(data)
+                    }
                 }
                 _ => {
                     return Err(Unknown)
@@ -302,7 +593,7 @@ impl <'a> ByteParser<'a> {
         Err(Unknown)
     }
     
-    fn parse_object(&mut self) -> Result<FxHashMap<String, JSONValue>, ParseError> {
+    fn parse_object(&mut self) -> Result<FxHashMap<JSONKey, JSONValue>, ParseError> {
         let mut data = FxHashMap::default();
         self.consume_whitespace();
         if self.data.get(self.head).copied() == Some(0x7d) {
@@ -311,8 +602,7 @@ impl <'a> ByteParser<'a> {
             return Ok(data);
         }
         while self.head < self.data.len() {
-            self.assert_next_byte(0x22)?; // "
-            let key = self.parse_string()?;
+            let key = self.parse_key()?;
             self.assert_next_byte(0x3a)?; // :
             self.consume_whitespace();
             let val = self.parse()?;
@@ -326,6 +616,11 @@ impl <'a> ByteParser<'a> {
                 0x2c => {
                     self.head += 1;
                     self.consume_whitespace();
+                    if self.dialect.allows_trailing_commas() && self.data.get(self.head) == Some(&0x7d) {
+                        self.head += 1;
+                        self.consume_whitespace();
+                        return Ok(data)
+                    }
                 }
                 _ => {
                     return Err(Unknown)
@@ -335,7 +630,102 @@ impl <'a> ByteParser<'a> {
         Err(Unknown)
     }
     
-    fn consume_whitespace(&mut self) {
+    // Walks a strict JSON value and hands everything in it to `sink`. Unlike `parse` it knows no
+    // other dialects, which keeps it small enough for the arena and tape parsers to share.
+    pub(crate) fn walk<S: Sink>(&mut self, sink: &mut S) -> Result<S::Value, ParseError> {
+        self.consume_whitespace();
+        if self.head >= self.data.len() {
+            return Err(Unknown)
+        }
+        self.head += 1;
+        let value = match self.data[self.head - 1] {
+            0x5b => self.walk_array(sink)?,
+            0x7b => self.walk_object(sink)?,
+            0x66 => {
+                self.parse_false()?;
+                sink.boolean(false)
+            }
+            0x6e => {
+                self.parse_null()?;
+                sink.null()
+            }
+            0x74 => {
+                self.parse_true()?;
+                sink.boolean(true)
+            }
+            0x22 => sink.string(self)?,
+            0x2d | 0x30..=0x39 => {
+                let number = self.parse_number()?;
+                sink.number(number)
+            }
+            _ => return Err(Unknown)
+        };
+        self.consume_whitespace();
+        Ok(value)
+    }
+
+    fn walk_array<S: Sink>(&mut self, sink: &mut S) -> Result<S::Value, ParseError> {
+        let mark = sink.begin_array();
+        self.consume_whitespace();
+        if self.data.get(self.head).copied() == Some(0x5d) {
+            self.head += 1;
+        } else {
+            loop {
+                let value = self.walk(sink)?;
+                sink.element(value);
+                if self.end_of_list(0x5d)? {
+                    break
+                }
+            }
+        }
+        Ok(sink.end_array(mark))
+    }
+
+    fn walk_object<S: Sink>(&mut self, sink: &mut S) -> Result<S::Value, ParseError> {
+        let mark = sink.begin_object();
+        self.consume_whitespace();
+        if self.data.get(self.head).copied() == Some(0x7d) {
+            self.head += 1;
+        } else {
+            loop {
+                self.consume_whitespace();
+                self.assert_next_byte(0x22)?; // "
+                let key = sink.key(self)?;
+                self.assert_next_byte(0x3a)?; // :
+                let value = self.walk(sink)?;
+                sink.member(key, value);
+                if self.end_of_list(0x7d)? {
+                    break
+                }
+            }
+        }
+        Ok(sink.end_object(mark))
+    }
+
+    // Consumes the comma or the closing byte after an element or member, true if it was the latter
+    fn end_of_list(&mut self, close: u8) -> Result<bool, ParseError> {
+        let byte = *self.data.get(self.head).ok_or(Unknown)?;
+        self.head += 1;
+        match byte {
+            _ if byte == close => Ok(true),
+            0x2c => Ok(false),
+            _ => Err(Unknown)
+        }
+    }
+
+    pub(crate) fn consume_whitespace(&mut self) {
+        if self.dialect.allows_comments() {
+            return self.consume_trivia()
+        }
+        match self.data.get(self.head) {
+            Some(0x20 | 0x09 | 0x0a | 0x0d) => {}
+            _ => return
+        }
+        // After whitespace the next token always starts at an indexed position
+        if self.structurals.is_some() {
+            self.head = self.next_structural().unwrap_or(self.data.len());
+            return
+        }
         while self.head < self.data.len() {
             match self.data[self.head] {
                 0x20 | 0x09 | 0x0a | 0x0d => self.head += 1,
@@ -344,7 +734,40 @@ impl <'a> ByteParser<'a> {
         }
     }
     
-    fn parse(&mut self) -> Result<JSONValue, ParseError> {
+    pub(crate) fn unterminated_comment(&self) -> Option<usize> {
+        self.unterminated_comment
+    }
+
+    // Skips whitespace and comments. A block comment that is never closed consumes the rest of the
+    // input and is reported once parsing is done, since it is the cause of any other error.
+    #[inline(never)]
+    fn consume_trivia(&mut self) {
+        while let Some(char) = self.peek_char() {
+            match char {
+                ' ' | '\t' | '\n' | '\r' => self.head += 1,
+                '/' => match self.data.get(self.head + 1) {
+                    Some(0x2f) => {
+                        self.head += 2;
+                        while let Some(char) = self.peek_char() && !json5::is_line_terminator(char) {
+                            self.head += char.len_utf8();
+                        }
+                    }
+                    Some(0x2a) => match self.data[self.head + 2..].windows(2).position(|window| window == b"*/") {
+                        Some(end) => self.head += end + 4,
+                        None => {
+                            self.unterminated_comment = Some(self.head);
+                            self.head = self.data.len();
+                        }
+                    },
+                    _ => return
+                },
+                _ if self.dialect == Dialect::Json5 && json5::is_whitespace(char) => self.head += char.len_utf8(),
+                _ => return
+            }
+        }
+    }
+
+    pub(crate) fn parse(&mut self) -> Result<JSONValue, ParseError> {
         self.consume_whitespace();
         if self.head >= self.data.len() {
             return Err(Unknown)
@@ -359,6 +782,10 @@ impl <'a> ByteParser<'a> {
                 let data = self.parse_object()?;
                 Ok(JSONValue::Object { data })
             }
+            _ if self.dialect == Dialect::Json5 => {
+                self.head -= 1;
+                self.parse_json5_scalar()
+            }
             0x66 => {
                 self.parse_false()?;
                 Ok(JSONValue::False)
@@ -372,7 +799,7 @@ impl <'a> ByteParser<'a> {
                 Ok(JSONValue::True)
             }
             0x22 => {
-                let string = self.parse_string()?;
+                let string = self.parse_small_string()?;
                 Ok(JSONValue::String { string })
             }
             0x2d | 0x30..=0x39 => {
@@ -387,12 +814,47 @@ impl <'a> ByteParser<'a> {
 }
 
 pub fn parse_bytes(bytes: &[u8]) -> Result<JSONValue, ParseError> {
-    let mut parser = ByteParser::new(bytes);
+    finish_parse(ByteParser::with_structural_index(bytes))
+}
+
+// Like parse_bytes, but object keys are looked up in `interner` so that repeated keys share storage
+#[cfg(feature = "interning")]
+pub fn parse_bytes_interned(bytes: &[u8], interner: &mut KeyInterner) -> Result<JSONValue, ParseError> {
+    let mut parser = ByteParser::with_structural_index(bytes);
+    parser.interner = Some(interner);
+    finish_parse(parser)
+}
+
+pub fn parse_bytes_with_dialect(bytes: &[u8], dialect: Dialect) -> Result<JSONValue, ParseError> {
+    finish_parse(ByteParser::with_dialect(bytes, dialect))
+}
+
+fn finish_parse(mut parser: ByteParser) -> Result<JSONValue, ParseError> {
     parser.consume_whitespace();
-    let res = parser.parse()?;
+    let res = parser.parse();
+    if let Some(offset) = parser.unterminated_comment {
+        return Err(ParseError::UnterminatedComment { offset })
+    }
+    let res = res?;
     if parser.head < parser.data.len() {
         Err(Unknown)
     } else {
         Ok(res)
     }
 }
+
+impl FromStr for JSONValue {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_bytes(s.as_bytes())
+    }
+}
+
+impl TryFrom<&[u8]> for JSONValue {
+    type Error = ParseError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        parse_bytes(bytes)
+    }
+}
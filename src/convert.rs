@@ -0,0 +1,465 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use crate::diff::format_path;
+use crate::types::{JSONKey, JSONValue, PathSegment};
+
+pub trait ToJson {
+    fn to_json(&self) -> JSONValue;
+}
+
+// Types that always convert to a JSON object, given as its members. Newtype variants of internally
+// tagged enums need one, since the tag is added to the members of their payload.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not always convert to a JSON object",
+    label = "internally tagged newtype variants need a struct with named fields deriving `ToJson`, or a map",
+)]
+pub trait ToJsonObject: ToJson {
+    fn to_json_members(&self) -> Vec<(JSONKey, JSONValue)>;
+}
+
+pub trait FromJson: Sized {
+    fn from_json(value: &JSONValue) -> Result<Self, ConversionError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConversionErrorKind {
+    ExpectedType { expected: &'static str, found: &'static str },
+    OutOfRange, // A number does not fit in the target type
+    WrongLength { expected: usize, found: usize },
+    MissingField(String),
+    UnknownVariant(String),
+    Custom(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionError {
+    pub path: Vec<PathSegment>, // The location of the mismatch within the converted value
+    pub kind: ConversionErrorKind,
+}
+
+impl ConversionError {
+    pub fn new(kind: ConversionErrorKind) -> Self {
+        ConversionError { path: Vec::new(), kind }
+    }
+
+    pub fn expected(expected: &'static str, found: &JSONValue) -> Self {
+        Self::new(ConversionErrorKind::ExpectedType { expected, found: type_name(found) })
+    }
+
+    // Records that the error occurred within the given member or element
+    pub fn at(mut self, segment: PathSegment) -> Self {
+        self.path.insert(0, segment);
+        self
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "at {}: ", format_path(&self.path))?;
+        match &self.kind {
+            ConversionErrorKind::ExpectedType { expected, found } => write!(f, "expected {expected}, found {found}"),
+            ConversionErrorKind::OutOfRange => write!(f, "number out of range"),
+            ConversionErrorKind::WrongLength { expected, found } => write!(f, "expected {expected} elements, found {found}"),
+            ConversionErrorKind::MissingField(field) => write!(f, "missing field {field:?}"),
+            ConversionErrorKind::UnknownVariant(variant) => write!(f, "unknown variant {variant:?}"),
+            ConversionErrorKind::Custom(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+pub(crate) fn type_name(value: &JSONValue) -> &'static str {
+    match value {
+        JSONValue::False | JSONValue::True => "boolean",
+        JSONValue::Null => "null",
+        JSONValue::Object { .. } => "object",
+        JSONValue::Array { .. } => "array",
+        JSONValue::Number { .. } => "number",
+        JSONValue::String { .. } => "string",
+    }
+}
+
+impl ToJson for JSONValue {
+    fn to_json(&self) -> JSONValue {
+        self.clone()
+    }
+}
+
+impl FromJson for JSONValue {
+    fn from_json(value: &JSONValue) -> Result<Self, ConversionError> {
+        Ok(value.clone())
+    }
+}
+
+impl <T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> JSONValue {
+        (**self).to_json()
+    }
+}
+
+impl <T: ToJson + ?Sized> ToJson for Box<T> {
+    fn to_json(&self) -> JSONValue {
+        (**self).to_json()
+    }
+}
+
+impl <T: ToJsonObject + ?Sized> ToJsonObject for &T {
+    fn to_json_members(&self) -> Vec<(JSONKey, JSONValue)> {
+        (**self).to_json_members()
+    }
+}
+
+impl <T: ToJsonObject + ?Sized> ToJsonObject for Box<T> {
+    fn to_json_members(&self) -> Vec<(JSONKey, JSONValue)> {
+        (**self).to_json_members()
+    }
+}
+
+impl <T: FromJson> FromJson for Box<T> {
+    fn from_json(value: &JSONValue) -> Result<Self, ConversionError> {
+        T::from_json(value).map(Box::new)
+    }
+}
+
+impl ToJson for () {
+    fn to_json(&self) -> JSONValue {
+        JSONValue::Null
+    }
+}
+
+impl FromJson for () {
+    fn from_json(value: &JSONValue) -> Result<Self, ConversionError> {
+        match value {
+            JSONValue::Null => Ok(()),
+            _ => Err(ConversionError::expected("null", value))
+        }
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> JSONValue {
+        if *self { JSONValue::True } else { JSONValue::False }
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: &JSONValue) -> Result<Self, ConversionError> {
+        match value {
+            JSONValue::True => Ok(true),
+            JSONValue::False => Ok(false),
+            _ => Err(ConversionError::expected("boolean", value))
+        }
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> JSONValue {
+        JSONValue::String { string: self.into() }
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> JSONValue {
+        JSONValue::String { string: self.into() }
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: &JSONValue) -> Result<Self, ConversionError> {
+        match value {
+            JSONValue::String { string } => Ok(string.to_string()),
+            _ => Err(ConversionError::expected("string", value))
+        }
+    }
+}
+
+impl ToJson for char {
+    fn to_json(&self) -> JSONValue {
+        JSONValue::String { string: (&*self.encode_utf8(&mut [0; 4])).into() }
+    }
+}
+
+impl FromJson for char {
+    fn from_json(value: &JSONValue) -> Result<Self, ConversionError> {
+        let JSONValue::String { string } = value else {
+            return Err(ConversionError::expected("character", value))
+        };
+        let mut chars = string.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(ConversionError::expected("character", value))
+        }
+    }
+}
+
+// JSON cannot represent NaN or the infinities so they are converted to null
+impl ToJson for f64 {
+    fn to_json(&self) -> JSONValue {
+        if self.is_finite() {
+            JSONValue::Number { number: *self }
+        } else {
+            JSONValue::Null
+        }
+    }
+}
+
+impl FromJson for f64 {
+    fn from_json(value: &JSONValue) -> Result<Self, ConversionError> {
+        match value {
+            JSONValue::Number { number } => Ok(*number),
+            _ => Err(ConversionError::expected("number", value))
+        }
+    }
+}
+
+impl ToJson for f32 {
+    fn to_json(&self) -> JSONValue {
+        (*self as f64).to_json()
+    }
+}
+
+impl FromJson for f32 {
+    fn from_json(value: &JSONValue) -> Result<Self, ConversionError> {
+        let number = f64::from_json(value)?;
+        // Finite numbers beyond the range of f32 would silently become infinities
+        if number.is_finite() && !(number as f32).is_finite() {
+            return Err(ConversionError::new(ConversionErrorKind::OutOfRange))
+        }
+        Ok(number as f32)
+    }
+}
+
+// JSON numbers are f64s, so integers beyond 2^53 in magnitude are rounded to the nearest f64 by
+// `to_json`. The 64 bit maxima round up to 2^63 and 2^64, which `from_json` rejects as out of range.
+macro_rules! impl_integer {
+    ($($int:ty),*) => {
+        $(
+            impl ToJson for $int {
+                fn to_json(&self) -> JSONValue {
+                    JSONValue::Number { number: *self as f64 }
+                }
+            }
+
+            impl FromJson for $int {
+                fn from_json(value: &JSONValue) -> Result<Self, ConversionError> {
+                    match value {
+                        JSONValue::Number { number } if number.fract() == 0.0 => {
+                            // `MAX as f64` rounds up to a power of two for the 64 bit types, so the
+                            // upper bound is exclusive
+                            if *number >= <$int>::MIN as f64 && *number < <$int>::MAX as f64 + 1.0 {
+                                Ok(*number as $int)
+                            } else {
+                                Err(ConversionError::new(ConversionErrorKind::OutOfRange))
+                            }
+                        }
+                        _ => Err(ConversionError::expected("integer", value))
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl <T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> JSONValue {
+        match self {
+            Some(value) => value.to_json(),
+            None => JSONValue::Null,
+        }
+    }
+}
+
+impl <T: FromJson> FromJson for Option<T> {
+    fn from_json(value: &JSONValue) -> Result<Self, ConversionError> {
+        match value {
+            JSONValue::Null => Ok(None),
+            _ => T::from_json(value).map(Some)
+        }
+    }
+}
+
+impl <T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> JSONValue {
+        JSONValue::Array { data: self.iter().map(ToJson::to_json).collect() }
+    }
+}
+
+impl <T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> JSONValue {
+        self.as_slice().to_json()
+    }
+}
+
+fn from_json_elements<T: FromJson>(data: &[JSONValue]) -> Result<Vec<T>, ConversionError> {
+    data.iter()
+        .enumerate()
+        .map(|(i, value)| T::from_json(value).map_err(|err| err.at(PathSegment::Index(i))))
+        .collect()
+}
+
+impl <T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: &JSONValue) -> Result<Self, ConversionError> {
+        match value {
+            JSONValue::Array { data } => from_json_elements(data),
+            _ => Err(ConversionError::expected("array", value))
+        }
+    }
+}
+
+impl <T: ToJson, const N: usize> ToJson for [T; N] {
+    fn to_json(&self) -> JSONValue {
+        self.as_slice().to_json()
+    }
+}
+
+impl <T: FromJson, const N: usize> FromJson for [T; N] {
+    fn from_json(value: &JSONValue) -> Result<Self, ConversionError> {
+        let JSONValue::Array { data } = value else {
+            return Err(ConversionError::expected("array", value))
+        };
+        if data.len() != N {
+            return Err(ConversionError::new(ConversionErrorKind::WrongLength { expected: N, found: data.len() }));
+        }
+        let elements: Vec<T> = from_json_elements(data)?;
+        Ok(elements.try_into().unwrap_or_else(|_| unreachable!()))
+    }
+}
+
+macro_rules! impl_tuple {
+    ($len:expr => $($name:ident $index:tt),+) => {
+        impl <$($name: ToJson),+> ToJson for ($($name,)+) {
+            fn to_json(&self) -> JSONValue {
+                JSONValue::Array { data: vec![$(self.$index.to_json()),+] }
+            }
+        }
+
+        impl <$($name: FromJson),+> FromJson for ($($name,)+) {
+            fn from_json(value: &JSONValue) -> Result<Self, ConversionError> {
+                let JSONValue::Array { data } = value else {
+                    return Err(ConversionError::expected("array", value))
+                };
+                if data.len() != $len {
+                    return Err(ConversionError::new(ConversionErrorKind::WrongLength { expected: $len, found: data.len() }));
+                }
+                Ok(($($name::from_json(&data[$index]).map_err(|err| err.at(PathSegment::Index($index)))?,)+))
+            }
+        }
+    };
+}
+
+impl_tuple!(1 => A 0);
+impl_tuple!(2 => A 0, B 1);
+impl_tuple!(3 => A 0, B 1, C 2);
+impl_tuple!(4 => A 0, B 1, C 2, D 3);
+impl_tuple!(5 => A 0, B 1, C 2, D 3, E 4);
+impl_tuple!(6 => A 0, B 1, C 2, D 3, E 4, F 5);
+
+fn from_json_members<'a, T: FromJson + 'a>(value: &'a JSONValue) -> Result<impl Iterator<Item=Result<(String, T), ConversionError>> + 'a, ConversionError> {
+    let JSONValue::Object { data } = value else {
+        return Err(ConversionError::expected("object", value))
+    };
+    Ok(data.iter().map(|(key, value)| {
+        T::from_json(value)
+            .map(|value| (key.to_string(), value))
+            .map_err(|err| err.at(PathSegment::Key(key.to_string())))
+    }))
+}
+
+impl <K: AsRef<str>, V: ToJson, S> ToJson for HashMap<K, V, S> {
+    fn to_json(&self) -> JSONValue {
+        JSONValue::Object { data: self.iter().map(|(key, value)| (key.as_ref().into(), value.to_json())).collect() }
+    }
+}
+
+impl <K: AsRef<str>, V: ToJson, S> ToJsonObject for HashMap<K, V, S> {
+    fn to_json_members(&self) -> Vec<(JSONKey, JSONValue)> {
+        self.iter().map(|(key, value)| (key.as_ref().into(), value.to_json())).collect()
+    }
+}
+
+impl <K: From<String> + Eq + Hash, V: FromJson, S: BuildHasher + Default> FromJson for HashMap<K, V, S> {
+    fn from_json(value: &JSONValue) -> Result<Self, ConversionError> {
+        from_json_members(value)?.map(|member| member.map(|(key, value)| (K::from(key), value))).collect()
+    }
+}
+
+impl <K: AsRef<str>, V: ToJson> ToJson for BTreeMap<K, V> {
+    fn to_json(&self) -> JSONValue {
+        JSONValue::Object { data: self.iter().map(|(key, value)| (key.as_ref().into(), value.to_json())).collect() }
+    }
+}
+
+impl <K: AsRef<str>, V: ToJson> ToJsonObject for BTreeMap<K, V> {
+    fn to_json_members(&self) -> Vec<(JSONKey, JSONValue)> {
+        self.iter().map(|(key, value)| (key.as_ref().into(), value.to_json())).collect()
+    }
+}
+
+impl <K: From<String> + Ord, V: FromJson> FromJson for BTreeMap<K, V> {
+    fn from_json(value: &JSONValue) -> Result<Self, ConversionError> {
+        from_json_members(value)?.map(|member| member.map(|(key, value)| (K::from(key), value))).collect()
+    }
+}
+
+// Owned conversions into JSONValue, so values can be built with `.into()` in generic code
+macro_rules! impl_from_via_to_json {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for JSONValue {
+                fn from(value: $ty) -> Self {
+                    value.to_json()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_via_to_json!((), bool, char, f32, f64, i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, &str);
+
+impl From<String> for JSONValue {
+    fn from(string: String) -> Self {
+        JSONValue::String { string: string.into() }
+    }
+}
+
+impl <T: Into<JSONValue>> From<Option<T>> for JSONValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(JSONValue::Null, Into::into)
+    }
+}
+
+impl <T: Into<JSONValue>> From<Vec<T>> for JSONValue {
+    fn from(data: Vec<T>) -> Self {
+        JSONValue::Array { data: data.into_iter().map(Into::into).collect() }
+    }
+}
+
+impl <K: Into<String>, V: Into<JSONValue>, S> From<HashMap<K, V, S>> for JSONValue {
+    fn from(data: HashMap<K, V, S>) -> Self {
+        JSONValue::Object { data: data.into_iter().map(|(key, value)| (JSONKey::from(Into::<String>::into(key)), value.into())).collect() }
+    }
+}
+
+impl <K: Into<String>, V: Into<JSONValue>> From<BTreeMap<K, V>> for JSONValue {
+    fn from(data: BTreeMap<K, V>) -> Self {
+        JSONValue::Object { data: data.into_iter().map(|(key, value)| (JSONKey::from(Into::<String>::into(key)), value.into())).collect() }
+    }
+}
+
+impl <T: Into<JSONValue>> FromIterator<T> for JSONValue {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        JSONValue::Array { data: iter.into_iter().map(Into::into).collect() }
+    }
+}
+
+pub fn to_json<T: ToJson + ?Sized>(value: &T) -> JSONValue {
+    value.to_json()
+}
+
+pub fn from_json<T: FromJson>(value: &JSONValue) -> Result<T, ConversionError> {
+    T::from_json(value)
+}
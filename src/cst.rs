@@ -0,0 +1,446 @@
+use std::fmt;
+use crate::byte_parser::{parse_bytes_with_dialect, ByteParser};
+use crate::diff::format_path;
+use crate::stringify::{stringify, stringify_pretty, stringify_string};
+use crate::types::{Dialect, JSONValue, ParseError, PathSegment};
+use crate::types::ParseError::Unknown;
+use crate::json5;
+
+// A concrete syntax tree that keeps everything a parser would throw away: whitespace, comments,
+// member order and the spelling of every scalar. Writing a document out reproduces its input
+// exactly, and edits only rewrite the values they touch.
+#[derive(Clone, Debug)]
+pub struct Document {
+    pub(crate) dialect: Dialect,
+    pub(crate) before: String, // Trivia before the root value
+    pub(crate) root: Node,
+    pub(crate) after: String,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) enum Node {
+    Scalar { raw: String },
+    Array(List<Node>),
+    Object(List<Member>),
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Member {
+    pub(crate) raw_key: String, // The key as written, quoted or not
+    pub(crate) key: String,
+    pub(crate) before_colon: String,
+    pub(crate) after_colon: String,
+    pub(crate) value: Node,
+}
+
+// The elements of an array or members of an object. Each item is written as `before`, the item
+// and `after`, then a comma if another item or a trailing comma follows, then `trailing`. The
+// trivia in front of the closing bracket is `end`.
+#[derive(Clone, Debug)]
+pub(crate) struct List<T> {
+    pub(crate) items: Vec<Item<T>>,
+    pub(crate) trailing_comma: bool,
+    pub(crate) end: String,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Item<T> {
+    pub(crate) before: String,
+    pub(crate) item: T,
+    pub(crate) after: String, // Between the item and its comma
+    pub(crate) trailing: String, // The rest of the line after the comma, usually a comment on the item
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditError {
+    NotFound(Vec<PathSegment>), // The edited value, or the container it would be added to, does not exist
+    RemoveRoot,
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::NotFound(path) => write!(f, "nothing at {}", format_path(path)),
+            EditError::RemoveRoot => write!(f, "the root value cannot be removed"),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+pub fn parse_document(text: &str, dialect: Dialect) -> Result<Document, ParseError> {
+    let mut parser = Parser { text, parser: ByteParser::with_dialect(text.as_bytes(), dialect), dialect };
+    let before = parser.trivia()?;
+    let root = parser.value()?;
+    let after = parser.trivia()?;
+    if parser.parser.head < text.len() {
+        return Err(Unknown)
+    }
+    Ok(Document { dialect, before, root, after })
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    parser: ByteParser<'a>, // Only used to skip trivia
+    dialect: Dialect,
+}
+
+impl Parser<'_> {
+    fn trivia(&mut self) -> Result<String, ParseError> {
+        let start = self.parser.head;
+        self.parser.consume_whitespace();
+        if let Some(offset) = self.parser.unterminated_comment() {
+            return Err(ParseError::UnterminatedComment { offset })
+        }
+        Ok(self.text[start..self.parser.head].to_string())
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.parser.head).copied()
+    }
+
+    // Strings run to their closing quote and everything else to the next delimiter
+    fn scalar(&mut self) -> Result<&str, ParseError> {
+        let bytes = self.text.as_bytes();
+        let start = self.parser.head;
+        let end = match self.peek().ok_or(Unknown)? {
+            quote @ (b'"' | b'\'') => {
+                let mut i = start + 1;
+                loop {
+                    match bytes.get(i).ok_or(Unknown)? {
+                        b'\\' => i += 2,
+                        &byte if byte == quote => break i + 1,
+                        _ => i += 1,
+                    }
+                }
+            }
+            _ => self.text[start..]
+                .find(|c: char| json5::is_whitespace(c) || "[]{}:,\"'/".contains(c))
+                .map_or(self.text.len(), |end| start + end),
+        };
+        if end == start || end > self.text.len() {
+            return Err(Unknown)
+        }
+        self.parser.head = end;
+        Ok(&self.text[start..end])
+    }
+
+    fn value(&mut self) -> Result<Node, ParseError> {
+        match self.peek().ok_or(Unknown)? {
+            b'[' => {
+                self.parser.head += 1;
+                Ok(Node::Array(self.list(b']', Self::value)?))
+            }
+            b'{' => {
+                self.parser.head += 1;
+                Ok(Node::Object(self.list(b'}', Self::member)?))
+            }
+            _ => {
+                let dialect = self.dialect;
+                let raw = self.scalar()?;
+                parse_bytes_with_dialect(raw.as_bytes(), dialect)?;
+                Ok(Node::Scalar { raw: raw.to_string() })
+            }
+        }
+    }
+
+    fn member(&mut self) -> Result<Member, ParseError> {
+        let dialect = self.dialect;
+        let raw_key = self.scalar()?;
+        let mut key_parser = ByteParser::with_dialect(raw_key.as_bytes(), dialect);
+        let key = key_parser.parse_key()?;
+        if key_parser.head < raw_key.len() {
+            return Err(Unknown)
+        }
+        let raw_key = raw_key.to_string();
+        let before_colon = self.trivia()?;
+        if self.peek() != Some(b':') {
+            return Err(Unknown)
+        }
+        self.parser.head += 1;
+        let after_colon = self.trivia()?;
+        let value = self.value()?;
+        Ok(Member { raw_key, key: key.to_string(), before_colon, after_colon, value })
+    }
+
+    fn list<T>(&mut self, close: u8, mut parse_item: impl FnMut(&mut Self) -> Result<T, ParseError>) -> Result<List<T>, ParseError> {
+        let mut items = Vec::new();
+        let mut before = self.trivia()?;
+        loop {
+            if self.peek() == Some(close) && (items.is_empty() || self.dialect.allows_trailing_commas()) {
+                self.parser.head += 1;
+                let trailing_comma = !items.is_empty();
+                return Ok(List { items, trailing_comma, end: before })
+            }
+            let item = parse_item(self)?;
+            let after = self.trivia()?;
+            match self.peek() {
+                Some(b',') => {
+                    self.parser.head += 1;
+                    let trivia = self.trivia()?;
+                    let (trailing, next) = split_line(&trivia);
+                    items.push(Item { before, item, after, trailing: trailing.to_string() });
+                    before = next.to_string();
+                }
+                Some(byte) if byte == close => {
+                    self.parser.head += 1;
+                    let (trailing, end) = split_line(&after);
+                    items.push(Item { before, item, after: String::new(), trailing: trailing.to_string() });
+                    return Ok(List { items, trailing_comma: false, end: end.to_string() })
+                }
+                _ => return Err(Unknown)
+            }
+        }
+    }
+}
+
+// Splits the trivia after an item at the first line break outside of a block comment, so that a
+// comment on the same line as the item stays with it
+fn split_line(trivia: &str) -> (&str, &str) {
+    let bytes = trivia.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\n' | b'\r' => return trivia.split_at(i),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = trivia[i + 2..].find("*/").map_or(bytes.len(), |end| i + end + 4),
+            _ => i += 1,
+        }
+    }
+    ("", trivia)
+}
+
+// The whitespace after the last line break, or None when the text does not span lines
+fn last_indent(trivia: &str) -> Option<&str> {
+    let line = &trivia[trivia.rfind('\n')? + 1..];
+    Some(&line[..line.len() - line.trim_start().len()])
+}
+
+impl<T> List<T> {
+    // New items are laid out like their neighbours: on their own line with the same indentation,
+    // or separated by a space
+    fn indent(&self) -> Option<String> {
+        match self.items.last() {
+            Some(item) if self.items.iter().any(|item| item.before.contains('\n')) => last_indent(&item.before).map(str::to_string),
+            Some(_) => None,
+            None => last_indent(&self.end).map(|indent| format!("{indent}  ")),
+        }
+    }
+
+    fn insert(&mut self, index: usize, item: T, indent: Option<&str>) {
+        let before = match indent {
+            Some(indent) => format!("\n{indent}"),
+            None if index == 0 => match self.items.first_mut() {
+                Some(first) => std::mem::replace(&mut first.before, " ".to_string()),
+                None => String::new(),
+            },
+            None => " ".to_string(),
+        };
+        self.items.insert(index, Item { before, item, after: String::new(), trailing: String::new() });
+    }
+
+    fn remove(&mut self, index: usize) -> T {
+        let removed = self.items.remove(index);
+        // The next item takes the place of the removed one, unless it starts its own line
+        if let Some(next) = self.items.get_mut(index) && !next.before.contains('\n') {
+            next.before = removed.before;
+        }
+        if self.items.is_empty() {
+            self.trailing_comma = false;
+            if self.end.trim().is_empty() {
+                self.end.clear();
+            }
+        }
+        removed.item
+    }
+
+    fn write(&self, out: &mut String, open: char, close: char, write_item: impl Fn(&T, &mut String)) {
+        out.push(open);
+        for (i, item) in self.items.iter().enumerate() {
+            out.push_str(&item.before);
+            write_item(&item.item, out);
+            out.push_str(&item.after);
+            if i + 1 < self.items.len() || self.trailing_comma {
+                out.push(',');
+            }
+            out.push_str(&item.trailing);
+        }
+        out.push_str(&self.end);
+        out.push(close);
+    }
+}
+
+impl Node {
+    // Builds the tree for a new value, pretty printed at `indent` or on a single line
+    fn new(value: &JSONValue, indent: Option<&str>) -> Node {
+        let text = match indent {
+            Some(indent) => stringify_pretty(value).replace('\n', &format!("\n{indent}")),
+            None => stringify(value),
+        };
+        parse_document(&text, Dialect::Json).expect("stringified values are valid JSON").root
+    }
+
+    fn value(&self, dialect: Dialect) -> JSONValue {
+        match self {
+            Node::Scalar { raw } => parse_bytes_with_dialect(raw.as_bytes(), dialect).expect("scalars are validated when parsed"),
+            Node::Array(list) => JSONValue::Array { data: list.items.iter().map(|item| item.item.value(dialect)).collect() },
+            Node::Object(list) => JSONValue::Object {
+                data: list.items.iter().map(|item| (item.item.key.as_str().into(), item.item.value.value(dialect))).collect()
+            },
+        }
+    }
+
+    pub(crate) fn write(&self, out: &mut String) {
+        match self {
+            Node::Scalar { raw } => out.push_str(raw),
+            Node::Array(list) => list.write(out, '[', ']', Node::write),
+            Node::Object(list) => list.write(out, '{', '}', |member, out| {
+                out.push_str(&member.raw_key);
+                out.push_str(&member.before_colon);
+                out.push(':');
+                out.push_str(&member.after_colon);
+                member.value.write(out);
+            }),
+        }
+    }
+
+    // With duplicate keys the last member is the one that counts, as in the parsers
+    fn position(&self, segment: &PathSegment) -> Option<usize> {
+        match (self, segment) {
+            (Node::Object(list), PathSegment::Key(key)) => list.items.iter().rposition(|item| item.item.key == *key),
+            (Node::Array(list), PathSegment::Index(index)) => Some(*index).filter(|index| *index < list.items.len()),
+            _ => None,
+        }
+    }
+
+    fn child(&self, segment: &PathSegment) -> Option<&Node> {
+        let index = self.position(segment)?;
+        match self {
+            Node::Object(list) => Some(&list.items[index].item.value),
+            Node::Array(list) => Some(&list.items[index].item),
+            Node::Scalar { .. } => None,
+        }
+    }
+
+    fn child_mut(&mut self, segment: &PathSegment) -> Option<&mut Node> {
+        let index = self.position(segment)?;
+        match self {
+            Node::Object(list) => Some(&mut list.items[index].item.value),
+            Node::Array(list) => Some(&mut list.items[index].item),
+            Node::Scalar { .. } => None,
+        }
+    }
+}
+
+impl Document {
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
+    pub fn value(&self) -> JSONValue {
+        self.root.value(self.dialect)
+    }
+
+    pub fn get(&self, path: &[PathSegment]) -> Option<JSONValue> {
+        let mut node = &self.root;
+        for segment in path {
+            node = node.child(segment)?;
+        }
+        Some(node.value(self.dialect))
+    }
+
+    // The container holding the last segment of the path
+    fn parent_mut(&mut self, path: &[PathSegment]) -> Result<&mut Node, EditError> {
+        let mut node = &mut self.root;
+        for (i, segment) in path[..path.len() - 1].iter().enumerate() {
+            node = node.child_mut(segment).ok_or_else(|| EditError::NotFound(path[..=i].to_vec()))?;
+        }
+        Ok(node)
+    }
+
+    // Replaces the value at `path`. A missing object member is added, as is an array element
+    // just past the end.
+    pub fn set(&mut self, path: &[PathSegment], value: &JSONValue) -> Result<(), EditError> {
+        let Some(segment) = path.last() else {
+            let indent = self.root_is_multiline().then_some("");
+            self.root = Node::new(value, indent);
+            return Ok(())
+        };
+        let parent = self.parent_mut(path)?;
+        match (parent, segment) {
+            (Node::Object(list), PathSegment::Key(key)) => {
+                let indent = list.indent();
+                match list.items.iter_mut().rev().find(|item| item.item.key == *key) {
+                    Some(item) => item.item.value = Node::new(value, indent.as_deref()),
+                    None => {
+                        let after_colon = list.items.last().map_or(" ".to_string(), |item| item.item.after_colon.clone());
+                        let raw_key = {
+                            let mut raw_key = String::new();
+                            stringify_string(key, &mut raw_key);
+                            raw_key
+                        };
+                        let member = Member { raw_key, key: key.clone(), before_colon: String::new(), after_colon, value: Node::new(value, indent.as_deref()) };
+                        list.insert(list.items.len(), member, indent.as_deref());
+                    }
+                }
+                Ok(())
+            }
+            (Node::Array(list), PathSegment::Index(index)) if *index < list.items.len() => {
+                let indent = list.indent();
+                list.items[*index].item = Node::new(value, indent.as_deref());
+                Ok(())
+            }
+            (Node::Array(list), PathSegment::Index(index)) if *index == list.items.len() => {
+                let indent = list.indent();
+                list.insert(*index, Node::new(value, indent.as_deref()), indent.as_deref());
+                Ok(())
+            }
+            _ => Err(EditError::NotFound(path.to_vec())),
+        }
+    }
+
+    // Inserts an array element before `index`, moving the following elements up. Object members
+    // are set as with `set`.
+    pub fn insert(&mut self, path: &[PathSegment], value: &JSONValue) -> Result<(), EditError> {
+        let Some(PathSegment::Index(index)) = path.last() else {
+            return self.set(path, value)
+        };
+        match self.parent_mut(path)? {
+            Node::Array(list) if *index <= list.items.len() => {
+                let indent = list.indent();
+                list.insert(*index, Node::new(value, indent.as_deref()), indent.as_deref());
+                Ok(())
+            }
+            _ => Err(EditError::NotFound(path.to_vec())),
+        }
+    }
+
+    // Removes a member or element along with the comments in front of it and on its line
+    pub fn remove(&mut self, path: &[PathSegment]) -> Result<JSONValue, EditError> {
+        let Some(segment) = path.last() else {
+            return Err(EditError::RemoveRoot)
+        };
+        let dialect = self.dialect;
+        let parent = self.parent_mut(path)?;
+        let index = parent.position(segment).ok_or_else(|| EditError::NotFound(path.to_vec()))?;
+        Ok(match parent {
+            Node::Object(list) => list.remove(index).value.value(dialect),
+            Node::Array(list) => list.remove(index).value(dialect),
+            Node::Scalar { .. } => unreachable!(),
+        })
+    }
+
+    fn root_is_multiline(&self) -> bool {
+        let mut text = String::new();
+        self.root.write(&mut text);
+        text.contains('\n')
+    }
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = self.before.clone();
+        self.root.write(&mut out);
+        out.push_str(&self.after);
+        f.write_str(&out)
+    }
+}
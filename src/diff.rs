@@ -0,0 +1,245 @@
+use rustc_hash::FxHashMap;
+use crate::stringify;
+use crate::types::{JSONKey, JSONValue};
+pub use crate::types::PathSegment;
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Difference {
+    Added { path: Vec<PathSegment>, value: JSONValue },
+    Removed { path: Vec<PathSegment>, value: JSONValue },
+    Changed { path: Vec<PathSegment>, old: JSONValue, new: JSONValue },
+}
+
+impl Difference {
+    pub fn path(&self) -> &[PathSegment] {
+        match self {
+            Difference::Added { path, .. } | Difference::Removed { path, .. } | Difference::Changed { path, .. } => path
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub enum ArrayDiffMode {
+    #[default]
+    Ordered, // Elements are compared index by index
+    Unordered, // Arrays are treated as multisets
+    ByKey(String), // Object elements are matched by the value of the given member
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct DiffOptions {
+    pub arrays: ArrayDiffMode,
+}
+
+struct Differ<'a> {
+    options: &'a DiffOptions,
+    path: Vec<PathSegment>,
+    differences: Vec<Difference>,
+}
+
+impl <'a> Differ<'a> {
+    fn new(options: &'a DiffOptions) -> Self {
+        Differ { options, path: Vec::new(), differences: Vec::new() }
+    }
+
+    fn added(&mut self, segment: PathSegment, value: &JSONValue) {
+        let mut path = self.path.clone();
+        path.push(segment);
+        self.differences.push(Difference::Added { path, value: value.clone() });
+    }
+
+    fn removed(&mut self, segment: PathSegment, value: &JSONValue) {
+        let mut path = self.path.clone();
+        path.push(segment);
+        self.differences.push(Difference::Removed { path, value: value.clone() });
+    }
+
+    fn diff_child(&mut self, segment: PathSegment, old: &JSONValue, new: &JSONValue) {
+        self.path.push(segment);
+        self.diff(old, new);
+        self.path.pop();
+    }
+
+    fn diff(&mut self, old: &JSONValue, new: &JSONValue) {
+        match (old, new) {
+            (JSONValue::Object { data: old }, JSONValue::Object { data: new }) => self.diff_objects(old, new),
+            (JSONValue::Array { data: old }, JSONValue::Array { data: new }) => match &self.options.arrays {
+                ArrayDiffMode::Ordered => self.diff_ordered(old, new),
+                ArrayDiffMode::Unordered => self.diff_unordered(old, new, (0..old.len()).collect(), (0..new.len()).collect()),
+                ArrayDiffMode::ByKey(key) => self.diff_by_key(old, new, key),
+            },
+            (old, new) => {
+                if old != new {
+                    self.differences.push(Difference::Changed { path: self.path.clone(), old: old.clone(), new: new.clone() });
+                }
+            }
+        }
+    }
+
+    fn diff_objects(&mut self, old: &FxHashMap<JSONKey, JSONValue>, new: &FxHashMap<JSONKey, JSONValue>) {
+        // Keys are visited in sorted order so that reports are stable
+        let mut keys: Vec<&JSONKey> = old.keys().chain(new.keys().filter(|key| !old.contains_key(*key))).collect();
+        keys.sort();
+        for key in keys {
+            match (old.get(key), new.get(key)) {
+                (Some(old), Some(new)) => self.diff_child(PathSegment::Key(key.to_string()), old, new),
+                (Some(old), None) => self.removed(PathSegment::Key(key.to_string()), old),
+                (None, Some(new)) => self.added(PathSegment::Key(key.to_string()), new),
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+
+    fn diff_ordered(&mut self, old: &[JSONValue], new: &[JSONValue]) {
+        for (i, (old, new)) in old.iter().zip(new).enumerate() {
+            self.diff_child(PathSegment::Index(i), old, new);
+        }
+        for (i, old) in old.iter().enumerate().skip(new.len()) {
+            self.removed(PathSegment::Index(i), old);
+        }
+        for (i, new) in new.iter().enumerate().skip(old.len()) {
+            self.added(PathSegment::Index(i), new);
+        }
+    }
+
+    fn diff_unordered(&mut self, old: &[JSONValue], new: &[JSONValue], old_indices: Vec<usize>, new_indices: Vec<usize>) {
+        let mut unmatched_old = old_indices;
+        let mut unmatched_new = Vec::new();
+        for j in new_indices {
+            if let Some(pos) = unmatched_old.iter().position(|&i| old[i] == new[j]) {
+                unmatched_old.remove(pos);
+            } else {
+                unmatched_new.push(j);
+            }
+        }
+        for i in unmatched_old {
+            self.removed(PathSegment::Index(i), &old[i]);
+        }
+        for j in unmatched_new {
+            self.added(PathSegment::Index(j), &new[j]);
+        }
+    }
+
+    fn diff_by_key(&mut self, old: &[JSONValue], new: &[JSONValue], key: &str) {
+        let mut unmatched_old: Vec<usize> = (0..old.len()).collect();
+        let mut keyless_old = Vec::new();
+        let mut keyless_new = Vec::new();
+        unmatched_old.retain(|&i| {
+            let has_key = old[i].get(key).is_some();
+            if !has_key {
+                keyless_old.push(i);
+            }
+            has_key
+        });
+        for (j, new_value) in new.iter().enumerate() {
+            let Some(new_key) = new_value.get(key) else {
+                keyless_new.push(j);
+                continue
+            };
+            if let Some(pos) = unmatched_old.iter().position(|&i| old[i].get(key) == Some(new_key)) {
+                let i = unmatched_old.remove(pos);
+                self.diff_child(PathSegment::Index(j), &old[i], new_value);
+            } else {
+                self.added(PathSegment::Index(j), new_value);
+            }
+        }
+        for i in unmatched_old {
+            self.removed(PathSegment::Index(i), &old[i]);
+        }
+        // Elements without the key can only be matched by equality
+        self.diff_unordered(old, new, keyless_old, keyless_new);
+    }
+}
+
+pub fn diff(old: &JSONValue, new: &JSONValue) -> Vec<Difference> {
+    diff_with_options(old, new, &DiffOptions::default())
+}
+
+pub fn diff_with_options(old: &JSONValue, new: &JSONValue, options: &DiffOptions) -> Vec<Difference> {
+    let mut differ = Differ::new(options);
+    differ.diff(old, new);
+    differ.differences
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!(chars.next(), Some('a'..='z' | 'A'..='Z' | '_'))
+        && chars.all(|c| matches!(c, 'a'..='z' | 'A'..='Z' | '0'..='9' | '_'))
+}
+
+// Paths are formatted like jq paths, e.g. `.a[0]["b c"]`
+pub fn format_path(path: &[PathSegment]) -> String {
+    if path.is_empty() {
+        return ".".to_string();
+    }
+    let mut string = String::new();
+    for segment in path {
+        match segment {
+            PathSegment::Key(key) if is_identifier(key) => {
+                string.push('.');
+                string.push_str(key);
+            }
+            PathSegment::Key(key) => {
+                string.push('[');
+                string.push_str(&stringify(&JSONValue::String { string: key.into() }));
+                string.push(']');
+            }
+            PathSegment::Index(i) => {
+                string.push_str(&format!("[{i}]"));
+            }
+        }
+    }
+    string
+}
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+fn render_line(out_string: &mut String, sign: char, path: &[PathSegment], value: &JSONValue, colored: bool) {
+    if colored {
+        out_string.push_str(if sign == '-' { RED } else { GREEN });
+    }
+    out_string.push(sign);
+    out_string.push(' ');
+    out_string.push_str(&format_path(path));
+    out_string.push_str(": ");
+    out_string.push_str(&stringify(value));
+    if colored {
+        out_string.push_str(RESET);
+    }
+    out_string.push('\n');
+}
+
+pub fn render_diff(differences: &[Difference], colored: bool) -> String {
+    let mut string = String::new();
+    if differences.is_empty() {
+        return string;
+    }
+    string.push_str("--- old\n+++ new\n");
+    for difference in differences {
+        match difference {
+            Difference::Added { path, value } => render_line(&mut string, '+', path, value, colored),
+            Difference::Removed { path, value } => render_line(&mut string, '-', path, value, colored),
+            Difference::Changed { path, old, new } => {
+                render_line(&mut string, '-', path, old, colored);
+                render_line(&mut string, '+', path, new, colored);
+            }
+        }
+    }
+    string
+}
+
+#[macro_export]
+macro_rules! assert_json_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => {
+                let differences = $crate::diff::diff(left, right);
+                if !differences.is_empty() {
+                    panic!("assertion `left == right` failed\n{}", $crate::diff::render_diff(&differences, false));
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,211 @@
+use crate::cst::{parse_document, Document, List, Node};
+use crate::json5;
+use crate::types::{Dialect, ParseError};
+
+// Reformats a document with every member and element on its own line, indented by `indent` per
+// level. Comments stay next to the member or element they were written beside, scalars and keys
+// keep their spelling, and single blank lines between members are kept.
+pub fn format_document(text: &str, dialect: Dialect, indent: &str) -> Result<String, ParseError> {
+    Ok(parse_document(text, dialect)?.format(indent))
+}
+
+impl Document {
+    pub fn format(&self, indent: &str) -> String {
+        let mut formatter = Formatter { out: String::new(), indent };
+        formatter.own_lines(&self.before, 0, false);
+        formatter.node(&self.root, 0);
+        let after = comments(&self.after);
+        match after.comments.first() {
+            Some(first) if first.newlines_before == 0 => formatter.inline(&self.after, 0),
+            Some(_) => {
+                formatter.line(0);
+                formatter.own_lines(&self.after, 0, true);
+            }
+            None => {}
+        }
+        let mut out = formatter.out;
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+        out
+    }
+
+    // Writes the document without whitespace or comments. Keys and scalars keep their spelling and
+    // members their order, unlike `stringify` on the parsed value.
+    pub fn minify(&self) -> String {
+        let mut out = String::new();
+        minify_node(&self.root, &mut out);
+        out
+    }
+}
+
+fn minify_node(node: &Node, out: &mut String) {
+    match node {
+        Node::Scalar { raw } => out.push_str(raw),
+        Node::Array(list) => {
+            out.push('[');
+            for (i, item) in list.items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                minify_node(&item.item, out);
+            }
+            out.push(']');
+        }
+        Node::Object(list) => {
+            out.push('{');
+            for (i, item) in list.items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&item.item.raw_key);
+                out.push(':');
+                minify_node(&item.item.value, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+struct Comment<'a> {
+    text: &'a str,
+    newlines_before: usize,
+}
+
+struct Comments<'a> {
+    comments: Vec<Comment<'a>>,
+    newlines_after: usize, // Between the last comment, or the start, and the end of the trivia
+}
+
+fn count_newlines(whitespace: &str) -> usize {
+    whitespace.replace("\r\n", "\n").chars().filter(|c| json5::is_line_terminator(*c)).count()
+}
+
+// The comments in a stretch of trivia, with the line breaks around them
+fn comments(trivia: &str) -> Comments<'_> {
+    let mut comments = Vec::new();
+    let mut rest = trivia;
+    loop {
+        let start = rest.find('/').unwrap_or(rest.len());
+        let newlines = count_newlines(&rest[..start]);
+        rest = &rest[start..];
+        let end = if rest.starts_with("//") {
+            rest.find(json5::is_line_terminator).unwrap_or(rest.len())
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            comment.find("*/").map_or(rest.len(), |end| end + 4)
+        } else {
+            return Comments { comments, newlines_after: newlines }
+        };
+        comments.push(Comment { text: rest[..end].trim_end(), newlines_before: newlines });
+        rest = &rest[end..];
+    }
+}
+
+struct Formatter<'a> {
+    out: String,
+    indent: &'a str,
+}
+
+impl Formatter<'_> {
+    // Moves to the start of a line indented for `depth`, unless the current line is still empty
+    fn line(&mut self, depth: usize) {
+        self.out.truncate(self.out.trim_end_matches([' ', '\t']).len());
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+        for _ in 0..depth {
+            self.out.push_str(self.indent);
+        }
+    }
+
+    fn blank_line(&mut self, depth: usize) {
+        self.line(0);
+        if !self.out.ends_with("\n\n") {
+            self.out.push('\n');
+        }
+        self.line(depth);
+    }
+
+    // Comments written on lines of their own stay on their own lines, a block comment followed by
+    // something on the same line stays in front of it. `blank` keeps a blank line above the first
+    // comment.
+    fn own_lines(&mut self, trivia: &str, depth: usize, blank: bool) {
+        let Comments { comments, newlines_after } = comments(trivia);
+        for (i, comment) in comments.iter().enumerate() {
+            if comment.newlines_before > 1 && (i > 0 || blank) {
+                self.blank_line(depth);
+            }
+            self.out.push_str(comment.text);
+            match comments.get(i + 1).map_or(newlines_after, |next| next.newlines_before) {
+                0 if !comment.text.starts_with("//") => self.out.push(' '),
+                _ => self.line(depth),
+            }
+        }
+        if newlines_after > 1 && !comments.is_empty() {
+            self.blank_line(depth);
+        }
+    }
+
+    // Comments kept on the current line. Nothing can follow a line comment on its line, so it
+    // ends the line.
+    fn inline(&mut self, trivia: &str, depth: usize) {
+        for comment in comments(trivia).comments {
+            if !self.out.ends_with(char::is_whitespace) {
+                self.out.push(' ');
+            }
+            self.out.push_str(comment.text);
+            if comment.text.starts_with("//") {
+                self.line(depth);
+            }
+        }
+    }
+
+    fn node(&mut self, node: &Node, depth: usize) {
+        match node {
+            Node::Scalar { raw } => self.out.push_str(raw),
+            Node::Array(list) => self.list(list, depth, '[', ']', Self::node),
+            Node::Object(list) => self.list(list, depth, '{', '}', |formatter, member, depth| {
+                formatter.out.push_str(&member.raw_key);
+                formatter.inline(&member.before_colon, depth + 1);
+                formatter.out.push(':');
+                formatter.inline(&member.after_colon, depth + 1);
+                if !formatter.out.ends_with(char::is_whitespace) {
+                    formatter.out.push(' ');
+                }
+                formatter.node(&member.value, depth);
+            }),
+        }
+    }
+
+    fn list<T>(&mut self, list: &List<T>, depth: usize, open: char, close: char, write_item: impl Fn(&mut Self, &T, usize)) {
+        self.out.push(open);
+        if list.items.is_empty() && comments(&list.end).comments.is_empty() {
+            self.out.push(close);
+            return
+        }
+        for (i, item) in list.items.iter().enumerate() {
+            let before = comments(&item.before);
+            let newlines = before.comments.first().map_or(before.newlines_after, |first| first.newlines_before);
+            if i > 0 && newlines > 1 {
+                self.blank_line(depth + 1);
+            }
+            self.line(depth + 1);
+            self.own_lines(&item.before, depth + 1, false);
+            write_item(self, &item.item, depth + 1);
+            if i + 1 < list.items.len() || list.trailing_comma {
+                self.out.push(',');
+            }
+            self.inline(&item.after, depth + 1);
+            self.inline(&item.trailing, depth + 1);
+        }
+        let end = comments(&list.end);
+        if let Some(first) = end.comments.first() {
+            if !list.items.is_empty() && first.newlines_before > 1 {
+                self.blank_line(depth + 1);
+            }
+            self.line(depth + 1);
+            self.own_lines(&list.end, depth + 1, false);
+        }
+        self.line(depth);
+        self.out.push(close);
+    }
+}
@@ -0,0 +1,44 @@
+use rustc_hash::FxHashSet;
+use crate::small_string::INLINE_CAPACITY;
+use crate::types::JSONKey;
+
+// A symbol table of object keys. Parsing with an interner makes every occurrence of a key share
+// one allocation, which pays off for documents made of many objects with the same shape. The
+// interner can be kept around so that keys are also shared between documents.
+#[derive(Default, Debug)]
+pub struct KeyInterner {
+    keys: FxHashSet<JSONKey>,
+}
+
+impl KeyInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, key: &str) -> JSONKey {
+        // Short keys are stored inline, so there is no allocation to share
+        if key.len() <= INLINE_CAPACITY {
+            return JSONKey::from(key)
+        }
+        if let Some(interned) = self.keys.get(key) {
+            return interned.clone()
+        }
+        let interned = JSONKey::from(key);
+        self.keys.insert(interned.clone());
+        interned
+    }
+
+    // The number of distinct keys stored so far. Short keys are never stored.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    // Forgets all keys. Values that were already parsed keep theirs alive.
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+}
@@ -0,0 +1,80 @@
+use crate::types::ParseError;
+use crate::types::ParseError::Unknown;
+
+// Pieces of the JSON5 grammar shared by the lexer and the byte parser.
+// JSON5 specification: https://spec.json5.org
+
+// Besides the JSON whitespace, JSON5 allows any Unicode space separator and the byte order mark
+pub(crate) fn is_whitespace(c: char) -> bool {
+    c.is_whitespace() || c == '\u{feff}'
+}
+
+pub(crate) fn is_line_terminator(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
+
+// Unquoted keys are ECMAScript identifier names. Letters stand in for the ID_Start and
+// ID_Continue classes, which the standard library does not expose.
+pub(crate) fn is_identifier_start(c: char) -> bool {
+    c == '$' || c == '_' || c.is_alphabetic()
+}
+
+pub(crate) fn is_identifier_part(c: char) -> bool {
+    is_identifier_start(c) || c.is_alphanumeric() || c == '\u{200c}' || c == '\u{200d}'
+}
+
+// The value of a single character escape. Digits, `x`, `u` and line terminators have their own
+// rules, every other character stands for itself.
+pub(crate) fn escape(c: char) -> Option<char> {
+    match c {
+        'b' => Some('\u{0008}'),
+        'f' => Some('\u{000c}'),
+        'n' => Some('\n'),
+        'r' => Some('\r'),
+        't' => Some('\t'),
+        'v' => Some('\u{000b}'),
+        '0'..='9' | 'x' | 'u' => None,
+        _ if is_line_terminator(c) => None,
+        _ => Some(c),
+    }
+}
+
+// Numbers may be written in hex, have a leading `+`, a leading or trailing decimal point, or be
+// one of `Infinity` and `NaN`
+pub(crate) fn parse_number(literal: &str) -> Result<f64, ParseError> {
+    let (sign, magnitude) = match literal.as_bytes().first() {
+        Some(b'-') => (-1.0, &literal[1..]),
+        Some(b'+') => (1.0, &literal[1..]),
+        _ => (1.0, literal),
+    };
+    let number = match magnitude {
+        "Infinity" => f64::INFINITY,
+        "NaN" => f64::NAN,
+        _ if magnitude.starts_with("0x") || magnitude.starts_with("0X") => {
+            let digits = &magnitude[2..];
+            if digits.is_empty() {
+                return Err(Unknown)
+            }
+            digits.chars().try_fold(0.0, |number, digit| Ok(number * 16.0 + digit.to_digit(16).ok_or(Unknown)? as f64))?
+        }
+        _ => {
+            let (mantissa, exponent) = match magnitude.find(['e', 'E']) {
+                Some(e) => (&magnitude[..e], Some(&magnitude[e + 1..])),
+                None => (magnitude, None),
+            };
+            let (int, frac) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+            let is_digits = |digits: &str| digits.bytes().all(|byte| byte.is_ascii_digit());
+            if !is_digits(int) || !is_digits(frac) || int.len() + frac.len() == 0 || (int.len() > 1 && int.starts_with('0')) {
+                return Err(Unknown)
+            }
+            if let Some(exponent) = exponent {
+                let digits = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+                if digits.is_empty() || !is_digits(digits) {
+                    return Err(Unknown)
+                }
+            }
+            magnitude.parse().or(Err(Unknown))?
+        }
+    };
+    Ok(sign * number)
+}
@@ -0,0 +1,290 @@
+use std::fmt;
+use crate::byte_parser::ByteParser;
+use crate::types::{JSONValue, ParseError};
+use crate::types::ParseError::Unknown;
+
+// A value within a document that has not been parsed yet. Only the parts of the document that are
+// read through it get validated: subtrees that are stepped over are only checked for balanced
+// brackets, so syntax errors inside them go unnoticed.
+#[derive(Clone, Copy)]
+pub struct LazyValue<'a> {
+    data: &'a [u8],
+    start: usize, // The first byte of the value
+}
+
+pub fn parse_bytes_lazy(bytes: &[u8]) -> Result<LazyValue<'_>, ParseError> {
+    let mut parser = ByteParser::new(bytes);
+    parser.consume_whitespace();
+    if parser.head >= bytes.len() {
+        return Err(Unknown)
+    }
+    Ok(LazyValue { data: bytes, start: parser.head })
+}
+
+fn is_delimiter(byte: u8) -> bool {
+    matches!(byte, 0x20 | 0x09 | 0x0a | 0x0d | 0x2c | 0x3a | 0x5b | 0x5d | 0x7b | 0x7d | 0x22)
+}
+
+// Returns the position just past the closing quote of a string whose body starts at `head`
+fn skip_string(data: &[u8], mut head: usize) -> Result<usize, ParseError> {
+    loop {
+        head += data.get(head..).ok_or(Unknown)?.iter().position(|&byte| byte == 0x22 || byte == 0x5c).ok_or(Unknown)?;
+        if data[head] == 0x22 {
+            return Ok(head + 1)
+        }
+        head += 2; // Whatever follows a backslash cannot end the string
+    }
+}
+
+// Returns the position just past the value starting at `start` by matching brackets
+fn skip_value(data: &[u8], start: usize) -> Result<usize, ParseError> {
+    match data.get(start).ok_or(Unknown)? {
+        0x22 => skip_string(data, start + 1),
+        0x5b | 0x7b => {
+            let mut depth = 1;
+            let mut head = start + 1;
+            loop {
+                head += data[head..].iter().position(|&byte| matches!(byte, 0x22 | 0x5b | 0x5d | 0x7b | 0x7d)).ok_or(Unknown)?;
+                match data[head] {
+                    0x22 => {
+                        head = skip_string(data, head + 1)?;
+                        continue
+                    }
+                    0x5b | 0x7b => depth += 1,
+                    _ => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Ok(head + 1)
+                        }
+                    }
+                }
+                head += 1;
+            }
+        }
+        _ => {
+            let len = data[start..].iter().position(|&byte| is_delimiter(byte)).unwrap_or(data.len() - start);
+            if len == 0 {
+                return Err(Unknown)
+            }
+            Ok(start + len)
+        }
+    }
+}
+
+impl<'a> LazyValue<'a> {
+    fn first_byte(&self) -> u8 {
+        self.data[self.start]
+    }
+
+    // Parses a scalar with the given parser function, checking nothing follows it directly
+    fn parse_scalar<T>(&self, parse: impl FnOnce(&mut ByteParser<'a>) -> Result<T, ParseError>) -> Result<T, ParseError> {
+        let mut parser = ByteParser::new(self.data);
+        parser.head = self.start + 1;
+        let res = parse(&mut parser)?;
+        match self.data.get(parser.head) {
+            Some(&byte) if !is_delimiter(byte) => Err(Unknown),
+            _ => Ok(res)
+        }
+    }
+
+    pub fn is_null(&self) -> Result<bool, ParseError> {
+        match self.first_byte() {
+            0x6e => self.parse_scalar(|parser| parser.parse_null()).map(|_| true),
+            _ => Ok(false)
+        }
+    }
+
+    pub fn as_bool(&self) -> Result<Option<bool>, ParseError> {
+        match self.first_byte() {
+            0x74 => self.parse_scalar(|parser| parser.parse_true()).map(|_| Some(true)),
+            0x66 => self.parse_scalar(|parser| parser.parse_false()).map(|_| Some(false)),
+            _ => Ok(None)
+        }
+    }
+
+    pub fn as_f64(&self) -> Result<Option<f64>, ParseError> {
+        match self.first_byte() {
+            0x2d | 0x30..=0x39 => self.parse_scalar(|parser| parser.parse_number()).map(Some),
+            _ => Ok(None)
+        }
+    }
+
+    pub fn as_str(&self) -> Result<Option<String>, ParseError> {
+        match self.first_byte() {
+            0x22 => self.parse_scalar(|parser| parser.parse_string()).map(Some),
+            _ => Ok(None)
+        }
+    }
+
+    pub fn is_array(&self) -> bool {
+        self.first_byte() == 0x5b
+    }
+
+    pub fn is_object(&self) -> bool {
+        self.first_byte() == 0x7b
+    }
+
+    // The unparsed bytes of the value
+    pub fn raw(&self) -> Result<&'a [u8], ParseError> {
+        Ok(&self.data[self.start..skip_value(self.data, self.start)?])
+    }
+
+    // Fully parses and validates the value and everything in it
+    pub fn parse(&self) -> Result<JSONValue, ParseError> {
+        let mut parser = ByteParser::new(self.data);
+        parser.head = self.start;
+        parser.parse()
+    }
+
+    pub fn elements(&self) -> Option<LazyElements<'a>> {
+        if !self.is_array() {
+            return None
+        }
+        let mut parser = ByteParser::new(self.data);
+        parser.head = self.start + 1;
+        Some(LazyElements { parser, first: true, done: false })
+    }
+
+    pub fn members(&self) -> Option<LazyMembers<'a>> {
+        if !self.is_object() {
+            return None
+        }
+        let mut parser = ByteParser::new(self.data);
+        parser.head = self.start + 1;
+        Some(LazyMembers { parser, first: true, done: false, key: Vec::new() })
+    }
+
+    // Finds an element by index, skipping over the elements before it
+    pub fn get_index(&self, index: usize) -> Result<Option<LazyValue<'a>>, ParseError> {
+        match self.elements() {
+            Some(mut elements) => elements.nth(index).transpose(),
+            None => Ok(None)
+        }
+    }
+
+    // Finds a member by key, skipping over the values of the members before it. Unlike
+    // `parse_bytes`, the first of several members with the same key is returned.
+    pub fn get(&self, key: &str) -> Result<Option<LazyValue<'a>>, ParseError> {
+        let Some(mut members) = self.members() else {
+            return Ok(None)
+        };
+        while let Some(value) = members.next_member()? {
+            if members.key == key.as_bytes() {
+                return Ok(Some(value))
+            }
+        }
+        Ok(None)
+    }
+
+    // Follows a path of object keys, as in `value.get(a)?.get(b)?`
+    pub fn get_path(&self, keys: &[&str]) -> Result<Option<LazyValue<'a>>, ParseError> {
+        let mut value = *self;
+        for key in keys {
+            match value.get(key)? {
+                Some(member) => value = member,
+                None => return Ok(None)
+            }
+        }
+        Ok(Some(value))
+    }
+}
+
+impl fmt::Debug for LazyValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.raw() {
+            Ok(raw) => write!(f, "LazyValue({})", String::from_utf8_lossy(raw)),
+            Err(_) => write!(f, "LazyValue(<invalid>)"),
+        }
+    }
+}
+
+// Steps the parser past the separator before the next element or member, returning false at the end
+fn next_entry(parser: &mut ByteParser, first: &mut bool, end: u8) -> Result<bool, ParseError> {
+    parser.consume_whitespace();
+    let byte = *parser.data.get(parser.head).ok_or(Unknown)?;
+    if byte == end {
+        parser.head += 1;
+        return Ok(false)
+    }
+    if std::mem::replace(first, false) {
+        return Ok(true)
+    }
+    parser.assert_next_byte(0x2c)?; // ,
+    parser.consume_whitespace();
+    Ok(true)
+}
+
+fn next_value<'a>(parser: &mut ByteParser<'a>) -> Result<LazyValue<'a>, ParseError> {
+    let start = parser.head;
+    parser.head = skip_value(parser.data, start)?;
+    Ok(LazyValue { data: parser.data, start })
+}
+
+pub struct LazyElements<'a> {
+    parser: ByteParser<'a>,
+    first: bool,
+    done: bool,
+}
+
+impl<'a> Iterator for LazyElements<'a> {
+    type Item = Result<LazyValue<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None
+        }
+        let res = next_entry(&mut self.parser, &mut self.first, 0x5d)
+            .and_then(|more| if more { next_value(&mut self.parser).map(Some) } else { Ok(None) });
+        if !matches!(res, Ok(Some(_))) {
+            self.done = true;
+        }
+        res.transpose()
+    }
+}
+
+pub struct LazyMembers<'a> {
+    parser: ByteParser<'a>,
+    first: bool,
+    done: bool,
+    key: Vec<u8>, // The unescaped key of the last member read
+}
+
+impl<'a> LazyMembers<'a> {
+    fn next_member(&mut self) -> Result<Option<LazyValue<'a>>, ParseError> {
+        if self.done {
+            return Ok(None)
+        }
+        let res = self.read_member();
+        if !matches!(res, Ok(Some(_))) {
+            self.done = true;
+        }
+        res
+    }
+
+    fn read_member(&mut self) -> Result<Option<LazyValue<'a>>, ParseError> {
+        if !next_entry(&mut self.parser, &mut self.first, 0x7d)? {
+            return Ok(None)
+        }
+        self.key.clear();
+        self.parser.assert_next_byte(0x22)?; // "
+        self.parser.parse_string_bytes(&mut self.key)?;
+        self.parser.assert_next_byte(0x3a)?; // :
+        self.parser.consume_whitespace();
+        next_value(&mut self.parser).map(Some)
+    }
+}
+
+impl<'a> Iterator for LazyMembers<'a> {
+    type Item = Result<(String, LazyValue<'a>), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let res = self.next_member().and_then(|value| match value {
+            Some(value) => String::from_utf8(self.key.clone()).or(Err(Unknown)).map(|key| Some((key, value))),
+            None => Ok(None),
+        });
+        if res.is_err() {
+            self.done = true;
+        }
+        res.transpose()
+    }
+}
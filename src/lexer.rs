@@ -1,16 +1,18 @@
 use std::char::DecodeUtf16Error;
-use crate::types::{ParseError, Token, TokenValue};
+use crate::json5;
+use crate::types::{Dialect, ParseError, Token, TokenValue};
 use crate::types::ParseError::{Unknown};
 
 struct Lexer<T: Iterator<Item=char>> {
     previous_char: Option<char>,
-    chars: T
+    chars: T,
+    dialect: Dialect,
+    offset: usize, // Bytes consumed so far, for positioned errors
 }
 
 impl <T: Iterator<Item=char>> Lexer<T> {
-    fn new(chars: T) -> Self {
-        let lexer = Lexer { chars, previous_char: None };
-        lexer
+    fn new(chars: T, dialect: Dialect) -> Self {
+        Lexer { chars, previous_char: None, dialect, offset: 0 }
     }
 
     #[inline(always)]
@@ -20,16 +22,21 @@ impl <T: Iterator<Item=char>> Lexer<T> {
 
     #[inline(always)]
     fn get_next_char_option(&mut self) -> Option<char> {
-        if let Some(c) = self.previous_char {
+        let c = if let Some(c) = self.previous_char {
             self.previous_char = None;
             Some(c)
         }  else {
             self.chars.next()
+        };
+        if let Some(c) = c {
+            self.offset += c.len_utf8();
         }
+        c
     }
 
     #[inline(always)]
     fn backtrack(&mut self, c: char) {
+        self.offset -= c.len_utf8();
         self.previous_char = Some(c);
     }
 
@@ -97,13 +104,8 @@ impl <T: Iterator<Item=char>> Lexer<T> {
                             return Ok(sign * (mantissa as f64));
                         }
                     }
-                    loop {
-                        match char {
-                            '0'..='9' => {
-                                offset -= 1;
-                            }
-                            _ => break
-                        }
+                    while let '0'..='9' = char {
+                        offset -= 1;
                         if let Some(c) = self.get_next_char_option() {
                             char = c;
                         } else {
@@ -134,11 +136,7 @@ impl <T: Iterator<Item=char>> Lexer<T> {
                     return Ok((sign * (mantissa as f64)) * 10.0f64.powi(-offset))
                 }
             }
-            loop {
-                match char {
-                    '0'..='9' => {}
-                    _ => break,
-                }
+            while let '0'..='9' = char {
                 if let Some(c) = self.get_next_char_option() {
                     char = c;
                 } else {
@@ -208,45 +206,156 @@ impl <T: Iterator<Item=char>> Lexer<T> {
                         'n' => string.push('\n'),
                         'r' => string.push('\r'),
                         't' => string.push('\t'),
-                        'u' => {
-                            let c1 = self.get_next_char(Unknown)?.to_digit(16).ok_or(Unknown)?;
-                            let c2 = self.get_next_char(Unknown)?.to_digit(16).ok_or(Unknown)?;
-                            let c3 = self.get_next_char(Unknown)?.to_digit(16).ok_or(Unknown)?;
-                            let c4 = self.get_next_char(Unknown)?.to_digit(16).ok_or(Unknown)?;
-                            let codepoint = c1 << 12 | c2 << 8 | c3 << 4 | c4;
-                            if let Some(char) = char::from_u32(codepoint) {
-                                string.push(char);
-                            } else {
-                                // We are in a utf-16 code_point
-                                self.assert_next_char('\\', Unknown)?;
-                                self.assert_next_char('u', Unknown)?;
-                                let c1 = self.get_next_char(Unknown)?.to_digit(16).ok_or(Unknown)?;
-                                let c2 = self.get_next_char(Unknown)?.to_digit(16).ok_or(Unknown)?;
-                                let c3 = self.get_next_char(Unknown)?.to_digit(16).ok_or(Unknown)?;
-                                let c4 = self.get_next_char(Unknown)?.to_digit(16).ok_or(Unknown)?;
-                                let next_codepoint = c1 << 12 | c2 << 8 | c3 << 4 | c4;
-                                let chars: Result<String, DecodeUtf16Error> = char::decode_utf16([codepoint as u16, next_codepoint as u16]).collect();
-                                string.push_str(&chars.or(Err(Unknown))?)
+                        'u' => string.push_str(&self.parse_unicode_escape()?),
+                        _ => return Err(Unknown),
+                    }
+                }
+                '\u{0000}'..='\u{001f}' => return Err(Unknown),
+                _ => string.push(char)
+            }
+        };
+        Err(Unknown)
+    }
+
+    // The four hex digits after `\u`, followed by a second escape when they are half of a surrogate pair
+    fn parse_unicode_escape(&mut self) -> Result<String, ParseError> {
+        let codepoint = self.parse_hex_digits(4)?;
+        if let Some(char) = char::from_u32(codepoint) {
+            return Ok(char.to_string())
+        }
+        // We are in a utf-16 code_point
+        self.assert_next_char('\\', Unknown)?;
+        self.assert_next_char('u', Unknown)?;
+        let next_codepoint = self.parse_hex_digits(4)?;
+        let chars: Result<String, DecodeUtf16Error> = char::decode_utf16([codepoint as u16, next_codepoint as u16]).collect();
+        chars.or(Err(Unknown))
+    }
+
+    fn parse_hex_digits(&mut self, count: usize) -> Result<u32, ParseError> {
+        let mut value = 0;
+        for _ in 0..count {
+            value = value << 4 | self.get_next_char(Unknown)?.to_digit(16).ok_or(Unknown)?;
+        }
+        Ok(value)
+    }
 
+    fn parse_json5_string(&mut self, quote: char) -> Result<String, ParseError> {
+        let mut string = String::new();
+        while let Some(char) = self.get_next_char_option() {
+            match char {
+                _ if char == quote => return Ok(string),
+                '\\' => {
+                    let escaped = self.get_next_char(Unknown)?;
+                    if let Some(char) = json5::escape(escaped) {
+                        string.push(char);
+                        continue
+                    }
+                    match escaped {
+                        'u' => string.push_str(&self.parse_unicode_escape()?),
+                        'x' => string.push(char::from_u32(self.parse_hex_digits(2)?).ok_or(Unknown)?),
+                        '0' => {
+                            // `\0` must not be followed by a digit, as it would read as an octal escape
+                            match self.get_next_char_option() {
+                                Some('0'..='9') => return Err(Unknown),
+                                Some(char) => self.backtrack(char),
+                                None => {}
                             }
+                            string.push('\u{0000}');
                         }
+                        // An escaped line break continues the string on the next line
+                        '\r' => match self.get_next_char_option() {
+                            Some('\n') | None => {}
+                            Some(char) => self.backtrack(char),
+                        },
+                        '\n' | '\u{2028}' | '\u{2029}' => {}
                         _ => return Err(Unknown),
                     }
                 }
-                '\u{0000}'..='\u{001f}' => return Err(Unknown),
+                '\n' | '\r' => return Err(Unknown),
                 _ => string.push(char)
             }
         };
         Err(Unknown)
     }
 
-    fn consume_whitespace(&mut self) {
+    fn parse_json5_number(&mut self, char: char) -> Result<f64, ParseError> {
+        let mut literal = String::from(char);
+        while let Some(char) = self.get_next_char_option() {
+            if char.is_ascii_alphanumeric() || matches!(char, '+' | '-' | '.') {
+                literal.push(char);
+            } else {
+                self.backtrack(char);
+                break
+            }
+        }
+        json5::parse_number(&literal)
+    }
+
+    fn parse_identifier_char(&mut self, char: char) -> Result<char, ParseError> {
+        if char != '\\' {
+            return Ok(char)
+        }
+        self.assert_next_char('u', Unknown)?;
+        char::from_u32(self.parse_hex_digits(4)?).ok_or(Unknown)
+    }
+
+    fn parse_identifier(&mut self, char: char) -> Result<String, ParseError> {
+        let char = self.parse_identifier_char(char)?;
+        if !json5::is_identifier_start(char) {
+            return Err(Unknown)
+        }
+        let mut identifier = String::from(char);
+        while let Some(char) = self.get_next_char_option() {
+            if char != '\\' && !json5::is_identifier_part(char) {
+                self.backtrack(char);
+                break
+            }
+            let char = self.parse_identifier_char(char)?;
+            if !json5::is_identifier_part(char) {
+                return Err(Unknown)
+            }
+            identifier.push(char);
+        }
+        Ok(identifier)
+    }
+
+    fn consume_whitespace(&mut self) -> Result<(), ParseError> {
         while let Some(char) = self.get_next_char_option() {
             match char {
                 ' ' | '\u{0009}' | '\u{000A}' | '\u{000D}' => continue,
-                _ => { self.backtrack(char); return }
+                '/' if self.dialect.allows_comments() => self.consume_comment()?,
+                _ if self.dialect == Dialect::Json5 && json5::is_whitespace(char) => continue,
+                _ => { self.backtrack(char); return Ok(()) }
             }
         }
+        Ok(())
+    }
+
+    // Skips a comment whose leading slash has been consumed
+    fn consume_comment(&mut self) -> Result<(), ParseError> {
+        let start = self.offset - 1;
+        match self.get_next_char(Unknown)? {
+            '/' => {
+                while let Some(char) = self.get_next_char_option() {
+                    if json5::is_line_terminator(char) {
+                        break
+                    }
+                }
+                Ok(())
+            }
+            '*' => {
+                let unterminated = || ParseError::UnterminatedComment { offset: start };
+                let mut previous = self.get_next_char_option().ok_or_else(unterminated)?;
+                loop {
+                    let char = self.get_next_char_option().ok_or_else(unterminated)?;
+                    if previous == '*' && char == '/' {
+                        return Ok(())
+                    }
+                    previous = char;
+                }
+            }
+            _ => Err(Unknown)
+        }
     }
 
     fn parse_false(&mut self) -> Result<Token, ParseError> {
@@ -284,8 +393,19 @@ impl <T: Iterator<Item=char>> Iterator for Lexer<T> {
     type Item = Result<Token, ParseError>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.consume_whitespace();
+        if let Err(error) = self.consume_whitespace() {
+            return Some(Err(error))
+        }
         let char = self.get_next_char_option()?;
+        if self.dialect == Dialect::Json5 {
+            match char {
+                '"' | '\'' => return Some(self.parse_json5_string(char).map(|s| Token::new(TokenValue::String(s)))),
+                '+' | '-' | '.' | '0'..='9' => return Some(self.parse_json5_number(char).map(|n| Token::new(TokenValue::Number(n)))),
+                '\\' => return Some(self.parse_identifier(char).map(|s| Token::new(TokenValue::Identifier(s)))),
+                _ if json5::is_identifier_start(char) => return Some(self.parse_identifier(char).map(|s| Token::new(TokenValue::Identifier(s)))),
+                _ => {}
+            }
+        }
         match char {
             '"' => Some(self.parse_string().map(|s| Token::new(TokenValue::String(s)))),
             '-' | '0'..='9' => Some(self.parse_number(char).map(|n| Token::new(TokenValue::Number(n)))),
@@ -305,7 +425,11 @@ impl <T: Iterator<Item=char>> Iterator for Lexer<T> {
 }
 
 pub fn lexer(chars: impl Iterator<Item=char>) -> impl Iterator<Item=Result<Token, ParseError>> {
-    Lexer::new(chars)
+    Lexer::new(chars, Dialect::Json)
+}
+
+pub fn lexer_with_dialect(chars: impl Iterator<Item=char>, dialect: Dialect) -> impl Iterator<Item=Result<Token, ParseError>> {
+    Lexer::new(chars, dialect)
 }
 
 
@@ -353,6 +477,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn num_negative() {
         test_lex_tokens([Number(-3.14)], "-3.14");
     }
@@ -1,9 +1,44 @@
 pub mod types;
+pub mod small_string;
 pub mod lexer;
 mod parser;
 mod stringify;
 mod byte_parser;
+mod structural;
+mod json5;
+mod merge_patch;
+mod macros;
+mod access;
+pub mod diff;
+pub mod schema;
+pub mod convert;
+pub mod tape;
+pub mod lazy;
+pub mod arena;
+#[cfg(feature = "interning")]
+pub mod intern;
+pub mod cst;
+mod format;
+pub mod recover;
+pub mod spans;
+pub mod query;
+#[cfg(feature = "serde")]
+pub mod serde;
 
-pub use parser::parse;
-pub use stringify::stringify;
-pub use byte_parser::parse_bytes;
+pub use parser::{parse, parse_with_dialect};
+pub use stringify::{stringify, stringify_pretty};
+pub use byte_parser::{parse_bytes, parse_bytes_with_dialect};
+#[cfg(feature = "interning")]
+pub use byte_parser::parse_bytes_interned;
+pub use tape::parse_bytes_to_tape;
+pub use lazy::parse_bytes_lazy;
+pub use arena::parse_bytes_in;
+pub use cst::parse_document;
+pub use format::format_document;
+pub use recover::parse_recovering;
+pub use spans::parse_with_spans;
+pub use query::{query, Query};
+pub use merge_patch::{merge_patch, create_merge_patch};
+pub use convert::{ToJson, ToJsonObject, FromJson, to_json, from_json};
+#[cfg(feature = "derive")]
+pub use myjson_derive::{ToJson, FromJson};
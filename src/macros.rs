@@ -0,0 +1,107 @@
+// Builds a `JSONValue` from JSON-like syntax. Any Rust expression implementing `ToJson` can be
+// used as a value, and any expression convertible `Into<String>` can be used as a key.
+#[macro_export]
+macro_rules! json {
+    ($($json:tt)+) => {
+        $crate::json_internal!($($json)+)
+    };
+}
+
+#[macro_export]
+#[doc(hidden)]
+macro_rules! json_internal {
+    // Arrays are munched element by element, accumulating the converted elements
+    (@array [$($elements:expr,)*]) => {
+        vec![$($elements,)*]
+    };
+    (@array [$($elements:expr),*]) => {
+        vec![$($elements),*]
+    };
+    (@array [$($elements:expr,)*] null $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json_internal!(null)] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] true $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json_internal!(true)] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] false $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json_internal!(false)] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] [$($array:tt)*] $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json_internal!([$($array)*])] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] {$($object:tt)*} $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json_internal!({$($object)*})] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json_internal!($next),] $($rest)*)
+    };
+    (@array [$($elements:expr,)*] $last:expr) => {
+        $crate::json_internal!(@array [$($elements,)* $crate::json_internal!($last)])
+    };
+    (@array [$($elements:expr),*] , $($rest:tt)*) => {
+        $crate::json_internal!(@array [$($elements,)*] $($rest)*)
+    };
+
+    // Objects are munched by collecting key tokens up to the colon, then the value up to the comma
+    (@object $object:ident () ()) => {};
+    (@object $object:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        $object.insert(($($key)+).into(), $value);
+        $crate::json_internal!(@object $object () ($($rest)*));
+    };
+    (@object $object:ident [$($key:tt)+] ($value:expr)) => {
+        $object.insert(($($key)+).into(), $value);
+    };
+    (@object $object:ident ($($key:tt)+) (: null $($rest:tt)*)) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!(null)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: true $($rest:tt)*)) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!(true)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: false $($rest:tt)*)) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!(false)) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: [$($array:tt)*] $($rest:tt)*)) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!([$($array)*])) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: {$($map:tt)*} $($rest:tt)*)) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!({$($map)*})) $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*)) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!($value)) , $($rest)*);
+    };
+    (@object $object:ident ($($key:tt)+) (: $value:expr)) => {
+        $crate::json_internal!(@object $object [$($key)+] ($crate::json_internal!($value)));
+    };
+    (@object $object:ident ($($key:tt)*) ($tt:tt $($rest:tt)*)) => {
+        $crate::json_internal!(@object $object ($($key)* $tt) ($($rest)*));
+    };
+
+    (null) => {
+        $crate::types::JSONValue::Null
+    };
+    (true) => {
+        $crate::types::JSONValue::True
+    };
+    (false) => {
+        $crate::types::JSONValue::False
+    };
+    ([]) => {
+        $crate::types::JSONValue::Array { data: vec![] }
+    };
+    ([ $($tt:tt)+ ]) => {
+        $crate::types::JSONValue::Array { data: $crate::json_internal!(@array [] $($tt)+) }
+    };
+    ({}) => {
+        $crate::types::JSONValue::Object { data: ::std::default::Default::default() }
+    };
+    ({ $($tt:tt)+ }) => {{
+        let mut value = $crate::types::JSONValue::Object { data: ::std::default::Default::default() };
+        if let $crate::types::JSONValue::Object { data: object } = &mut value {
+            $crate::json_internal!(@object object () ($($tt)+));
+        }
+        value
+    }};
+    ($other:expr) => {
+        $crate::convert::ToJson::to_json(&$other)
+    };
+}
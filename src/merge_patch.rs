@@ -0,0 +1,50 @@
+use rustc_hash::FxHashMap;
+use crate::types::JSONValue;
+
+// JSON Merge Patch specification: https://datatracker.ietf.org/doc/html/rfc7396
+
+pub fn merge_patch(target: &mut JSONValue, patch: &JSONValue) {
+    let JSONValue::Object { data: patch } = patch else {
+        *target = patch.clone();
+        return
+    };
+    if !matches!(target, JSONValue::Object { .. }) {
+        *target = JSONValue::Object { data: FxHashMap::default() };
+    }
+    let JSONValue::Object { data: target } = target else {
+        unreachable!()
+    };
+    for (key, value) in patch {
+        if *value == JSONValue::Null {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.clone()).or_insert(JSONValue::Null), value);
+        }
+    }
+}
+
+// Null members of objects in `target` cannot be represented in a merge patch as they would be
+// interpreted as deletions, so applying the result to `source` will drop them.
+pub fn create_merge_patch(source: &JSONValue, target: &JSONValue) -> JSONValue {
+    let (JSONValue::Object { data: source }, JSONValue::Object { data: target }) = (source, target) else {
+        return target.clone()
+    };
+    let mut patch = FxHashMap::default();
+    for key in source.keys() {
+        if !target.contains_key(key) {
+            patch.insert(key.clone(), JSONValue::Null);
+        }
+    }
+    for (key, value) in target {
+        match source.get(key) {
+            Some(old_value) if old_value == value => {}
+            Some(old_value) => {
+                patch.insert(key.clone(), create_merge_patch(old_value, value));
+            }
+            None => {
+                patch.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    JSONValue::Object { data: patch }
+}
@@ -1,24 +1,23 @@
-use std::hint::unreachable_unchecked;
 use rustc_hash::FxHashMap;
-use crate::lexer::lexer;
-use crate::types::{JSONValue, ParseError, Token, TokenValue};
-use crate::types::ParseError::{BadState, Unknown};
+use crate::lexer::lexer_with_dialect;
+use crate::types::{Dialect, JSONKey, JSONValue, ParseError, Token, TokenValue};
+use crate::types::ParseError::Unknown;
 
 enum JSONCollections {
-    Object { data: FxHashMap<String, JSONValue>, curr_label: Option<String> },
+    Object { data: FxHashMap<JSONKey, JSONValue>, curr_label: Option<String> },
     Array { data: Vec<JSONValue> }
 }
 
 impl JSONCollections {
 
-    fn to_object(self) -> Option<(FxHashMap<String, JSONValue>, Option<String>)> {
+    fn into_object(self) -> Option<(FxHashMap<JSONKey, JSONValue>, Option<String>)> {
         match self {
             JSONCollections::Object { data, curr_label} => Some((data, curr_label)),
             _ => None
         }
     }
     
-    fn to_array(self) -> Option<Vec<JSONValue>>  {
+    fn into_array(self) -> Option<Vec<JSONValue>>  {
         match self {
             JSONCollections::Array { data } => Some(data),
             _ => None
@@ -31,7 +30,7 @@ impl JSONCollections {
         match self {
             JSONCollections::Object { data, curr_label} => {
                 if let Some(s) = curr_label.take() {
-                    data.insert(s, value);
+                    data.insert(JSONKey::from(s), value);
                     Ok(())
                 } else {
                     Err(Unknown)
@@ -45,13 +44,14 @@ impl JSONCollections {
     } 
 }
 
-fn ensure_separator(tokens: &mut impl Iterator<Item=Result<Token, ParseError>>) -> Result<Token, ParseError> {
+fn ensure_separator(tokens: &mut impl Iterator<Item=Result<Token, ParseError>>, dialect: Dialect) -> Result<Token, ParseError> {
     let token = tokens.next().ok_or(Unknown)??;
     match token.value {
         TokenValue::EndArray | TokenValue::EndObject => Ok(token),
         TokenValue::ValueSeparator => {
             let token = tokens.next().ok_or(Unknown)??;
-            if token.value.can_be_value_start() {
+            let is_trailing = matches!(token.value, TokenValue::EndArray | TokenValue::EndObject);
+            if token.value.can_be_value_start() || (is_trailing && dialect.allows_trailing_commas()) {
                 Ok(token)
             } else {
                 Err(Unknown)
@@ -61,26 +61,38 @@ fn ensure_separator(tokens: &mut impl Iterator<Item=Result<Token, ParseError>>)
     }
 }
 
-fn parse_first(tokens: &mut impl Iterator<Item=Result<Token, ParseError>>) -> Result<JSONValue, ParseError> {
+// Unquoted JSON5 words are literals wherever they are not a key
+fn identifier_value(identifier: &str) -> Result<TokenValue, ParseError> {
+    match identifier {
+        "true" => Ok(TokenValue::True),
+        "false" => Ok(TokenValue::False),
+        "null" => Ok(TokenValue::Null),
+        "Infinity" => Ok(TokenValue::Number(f64::INFINITY)),
+        "NaN" => Ok(TokenValue::Number(f64::NAN)),
+        _ => Err(Unknown)
+    }
+}
+
+fn parse_first(tokens: &mut impl Iterator<Item=Result<Token, ParseError>>, dialect: Dialect) -> Result<JSONValue, ParseError> {
     let mut values: Vec<JSONCollections> = Vec::new();
     let mut token = tokens.next().ok_or(Unknown)??;
     loop {
-        let mut expecting_separator = match token.value {
-            TokenValue::True | TokenValue::False | TokenValue::Null | TokenValue::String(_) | TokenValue::Number(_) | TokenValue::EndArray | TokenValue::EndObject => true,
-            _ => false
-        };
+        if let TokenValue::Identifier(identifier) = &token.value && !matches!(values.last(), Some(JSONCollections::Object { curr_label: None, .. })) {
+            token = Token::new(identifier_value(identifier)?);
+        }
+        let mut expecting_separator = matches!(token.value, TokenValue::True | TokenValue::False | TokenValue::Null | TokenValue::String(_) | TokenValue::Number(_) | TokenValue::EndArray | TokenValue::EndObject);
         
         match (values.last_mut(), token.value) {
             (None, TokenValue::True) => return Ok(JSONValue::True),
             (None, TokenValue::False) => return Ok(JSONValue::False),
             (None, TokenValue::Null) => return Ok(JSONValue::Null),
-            (None, TokenValue::String(s)) => return Ok(JSONValue::String { string: s }),
+            (None, TokenValue::String(s)) => return Ok(JSONValue::String { string: s.into() }),
             (None, TokenValue::Number(n)) => return Ok(JSONValue::Number { number: n }),
             (None, TokenValue::BeginObject) => values.push(JSONCollections::Object{data: Default::default(), curr_label: None}),
             (None, TokenValue::BeginArray) => values.push(JSONCollections::Array {data: vec![]}),
-            (Some(JSONCollections::Object {data, curr_label}), TokenValue::String(s)) => {
+            (Some(JSONCollections::Object {data, curr_label}), TokenValue::String(s) | TokenValue::Identifier(s)) => {
                 if let Some(label) = curr_label.take() {
-                    data.insert(label, JSONValue::String { string: s });
+                    data.insert(JSONKey::from(label), JSONValue::String { string: s.into() });
                 } else {
                     *curr_label = Some(s);
                     if TokenValue::NameSeparator == tokens.next().ok_or(Unknown)??.value {
@@ -93,12 +105,12 @@ fn parse_first(tokens: &mut impl Iterator<Item=Result<Token, ParseError>>) -> Re
             (Some(collection), TokenValue::True) => collection.add_value(JSONValue::True)?,
             (Some(collection), TokenValue::False) => collection.add_value(JSONValue::False)?,
             (Some(collection), TokenValue::Null) => collection.add_value(JSONValue::Null)?,
-            (Some(JSONCollections::Array { data }), TokenValue::String(s)) => data.push(JSONValue::String { string: s }),
+            (Some(JSONCollections::Array { data }), TokenValue::String(s)) => data.push(JSONValue::String { string: s.into() }),
             (Some(collection), TokenValue::Number(n)) => collection.add_value(JSONValue::Number { number: n })?,
             (Some(_), TokenValue::BeginArray) => values.push(JSONCollections::Array {data: vec![]}),
             (Some(_), TokenValue::BeginObject) => values.push(JSONCollections::Object {data: Default::default(), curr_label: None}),
             (Some(JSONCollections::Array {..}), TokenValue::EndArray) => {
-                let data = values.pop().unwrap().to_array().unwrap();
+                let data = values.pop().unwrap().into_array().unwrap();
                 if let Some(collection) = values.last_mut() {
                     collection.add_value(JSONValue::Array { data })?;
                 } else {
@@ -106,7 +118,7 @@ fn parse_first(tokens: &mut impl Iterator<Item=Result<Token, ParseError>>) -> Re
                 };
             }
             (Some(JSONCollections::Object {..}), TokenValue::EndObject) => {
-                let (data, curr_label) = values.pop().unwrap().to_object().unwrap();
+                let (data, curr_label) = values.pop().unwrap().into_object().unwrap();
                
                 if curr_label.is_some() {
                     return Err(Unknown);
@@ -122,7 +134,7 @@ fn parse_first(tokens: &mut impl Iterator<Item=Result<Token, ParseError>>) -> Re
         };
         
         if expecting_separator {
-            token = ensure_separator(tokens)?;
+            token = ensure_separator(tokens, dialect)?;
         } else {
             token = tokens.next().ok_or(Unknown)??;
         }
@@ -130,12 +142,16 @@ fn parse_first(tokens: &mut impl Iterator<Item=Result<Token, ParseError>>) -> Re
 }
 
 pub fn parse(chars: impl Iterator<Item=char>) -> Result<JSONValue, ParseError> {
-    let mut tokens = lexer(chars);
-    let res = parse_first(&mut tokens)?;
-    if tokens.next() == None {
-        Ok(res)
-    } else {
-        Err(Unknown)
+    parse_with_dialect(chars, Dialect::Json)
+}
+
+pub fn parse_with_dialect(chars: impl Iterator<Item=char>, dialect: Dialect) -> Result<JSONValue, ParseError> {
+    let mut tokens = lexer_with_dialect(chars, dialect);
+    let res = parse_first(&mut tokens, dialect)?;
+    match tokens.next() {
+        None => Ok(res),
+        Some(Err(error)) => Err(error),
+        Some(Ok(_)) => Err(Unknown),
     }
 }
 
@@ -145,9 +161,9 @@ mod tests {
 
     #[test]
     fn test_to_object_array() {
-        assert!((JSONCollections::Object { data: FxHashMap::default(), curr_label: None}).to_object().is_some());
-        assert!((JSONCollections::Object { data: FxHashMap::default(), curr_label: None}).to_array().is_none());
-        assert!((JSONCollections::Array { data: vec![]}).to_object().is_none());
-        assert!((JSONCollections::Array { data: vec![]}).to_array().is_some());
+        assert!((JSONCollections::Object { data: FxHashMap::default(), curr_label: None}).into_object().is_some());
+        assert!((JSONCollections::Object { data: FxHashMap::default(), curr_label: None}).into_array().is_none());
+        assert!((JSONCollections::Array { data: vec![]}).into_object().is_none());
+        assert!((JSONCollections::Array { data: vec![]}).into_array().is_some());
     }
 }
\ No newline at end of file
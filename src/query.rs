@@ -0,0 +1,718 @@
+use std::cmp::Ordering;
+use std::fmt;
+use rustc_hash::FxHashMap;
+use crate::byte_parser::parse_bytes;
+use crate::convert::type_name;
+use crate::stringify::stringify;
+use crate::types::JSONValue;
+
+// A subset of the jq language: https://jqlang.github.io/jq/manual/
+// Supported are paths (`.a.b`, `.[0]`, `.["key"]`, `.[]`, `..`), `?`, pipes, `,`, literals, array
+// and object construction, arithmetic, comparisons, `and`, `or`, and the builtins listed in `call`.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    Syntax { offset: usize, message: String }, // A byte offset into the query
+    Runtime(String), // The query does not apply to its input, such as indexing a number
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::Syntax { offset, message } => write!(f, "syntax error at {offset}: {message}"),
+            QueryError::Runtime(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Clone)]
+pub struct Query {
+    filter: Filter,
+}
+
+impl Query {
+    pub fn compile(source: &str) -> Result<Query, QueryError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, position: 0, end: source.len() };
+        let filter = parser.pipe()?;
+        match parser.tokens.get(parser.position) {
+            Some((offset, token)) => Err(syntax(*offset, &format!("unexpected {token}"))),
+            None => Ok(Query { filter }),
+        }
+    }
+
+    // Every output of the query for `input`, in order
+    pub fn run(&self, input: &JSONValue) -> Result<Vec<JSONValue>, QueryError> {
+        let mut outputs = Vec::new();
+        self.filter.eval(input, &mut outputs)?;
+        Ok(outputs)
+    }
+}
+
+// Compiles and runs a query in one go
+pub fn query(source: &str, input: &JSONValue) -> Result<Vec<JSONValue>, QueryError> {
+    Query::compile(source)?.run(input)
+}
+
+fn syntax(offset: usize, message: &str) -> QueryError {
+    QueryError::Syntax { offset, message: message.to_string() }
+}
+
+fn runtime(message: String) -> QueryError {
+    QueryError::Runtime(message)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot, // .
+    Recurse, // ..
+    Field(String), // .name or ."name"
+    Identifier(String),
+    Literal(JSONValue),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Dot => write!(f, "`.`"),
+            Token::Recurse => write!(f, "`..`"),
+            Token::Field(name) => write!(f, "`.{name}`"),
+            Token::Identifier(name) => write!(f, "`{name}`"),
+            Token::Literal(value) => write!(f, "`{}`", stringify(value)),
+            Token::Symbol(symbol) => write!(f, "`{symbol}`"),
+        }
+    }
+}
+
+// Two character symbols come first so that `<=` is not read as `<`
+const SYMBOLS: [&str; 22] = ["==", "!=", "<=", ">=", "|", ",", "<", ">", "+", "-", "*", "/", "%", "(", ")", "[", "]", "{", "}", ":", ";", "?"];
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_identifier_part(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+// The length of the JSON string literal at the start of `text`
+fn string_len(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut i = 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return Some(i + 1),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+// The length of the number at the start of `text`, which is checked by the JSON parser
+fn number_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let digits = |i: usize| i + bytes[i..].iter().take_while(|byte| byte.is_ascii_digit()).count();
+    let mut i = digits(0);
+    if bytes.get(i) == Some(&b'.') {
+        i = digits(i + 1);
+    }
+    if matches!(bytes.get(i), Some(b'e' | b'E')) {
+        i += 1;
+        if matches!(bytes.get(i), Some(b'+' | b'-')) {
+            i += 1;
+        }
+        i = digits(i);
+    }
+    i
+}
+
+fn string_literal(source: &str, offset: usize) -> Result<(String, usize), QueryError> {
+    let len = string_len(&source[offset..]).ok_or_else(|| syntax(offset, "unterminated string"))?;
+    match parse_bytes(&source.as_bytes()[offset..offset + len]) {
+        Ok(JSONValue::String { string }) => Ok((string.into(), len)),
+        _ => Err(syntax(offset, "invalid string")),
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(usize, Token)>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+    while let Some(c) = source[offset..].chars().next() {
+        let rest = &source[offset..];
+        let (token, len) = if c.is_whitespace() {
+            offset += c.len_utf8();
+            continue
+        } else if rest.starts_with("..") {
+            (Token::Recurse, 2)
+        } else if c == '.' && rest[1..].starts_with(is_identifier_start) {
+            let len = rest[1..].find(|c| !is_identifier_part(c)).map_or(rest.len(), |len| len + 1);
+            (Token::Field(rest[1..len].to_string()), len)
+        } else if c == '.' && rest[1..].starts_with('"') {
+            let (name, len) = string_literal(source, offset + 1)?;
+            (Token::Field(name), len + 1)
+        } else if c == '.' {
+            (Token::Dot, 1)
+        } else if c == '"' {
+            let (string, len) = string_literal(source, offset)?;
+            (Token::Literal(JSONValue::String { string: string.into() }), len)
+        } else if c.is_ascii_digit() {
+            let len = number_len(rest);
+            let number = parse_bytes(&rest.as_bytes()[..len]).map_err(|_| syntax(offset, "invalid number"))?;
+            (Token::Literal(number), len)
+        } else if is_identifier_start(c) {
+            let len = rest.find(|c| !is_identifier_part(c)).unwrap_or(rest.len());
+            (Token::Identifier(rest[..len].to_string()), len)
+        } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+            (Token::Symbol(symbol), symbol.len())
+        } else {
+            return Err(syntax(offset, &format!("unexpected character `{c}`")))
+        };
+        tokens.push((offset, token));
+        offset += len;
+    }
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Debug, Clone)]
+enum Filter {
+    Identity,
+    Recurse,
+    Literal(JSONValue),
+    Index(Box<Filter>, Box<Filter>), // target[index]
+    Iterate(Box<Filter>), // target[]
+    Try(Box<Filter>), // target? drops errors
+    Pipe(Box<Filter>, Box<Filter>),
+    Comma(Box<Filter>, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Binary(Operator, Box<Filter>, Box<Filter>),
+    Negate(Box<Filter>),
+    Array(Option<Box<Filter>>),
+    Object(Vec<(Filter, Filter)>),
+    Call(String, Vec<Filter>),
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    end: usize, // Where the query ends, for errors about missing tokens
+}
+
+// Each level of the grammar, from the loosest binding to the tightest, as in jq:
+// pipe `|`, comma `,`, `or`, `and`, comparisons, `+` and `-`, `*`, `/` and `%`, then postfix terms
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.position).map_or(self.end, |(offset, _)| *offset)
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Identifier(name)) if name == keyword);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), QueryError> {
+        if self.eat_symbol(symbol) {
+            return Ok(())
+        }
+        let found = self.peek().map_or("the end of the query".to_string(), |token| token.to_string());
+        Err(syntax(self.offset(), &format!("expected `{symbol}`, found {found}")))
+    }
+
+    fn pipe(&mut self) -> Result<Filter, QueryError> {
+        let left = self.comma()?;
+        if self.eat_symbol("|") {
+            return Ok(Filter::Pipe(Box::new(left), Box::new(self.pipe()?)))
+        }
+        Ok(left)
+    }
+
+    fn comma(&mut self) -> Result<Filter, QueryError> {
+        let mut left = self.or()?;
+        while self.eat_symbol(",") {
+            left = Filter::Comma(Box::new(left), Box::new(self.or()?));
+        }
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Filter, QueryError> {
+        let mut left = self.and()?;
+        while self.eat_keyword("or") {
+            left = Filter::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Filter, QueryError> {
+        let mut left = self.comparison()?;
+        while self.eat_keyword("and") {
+            left = Filter::And(Box::new(left), Box::new(self.comparison()?));
+        }
+        Ok(left)
+    }
+
+    // Comparisons do not chain
+    fn comparison(&mut self) -> Result<Filter, QueryError> {
+        let left = self.additive()?;
+        let operators = [("==", Operator::Equal), ("!=", Operator::NotEqual), ("<=", Operator::LessOrEqual), (">=", Operator::GreaterOrEqual), ("<", Operator::Less), (">", Operator::Greater)];
+        for (symbol, operator) in operators {
+            if self.eat_symbol(symbol) {
+                return Ok(Filter::Binary(operator, Box::new(left), Box::new(self.additive()?)))
+            }
+        }
+        Ok(left)
+    }
+
+    fn additive(&mut self) -> Result<Filter, QueryError> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let operator = if self.eat_symbol("+") {
+                Operator::Add
+            } else if self.eat_symbol("-") {
+                Operator::Subtract
+            } else {
+                return Ok(left)
+            };
+            left = Filter::Binary(operator, Box::new(left), Box::new(self.multiplicative()?));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Filter, QueryError> {
+        let mut left = self.postfix()?;
+        loop {
+            let operator = if self.eat_symbol("*") {
+                Operator::Multiply
+            } else if self.eat_symbol("/") {
+                Operator::Divide
+            } else if self.eat_symbol("%") {
+                Operator::Remainder
+            } else {
+                return Ok(left)
+            };
+            left = Filter::Binary(operator, Box::new(left), Box::new(self.postfix()?));
+        }
+    }
+
+    // A term followed by any number of `.name`, `[index]`, `[]` and `?`
+    fn postfix(&mut self) -> Result<Filter, QueryError> {
+        let mut filter = self.term()?;
+        loop {
+            filter = match self.peek() {
+                Some(Token::Field(name)) => {
+                    let name = JSONValue::String { string: name.as_str().into() };
+                    self.position += 1;
+                    Filter::Index(Box::new(filter), Box::new(Filter::Literal(name)))
+                }
+                Some(Token::Dot) if matches!(self.tokens.get(self.position + 1), Some((_, Token::Symbol("[")))) => {
+                    self.position += 1;
+                    continue
+                }
+                Some(Token::Symbol("[")) => {
+                    self.position += 1;
+                    if self.eat_symbol("]") {
+                        Filter::Iterate(Box::new(filter))
+                    } else {
+                        let index = self.pipe()?;
+                        self.expect("]")?;
+                        Filter::Index(Box::new(filter), Box::new(index))
+                    }
+                }
+                Some(Token::Symbol("?")) => {
+                    self.position += 1;
+                    Filter::Try(Box::new(filter))
+                }
+                _ => return Ok(filter)
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Filter, QueryError> {
+        let offset = self.offset();
+        let Some((_, token)) = self.tokens.get(self.position).cloned() else {
+            return Err(syntax(offset, "expected a filter, found the end of the query"))
+        };
+        self.position += 1;
+        match token {
+            Token::Dot => Ok(Filter::Identity),
+            Token::Recurse => Ok(Filter::Recurse),
+            Token::Field(name) => Ok(Filter::Index(Box::new(Filter::Identity), Box::new(Filter::Literal(JSONValue::String { string: name.into() })))),
+            Token::Literal(value) => Ok(Filter::Literal(value)),
+            Token::Symbol("-") => Ok(Filter::Negate(Box::new(self.postfix()?))),
+            Token::Symbol("(") => {
+                let filter = self.pipe()?;
+                self.expect(")")?;
+                Ok(filter)
+            }
+            Token::Symbol("[") => {
+                if self.eat_symbol("]") {
+                    return Ok(Filter::Array(None))
+                }
+                let filter = self.pipe()?;
+                self.expect("]")?;
+                Ok(Filter::Array(Some(Box::new(filter))))
+            }
+            Token::Symbol("{") => self.object(),
+            Token::Identifier(name) => match name.as_str() {
+                "true" => Ok(Filter::Literal(JSONValue::True)),
+                "false" => Ok(Filter::Literal(JSONValue::False)),
+                "null" => Ok(Filter::Literal(JSONValue::Null)),
+                _ => self.call(name, offset),
+            },
+            token => Err(syntax(offset, &format!("unexpected {token}"))),
+        }
+    }
+
+    fn object(&mut self) -> Result<Filter, QueryError> {
+        let mut members = Vec::new();
+        if self.eat_symbol("}") {
+            return Ok(Filter::Object(members))
+        }
+        loop {
+            let offset = self.offset();
+            let key = match self.peek().cloned() {
+                Some(Token::Identifier(name)) => {
+                    self.position += 1;
+                    Filter::Literal(JSONValue::String { string: name.into() })
+                }
+                Some(Token::Literal(string @ JSONValue::String { .. })) => {
+                    self.position += 1;
+                    Filter::Literal(string)
+                }
+                Some(Token::Symbol("(")) => {
+                    self.position += 1;
+                    let key = self.pipe()?;
+                    self.expect(")")?;
+                    key
+                }
+                _ => return Err(syntax(offset, "expected an object key")),
+            };
+            // `{a}` is short for `{a: .a}`
+            let value = if self.eat_symbol(":") {
+                self.or()?
+            } else if let Filter::Literal(_) = key {
+                Filter::Index(Box::new(Filter::Identity), Box::new(key.clone()))
+            } else {
+                return Err(syntax(self.offset(), "expected `:`"))
+            };
+            members.push((key, value));
+            if self.eat_symbol("}") {
+                return Ok(Filter::Object(members))
+            }
+            self.expect(",")?;
+        }
+    }
+
+    fn call(&mut self, name: String, offset: usize) -> Result<Filter, QueryError> {
+        let mut args = Vec::new();
+        if self.eat_symbol("(") {
+            loop {
+                args.push(self.pipe()?);
+                if self.eat_symbol(")") {
+                    break
+                }
+                self.expect(";")?;
+            }
+        }
+        let arity = match name.as_str() {
+            "length" | "keys" | "not" | "empty" | "type" | "add" => 0,
+            "map" | "select" | "has" => 1,
+            _ => return Err(syntax(offset, &format!("unknown function `{name}`"))),
+        };
+        if args.len() != arity {
+            return Err(syntax(offset, &format!("`{name}` takes {arity} argument(s)")))
+        }
+        // `map(f)` is `[.[] | f]`
+        if name == "map" {
+            let iterate = Filter::Iterate(Box::new(Filter::Identity));
+            return Ok(Filter::Array(Some(Box::new(Filter::Pipe(Box::new(iterate), Box::new(args.remove(0)))))))
+        }
+        Ok(Filter::Call(name, args))
+    }
+}
+
+fn is_truthy(value: &JSONValue) -> bool {
+    !matches!(value, JSONValue::False | JSONValue::Null)
+}
+
+fn boolean(value: bool) -> JSONValue {
+    if value { JSONValue::True } else { JSONValue::False }
+}
+
+fn describe(value: &JSONValue) -> String {
+    let text = stringify(value);
+    let text = if text.len() > 20 { format!("{}...", &text[..text.floor_char_boundary(20)]) } else { text };
+    format!("{} ({text})", type_name(value))
+}
+
+fn index(target: &JSONValue, index: &JSONValue) -> Result<JSONValue, QueryError> {
+    match (target, index) {
+        (JSONValue::Null, JSONValue::String { .. } | JSONValue::Number { .. }) => Ok(JSONValue::Null),
+        (JSONValue::Object { data }, JSONValue::String { string }) => Ok(data.get(string.as_str()).cloned().unwrap_or(JSONValue::Null)),
+        // Negative indices count from the end
+        (JSONValue::Array { data }, JSONValue::Number { number }) => {
+            let i = number.floor();
+            let i = if i < 0.0 { data.len() as f64 + i } else { i };
+            Ok(if i < 0.0 { JSONValue::Null } else { data.get(i as usize).cloned().unwrap_or(JSONValue::Null) })
+        }
+        _ => Err(runtime(format!("cannot index {} with {}", type_name(target), describe(index)))),
+    }
+}
+
+fn arithmetic(operator: Operator, left: &JSONValue, right: &JSONValue) -> Result<JSONValue, QueryError> {
+    use JSONValue::{Array, Null, Number, Object, String};
+    let result = match (operator, left, right) {
+        (Operator::Add, Null, value) | (Operator::Add, value, Null) => Some(value.clone()),
+        (Operator::Add, Number { number: a }, Number { number: b }) => Some(Number { number: a + b }),
+        (Operator::Add, String { string: a }, String { string: b }) => Some(String { string: format!("{a}{b}").into() }),
+        (Operator::Add, Array { data: a }, Array { data: b }) => Some(Array { data: a.iter().chain(b).cloned().collect() }),
+        (Operator::Add, Object { data: a }, Object { data: b }) => {
+            let mut data = a.clone();
+            data.extend(b.iter().map(|(key, value)| (key.clone(), value.clone())));
+            Some(Object { data })
+        }
+        (Operator::Subtract, Number { number: a }, Number { number: b }) => Some(Number { number: a - b }),
+        (Operator::Subtract, Array { data: a }, Array { data: b }) => Some(Array { data: a.iter().filter(|value| !b.contains(value)).cloned().collect() }),
+        (Operator::Multiply, Number { number: a }, Number { number: b }) => Some(Number { number: a * b }),
+        (Operator::Divide | Operator::Remainder, Number { .. }, Number { number: b }) if *b == 0.0 => {
+            return Err(runtime(format!("{} and {} cannot be divided because the divisor is zero", describe(left), describe(right))))
+        }
+        (Operator::Divide, Number { number: a }, Number { number: b }) => Some(Number { number: a / b }),
+        // Like jq, the remainder of the operands truncated to integers
+        (Operator::Remainder, Number { number: a }, Number { number: b }) => Some(Number { number: (a.trunc() % b.trunc()).abs().copysign(a.trunc()) }),
+        _ => None,
+    };
+    result.ok_or_else(|| {
+        let verb = match operator {
+            Operator::Add => "added",
+            Operator::Subtract => "subtracted",
+            Operator::Multiply => "multiplied",
+            _ => "divided",
+        };
+        runtime(format!("{} and {} cannot be {verb}", describe(left), describe(right)))
+    })
+}
+
+fn compare(operator: Operator, left: &JSONValue, right: &JSONValue) -> JSONValue {
+    let ordering = left.cmp(right);
+    boolean(match operator {
+        Operator::Equal => ordering == Ordering::Equal,
+        Operator::NotEqual => ordering != Ordering::Equal,
+        Operator::Less => ordering == Ordering::Less,
+        Operator::LessOrEqual => ordering != Ordering::Greater,
+        Operator::Greater => ordering == Ordering::Greater,
+        Operator::GreaterOrEqual => ordering != Ordering::Less,
+        _ => unreachable!(),
+    })
+}
+
+fn recurse(value: &JSONValue, outputs: &mut Vec<JSONValue>) {
+    outputs.push(value.clone());
+    match value {
+        JSONValue::Array { data } => data.iter().for_each(|value| recurse(value, outputs)),
+        JSONValue::Object { data } => data.values().for_each(|value| recurse(value, outputs)),
+        _ => {}
+    }
+}
+
+impl Filter {
+    fn outputs(&self, input: &JSONValue) -> Result<Vec<JSONValue>, QueryError> {
+        let mut outputs = Vec::new();
+        self.eval(input, &mut outputs)?;
+        Ok(outputs)
+    }
+
+    // Like jq, binary operators run the right operand in the outer loop
+    fn each_pair(&self, right: &Filter, input: &JSONValue, outputs: &mut Vec<JSONValue>, f: impl Fn(&JSONValue, &JSONValue) -> Result<JSONValue, QueryError>) -> Result<(), QueryError> {
+        let left = self.outputs(input)?;
+        for right in right.outputs(input)? {
+            for left in &left {
+                outputs.push(f(left, &right)?);
+            }
+        }
+        Ok(())
+    }
+
+    fn eval(&self, input: &JSONValue, outputs: &mut Vec<JSONValue>) -> Result<(), QueryError> {
+        match self {
+            Filter::Identity => outputs.push(input.clone()),
+            Filter::Recurse => recurse(input, outputs),
+            Filter::Literal(value) => outputs.push(value.clone()),
+            Filter::Index(target, index_filter) => {
+                for index_value in index_filter.outputs(input)? {
+                    for target in target.outputs(input)? {
+                        outputs.push(index(&target, &index_value)?);
+                    }
+                }
+            }
+            Filter::Iterate(target) => {
+                for target in target.outputs(input)? {
+                    match target {
+                        JSONValue::Array { data } => outputs.extend(data),
+                        JSONValue::Object { data } => outputs.extend(data.into_values()),
+                        _ => return Err(runtime(format!("cannot iterate over {}", describe(&target)))),
+                    }
+                }
+            }
+            Filter::Try(filter) => {
+                let mut partial = Vec::new();
+                let _ = filter.eval(input, &mut partial);
+                outputs.extend(partial);
+            }
+            Filter::Pipe(left, right) => {
+                for value in left.outputs(input)? {
+                    right.eval(&value, outputs)?;
+                }
+            }
+            Filter::Comma(left, right) => {
+                left.eval(input, outputs)?;
+                right.eval(input, outputs)?;
+            }
+            // `and` and `or` only run the right operand when the left one does not decide the result
+            Filter::And(left, right) | Filter::Or(left, right) => {
+                let is_and = matches!(self, Filter::And(..));
+                for left in left.outputs(input)? {
+                    if is_truthy(&left) != is_and {
+                        outputs.push(boolean(!is_and));
+                        continue
+                    }
+                    for right in right.outputs(input)? {
+                        outputs.push(boolean(is_truthy(&right)));
+                    }
+                }
+            }
+            Filter::Binary(operator @ (Operator::Add | Operator::Subtract | Operator::Multiply | Operator::Divide | Operator::Remainder), left, right) => {
+                left.each_pair(right, input, outputs, |left, right| arithmetic(*operator, left, right))?;
+            }
+            Filter::Binary(operator, left, right) => {
+                left.each_pair(right, input, outputs, |left, right| Ok(compare(*operator, left, right)))?;
+            }
+            Filter::Negate(filter) => {
+                for value in filter.outputs(input)? {
+                    match value {
+                        JSONValue::Number { number } => outputs.push(JSONValue::Number { number: -number }),
+                        _ => return Err(runtime(format!("{} cannot be negated", describe(&value)))),
+                    }
+                }
+            }
+            Filter::Array(filter) => {
+                let data = match filter {
+                    Some(filter) => filter.outputs(input)?,
+                    None => Vec::new(),
+                };
+                outputs.push(JSONValue::Array { data });
+            }
+            // One object for every combination of the outputs of the keys and values
+            Filter::Object(members) => {
+                let mut objects = vec![FxHashMap::default()];
+                for (key, value) in members {
+                    let mut next = Vec::new();
+                    for key in key.outputs(input)? {
+                        let JSONValue::String { string: key } = key else {
+                            return Err(runtime(format!("object keys must be strings, found {}", describe(&key))))
+                        };
+                        for value in value.outputs(input)? {
+                            for object in &objects {
+                                let mut object = object.clone();
+                                object.insert(key.as_str().into(), value.clone());
+                                next.push(object);
+                            }
+                        }
+                    }
+                    objects = next;
+                }
+                outputs.extend(objects.into_iter().map(|data| JSONValue::Object { data }));
+            }
+            Filter::Call(name, args) => call(name, args, input, outputs)?,
+        }
+        Ok(())
+    }
+}
+
+fn call(name: &str, args: &[Filter], input: &JSONValue, outputs: &mut Vec<JSONValue>) -> Result<(), QueryError> {
+    match name {
+        "empty" => {}
+        "not" => outputs.push(boolean(!is_truthy(input))),
+        "type" => outputs.push(JSONValue::String { string: type_name(input).into() }),
+        "length" => outputs.push(JSONValue::Number {
+            number: match input {
+                JSONValue::Null => 0.0,
+                JSONValue::Number { number } => number.abs(),
+                JSONValue::String { string } => string.chars().count() as f64,
+                JSONValue::Array { data } => data.len() as f64,
+                JSONValue::Object { data } => data.len() as f64,
+                _ => return Err(runtime(format!("{} has no length", describe(input)))),
+            },
+        }),
+        // Object keys are sorted
+        "keys" => outputs.push(JSONValue::Array {
+            data: match input {
+                JSONValue::Object { data } => {
+                    let mut keys: Vec<_> = data.keys().collect();
+                    keys.sort();
+                    keys.into_iter().map(|key| JSONValue::String { string: key.as_str().into() }).collect()
+                }
+                JSONValue::Array { data } => (0..data.len()).map(|i| JSONValue::Number { number: i as f64 }).collect(),
+                _ => return Err(runtime(format!("{} has no keys", describe(input)))),
+            },
+        }),
+        "has" => {
+            for key in args[0].outputs(input)? {
+                outputs.push(boolean(match (input, &key) {
+                    (JSONValue::Object { data }, JSONValue::String { string }) => data.contains_key(string.as_str()),
+                    (JSONValue::Array { data }, JSONValue::Number { number }) => *number >= 0.0 && (*number as usize) < data.len(),
+                    _ => return Err(runtime(format!("cannot check whether {} has a key {}", describe(input), describe(&key)))),
+                }));
+            }
+        }
+        "add" => {
+            let elements = match input {
+                JSONValue::Array { data } => data.clone(),
+                JSONValue::Object { data } => data.values().cloned().collect(),
+                _ => return Err(runtime(format!("cannot iterate over {}", describe(input)))),
+            };
+            outputs.push(elements.iter().try_fold(JSONValue::Null, |sum, value| arithmetic(Operator::Add, &sum, value))?);
+        }
+        "select" => {
+            for condition in args[0].outputs(input)? {
+                if is_truthy(&condition) {
+                    outputs.push(input.clone());
+                }
+            }
+        }
+        _ => unreachable!("calls are checked when parsing"),
+    }
+    Ok(())
+}
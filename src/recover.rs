@@ -0,0 +1,255 @@
+use std::fmt;
+use std::ops::Range;
+use rustc_hash::FxHashMap;
+use crate::byte_parser::{parse_bytes_with_dialect, ByteParser};
+use crate::json5;
+use crate::types::{Dialect, JSONKey, JSONValue};
+
+// A syntax error found by `parse_recovering`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub span: Range<usize>, // Byte offsets into the input. Empty where something is missing.
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}..{}", self.message, self.span.start, self.span.end)
+    }
+}
+
+// Parses as much of `text` as possible instead of stopping at the first error, for editors and
+// linters. Every syntax error is reported, and the value holds everything that could be read:
+// missing commas and colons are assumed, unclosed brackets are closed at the end of the input,
+// and unreadable values become null. The input is valid exactly when no diagnostics are returned.
+pub fn parse_recovering(text: &str, dialect: Dialect) -> (JSONValue, Vec<Diagnostic>) {
+    let mut parser = Parser { text, head: 0, dialect, diagnostics: Vec::new() };
+    parser.trivia();
+    let value = parser.value();
+    parser.trivia();
+    if let Some(char) = text[parser.head..].chars().next() {
+        // Unicode whitespace that the dialect does not allow is trimmed too, so the span covers at least one character
+        let end = text.trim_end().len().max(parser.head + char.len_utf8());
+        parser.report(parser.head..end, "unexpected content after the value");
+    }
+    (value, parser.diagnostics)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    head: usize,
+    dialect: Dialect,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Parser<'_> {
+    fn report(&mut self, span: Range<usize>, message: &str) {
+        self.diagnostics.push(Diagnostic { span, message: message.to_string() });
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.as_bytes().get(self.head).copied()
+    }
+
+    // Comments are skipped in every dialect so that they only cause one diagnostic in JSON
+    fn trivia(&mut self) {
+        loop {
+            let rest = &self.text[self.head..];
+            let trimmed = match self.dialect {
+                Dialect::Json5 => rest.trim_start_matches(json5::is_whitespace),
+                _ => rest.trim_start_matches([' ', '\t', '\n', '\r']),
+            };
+            self.head += rest.len() - trimmed.len();
+            let start = self.head;
+            let end = if trimmed.starts_with("//") {
+                trimmed.find(json5::is_line_terminator).unwrap_or(trimmed.len())
+            } else if let Some(comment) = trimmed.strip_prefix("/*") {
+                match comment.find("*/") {
+                    Some(end) => end + 4,
+                    None => {
+                        self.head = self.text.len();
+                        return self.report(start..self.head, "unterminated comment")
+                    }
+                }
+            } else {
+                return
+            };
+            self.head += end;
+            if !self.dialect.allows_comments() {
+                self.report(start..self.head, "comments are not allowed in JSON");
+            }
+        }
+    }
+
+    // The extent of a scalar: strings run to their closing quote or the end of the line, and
+    // everything else to the next delimiter
+    fn token_end(&self) -> (usize, bool) {
+        let bytes = self.text.as_bytes();
+        match bytes[self.head] {
+            quote @ (b'"' | b'\'') => {
+                let mut i = self.head + 1;
+                while i < bytes.len() && bytes[i] != b'\n' && bytes[i] != b'\r' {
+                    match bytes[i] {
+                        b'\\' => i += 2,
+                        byte if byte == quote => return (i + 1, true),
+                        _ => i += 1,
+                    }
+                }
+                (i.min(bytes.len()), false)
+            }
+            _ => {
+                let rest = &self.text[self.head..];
+                let len = rest.find(|c: char| json5::is_whitespace(c) || "[]{}:,\"'/".contains(c)).unwrap_or(rest.len());
+                // A stray delimiter such as `/` is a token of its own
+                let len = if len == 0 { rest.chars().next().map_or(0, char::len_utf8) } else { len };
+                (self.head + len, true)
+            }
+        }
+    }
+
+    fn value(&mut self) -> JSONValue {
+        match self.peek() {
+            Some(b'[') => self.array(),
+            Some(b'{') => self.object(),
+            None | Some(b']' | b'}' | b',' | b':') => {
+                self.report(self.head..self.head, "expected a value");
+                JSONValue::Null
+            }
+            Some(_) => self.scalar(),
+        }
+    }
+
+    fn scalar(&mut self) -> JSONValue {
+        let start = self.head;
+        let (end, terminated) = self.token_end();
+        self.head = end;
+        if !terminated {
+            self.report(start..end, "unterminated string");
+            return JSONValue::Null
+        }
+        match parse_bytes_with_dialect(&self.text.as_bytes()[start..end], self.dialect) {
+            Ok(value) => value,
+            Err(_) => {
+                self.report(start..end, "invalid value");
+                JSONValue::Null
+            }
+        }
+    }
+
+    fn key(&mut self) -> Option<String> {
+        let start = self.head;
+        let (end, terminated) = self.token_end();
+        self.head = end;
+        if !terminated {
+            self.report(start..end, "unterminated string");
+            return None
+        }
+        let raw = &self.text[start..end];
+        let mut parser = ByteParser::with_dialect(raw.as_bytes(), self.dialect);
+        match parser.parse_key() {
+            Ok(key) if parser.head == raw.len() => Some(key.to_string()),
+            _ => {
+                self.report(start..end, "invalid key");
+                None
+            }
+        }
+    }
+
+    // Handles what comes between items: separators, missing commas and the closing bracket.
+    // Returns false once the list is over.
+    fn separator(&mut self, open: usize, close: u8, expect_item: &mut bool, comma: &mut Option<usize>) -> bool {
+        let name = if close == b']' { "array" } else { "object" };
+        loop {
+            self.trivia();
+            match self.peek() {
+                None => {
+                    self.report(open..open + 1, &format!("unclosed {name}"));
+                    return false
+                }
+                Some(byte) if byte == close => {
+                    self.head += 1;
+                    if let Some(comma) = comma.filter(|_| !self.dialect.allows_trailing_commas()) {
+                        self.report(comma..comma + 1, "trailing comma");
+                    }
+                    return false
+                }
+                // The other closing bracket most likely belongs to an enclosing container
+                Some(b']' | b'}') => {
+                    self.report(self.head..self.head + 1, &format!("expected `{}`", close as char));
+                    return false
+                }
+                Some(b',') => {
+                    if *expect_item {
+                        self.report(self.head..self.head + 1, "unexpected comma");
+                    }
+                    *comma = Some(self.head);
+                    *expect_item = true;
+                    self.head += 1;
+                }
+                Some(b':') if close == b']' => {
+                    self.report(self.head..self.head + 1, "unexpected colon");
+                    *expect_item = true;
+                    self.head += 1;
+                }
+                Some(_) => {
+                    if !*expect_item {
+                        self.report(self.head..self.head, "missing comma");
+                    }
+                    *expect_item = false;
+                    *comma = None;
+                    return true
+                }
+            }
+        }
+    }
+
+    fn array(&mut self) -> JSONValue {
+        let open = self.head;
+        self.head += 1;
+        let mut data = Vec::new();
+        let (mut expect_item, mut comma) = (true, None);
+        while self.separator(open, b']', &mut expect_item, &mut comma) {
+            data.push(self.value());
+        }
+        JSONValue::Array { data }
+    }
+
+    fn object(&mut self) -> JSONValue {
+        let open = self.head;
+        self.head += 1;
+        let mut data = FxHashMap::default();
+        let (mut expect_item, mut comma) = (true, None);
+        while self.separator(open, b'}', &mut expect_item, &mut comma) {
+            let key = match self.peek() {
+                Some(b':') => {
+                    self.report(self.head..self.head, "expected a key");
+                    None
+                }
+                // An array or object in place of a key is skipped along with its value
+                Some(b'[' | b'{') => {
+                    self.report(self.head..self.head, "expected a key");
+                    self.value();
+                    None
+                }
+                _ => self.key(),
+            };
+            self.trivia();
+            match self.peek() {
+                Some(b':') => {
+                    self.head += 1;
+                    self.trivia();
+                }
+                None | Some(b',' | b'}' | b']') => {
+                    self.report(self.head..self.head, "expected `:`");
+                    continue
+                }
+                Some(_) => self.report(self.head..self.head, "expected `:`"),
+            }
+            let value = self.value();
+            if let Some(key) = key {
+                data.insert(JSONKey::from(key), value);
+            }
+        }
+        JSONValue::Object { data }
+    }
+}
@@ -0,0 +1,720 @@
+use regex::Regex;
+use rustc_hash::FxHashMap;
+use crate::types::{JSONKey, JSONValue};
+
+// JSON Schema specification (draft 2020-12): https://json-schema.org/draft/2020-12/json-schema-core
+// Only references within the schema document are supported.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaError {
+    InvalidKeyword { path: String, keyword: String }, // The value of a keyword has the wrong type
+    InvalidPattern { path: String, pattern: String },
+    UnresolvedReference { path: String, reference: String },
+    ReferenceCycle { path: String }, // Following $refs leads back here without descending into the instance
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationError {
+    pub instance_path: String, // JSON Pointer to the failing part of the instance
+    pub schema_path: String, // JSON Pointer to the failing keyword, following any $refs
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InstanceType {
+    Null,
+    Boolean,
+    Object,
+    Array,
+    Number,
+    String,
+    Integer,
+}
+
+impl InstanceType {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "null" => Some(InstanceType::Null),
+            "boolean" => Some(InstanceType::Boolean),
+            "object" => Some(InstanceType::Object),
+            "array" => Some(InstanceType::Array),
+            "number" => Some(InstanceType::Number),
+            "string" => Some(InstanceType::String),
+            "integer" => Some(InstanceType::Integer),
+            _ => None
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            InstanceType::Null => "null",
+            InstanceType::Boolean => "boolean",
+            InstanceType::Object => "object",
+            InstanceType::Array => "array",
+            InstanceType::Number => "number",
+            InstanceType::String => "string",
+            InstanceType::Integer => "integer",
+        }
+    }
+
+    fn of(value: &JSONValue) -> Self {
+        match value {
+            JSONValue::Null => InstanceType::Null,
+            JSONValue::True | JSONValue::False => InstanceType::Boolean,
+            JSONValue::Object { .. } => InstanceType::Object,
+            JSONValue::Array { .. } => InstanceType::Array,
+            JSONValue::Number { number } if is_integer(*number) => InstanceType::Integer,
+            JSONValue::Number { .. } => InstanceType::Number,
+            JSONValue::String { .. } => InstanceType::String,
+        }
+    }
+
+    fn matches(&self, value: &JSONValue) -> bool {
+        let actual = InstanceType::of(value);
+        *self == actual || (*self == InstanceType::Number && actual == InstanceType::Integer)
+    }
+}
+
+fn is_integer(number: f64) -> bool {
+    number.is_finite() && number.fract() == 0.0
+}
+
+#[derive(Debug, Clone)]
+enum Keyword {
+    Type(Vec<InstanceType>),
+    Enum(Vec<JSONValue>),
+    Const(JSONValue),
+    Properties(Vec<(String, usize)>),
+    PatternProperties(Vec<(Regex, usize)>),
+    AdditionalProperties { schema: usize, properties: Vec<String>, patterns: Vec<Regex> },
+    Required(Vec<String>),
+    MinProperties(usize),
+    MaxProperties(usize),
+    PrefixItems(Vec<usize>),
+    Items { schema: usize, prefix_len: usize },
+    Contains(usize),
+    MinItems(usize),
+    MaxItems(usize),
+    UniqueItems,
+    Minimum(f64),
+    Maximum(f64),
+    ExclusiveMinimum(f64),
+    ExclusiveMaximum(f64),
+    MultipleOf(f64),
+    MinLength(usize),
+    MaxLength(usize),
+    Pattern(Regex),
+    AllOf(Vec<usize>),
+    AnyOf(Vec<usize>),
+    OneOf(Vec<usize>),
+    Not(usize),
+    Ref(usize),
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Bool(bool),
+    Keywords(Vec<(&'static str, Keyword)>),
+}
+
+impl Node {
+    // The subschemas that apply to the same instance as the node itself
+    fn in_place_subschemas(&self) -> Vec<usize> {
+        let Node::Keywords(keywords) = self else {
+            return Vec::new()
+        };
+        keywords.iter()
+            .flat_map(|(_, keyword)| match keyword {
+                Keyword::Ref(node) | Keyword::Not(node) => vec![*node],
+                Keyword::AllOf(nodes) | Keyword::AnyOf(nodes) | Keyword::OneOf(nodes) => nodes.clone(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Schema {
+    nodes: Vec<Node>,
+}
+
+pub(crate) fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape_pointer_token(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+fn resolve_pointer<'a>(value: &'a JSONValue, pointer: &str) -> Option<&'a JSONValue> {
+    if pointer.is_empty() {
+        return Some(value);
+    }
+    let mut value = value;
+    for token in pointer.strip_prefix('/')?.split('/') {
+        let token = unescape_pointer_token(token);
+        value = match value {
+            JSONValue::Object { data } => data.get(token.as_str())?,
+            JSONValue::Array { data } => data.get(token.parse::<usize>().ok()?)?,
+            _ => return None
+        };
+    }
+    Some(value)
+}
+
+fn find_anchors(value: &JSONValue, pointer: &str, anchors: &mut FxHashMap<String, String>) {
+    match value {
+        JSONValue::Object { data } => {
+            if let Some(JSONValue::String { string }) = data.get("$anchor") {
+                anchors.insert(string.to_string(), pointer.to_string());
+            }
+            for (key, value) in data {
+                find_anchors(value, &format!("{pointer}/{}", escape_pointer_token(key)), anchors);
+            }
+        }
+        JSONValue::Array { data } => {
+            for (i, value) in data.iter().enumerate() {
+                find_anchors(value, &format!("{pointer}/{i}"), anchors);
+            }
+        }
+        _ => {}
+    }
+}
+
+struct Compiler<'a> {
+    root: &'a JSONValue,
+    anchors: FxHashMap<String, String>,
+    compiled: FxHashMap<String, usize>,
+    nodes: Vec<Node>,
+}
+
+impl <'a> Compiler<'a> {
+    fn new(root: &'a JSONValue) -> Self {
+        let mut anchors = FxHashMap::default();
+        find_anchors(root, "", &mut anchors);
+        Compiler { root, anchors, compiled: FxHashMap::default(), nodes: Vec::new() }
+    }
+
+    fn invalid(pointer: &str, keyword: &str) -> SchemaError {
+        SchemaError::InvalidKeyword { path: pointer.to_string(), keyword: keyword.to_string() }
+    }
+
+    fn get_number(value: &JSONValue, pointer: &str, keyword: &str) -> Result<f64, SchemaError> {
+        match value {
+            JSONValue::Number { number } => Ok(*number),
+            _ => Err(Self::invalid(pointer, keyword))
+        }
+    }
+
+    fn get_count(value: &JSONValue, pointer: &str, keyword: &str) -> Result<usize, SchemaError> {
+        match value {
+            JSONValue::Number { number } if is_integer(*number) && *number >= 0.0 => Ok(*number as usize),
+            _ => Err(Self::invalid(pointer, keyword))
+        }
+    }
+
+    fn get_regex(value: &JSONValue, pointer: &str, keyword: &str) -> Result<Regex, SchemaError> {
+        let JSONValue::String { string } = value else {
+            return Err(Self::invalid(pointer, keyword))
+        };
+        Regex::new(string).or(Err(SchemaError::InvalidPattern { path: pointer.to_string(), pattern: string.to_string() }))
+    }
+
+    fn compile_array(&mut self, value: &'a JSONValue, pointer: &str, keyword: &str) -> Result<Vec<usize>, SchemaError> {
+        let JSONValue::Array { data } = value else {
+            return Err(Self::invalid(pointer, keyword))
+        };
+        data.iter()
+            .enumerate()
+            .map(|(i, value)| self.compile(value, &format!("{pointer}/{keyword}/{i}")))
+            .collect()
+    }
+
+    fn compile_map(&mut self, value: &'a JSONValue, pointer: &str, keyword: &str) -> Result<Vec<(&'a JSONKey, usize)>, SchemaError> {
+        let JSONValue::Object { data } = value else {
+            return Err(Self::invalid(pointer, keyword))
+        };
+        let mut entries: Vec<(&'a JSONKey, &'a JSONValue)> = data.iter().collect();
+        entries.sort_by_key(|(key, _)| *key);
+        entries.into_iter()
+            .map(|(key, value)| Ok((key, self.compile(value, &format!("{pointer}/{keyword}/{}", escape_pointer_token(key)))?)))
+            .collect()
+    }
+
+    // Validation would never terminate if a node could reach itself without moving on to a part of
+    // the instance, as in `{"$ref": "#"}`. Returns the pointer of a node on such a cycle.
+    fn find_cycle(&self) -> Option<String> {
+        // 0: not visited yet, 1: on the current path, 2: done
+        fn visit(nodes: &[Node], node: usize, states: &mut [u8]) -> Option<usize> {
+            states[node] = 1;
+            for next in nodes[node].in_place_subschemas() {
+                match states[next] {
+                    0 => if let Some(cycle) = visit(nodes, next, states) {
+                        return Some(cycle)
+                    },
+                    1 => return Some(node),
+                    _ => {}
+                }
+            }
+            states[node] = 2;
+            None
+        }
+        let mut states = vec![0; self.nodes.len()];
+        let node = (0..self.nodes.len()).find_map(|node| if states[node] == 0 { visit(&self.nodes, node, &mut states) } else { None })?;
+        self.compiled.iter().find(|(_, index)| **index == node).map(|(pointer, _)| pointer.clone())
+    }
+
+    fn resolve_reference(&self, reference: &str, pointer: &str) -> Result<(String, &'a JSONValue), SchemaError> {
+        let unresolved = || SchemaError::UnresolvedReference { path: pointer.to_string(), reference: reference.to_string() };
+        let fragment = reference.strip_prefix('#').ok_or_else(unresolved)?;
+        let target = if fragment.is_empty() || fragment.starts_with('/') {
+            fragment.to_string()
+        } else {
+            self.anchors.get(fragment).ok_or_else(unresolved)?.clone()
+        };
+        let value = resolve_pointer(self.root, &target).ok_or_else(unresolved)?;
+        Ok((target, value))
+    }
+
+    fn compile(&mut self, value: &'a JSONValue, pointer: &str) -> Result<usize, SchemaError> {
+        if let Some(index) = self.compiled.get(pointer) {
+            return Ok(*index);
+        }
+        let index = self.nodes.len();
+        // A placeholder is inserted first so recursive references terminate
+        self.nodes.push(Node::Bool(true));
+        self.compiled.insert(pointer.to_string(), index);
+        let node = match value {
+            JSONValue::True => Node::Bool(true),
+            JSONValue::False => Node::Bool(false),
+            JSONValue::Object { data } => Node::Keywords(self.compile_keywords(data, pointer)?),
+            _ => return Err(Self::invalid(pointer, ""))
+        };
+        self.nodes[index] = node;
+        Ok(index)
+    }
+
+    fn compile_keywords(&mut self, data: &'a FxHashMap<JSONKey, JSONValue>, pointer: &str) -> Result<Vec<(&'static str, Keyword)>, SchemaError> {
+        let mut keywords = Vec::new();
+        if let Some(value) = data.get("$ref") {
+            let JSONValue::String { string: reference } = value else {
+                return Err(Self::invalid(pointer, "$ref"))
+            };
+            let (target, value) = self.resolve_reference(reference, pointer)?;
+            keywords.push(("$ref", Keyword::Ref(self.compile(value, &target)?)));
+        }
+        if let Some(value) = data.get("type") {
+            let names = match value {
+                JSONValue::String { .. } => std::slice::from_ref(value),
+                JSONValue::Array { data } => data.as_slice(),
+                _ => return Err(Self::invalid(pointer, "type"))
+            };
+            let types = names.iter()
+                .map(|name| match name {
+                    JSONValue::String { string } => InstanceType::from_name(string).ok_or_else(|| Self::invalid(pointer, "type")),
+                    _ => Err(Self::invalid(pointer, "type"))
+                })
+                .collect::<Result<_, _>>()?;
+            keywords.push(("type", Keyword::Type(types)));
+        }
+        if let Some(value) = data.get("enum") {
+            let JSONValue::Array { data } = value else {
+                return Err(Self::invalid(pointer, "enum"))
+            };
+            keywords.push(("enum", Keyword::Enum(data.clone())));
+        }
+        if let Some(value) = data.get("const") {
+            keywords.push(("const", Keyword::Const(value.clone())));
+        }
+
+        let mut property_names = Vec::new();
+        let mut property_patterns = Vec::new();
+        if let Some(value) = data.get("properties") {
+            let properties: Vec<(String, usize)> = self.compile_map(value, pointer, "properties")?
+                .into_iter()
+                .map(|(key, index)| (key.to_string(), index))
+                .collect();
+            property_names = properties.iter().map(|(key, _)| key.clone()).collect();
+            keywords.push(("properties", Keyword::Properties(properties)));
+        }
+        if let Some(value) = data.get("patternProperties") {
+            let pattern_pointer = format!("{pointer}/patternProperties");
+            let properties = self.compile_map(value, pointer, "patternProperties")?
+                .into_iter()
+                .map(|(key, index)| Ok((Self::get_regex(&JSONValue::String { string: key.as_str().into() }, &pattern_pointer, "patternProperties")?, index)))
+                .collect::<Result<Vec<_>, _>>()?;
+            property_patterns = properties.iter().map(|(regex, _)| regex.clone()).collect();
+            keywords.push(("patternProperties", Keyword::PatternProperties(properties)));
+        }
+        if let Some(value) = data.get("additionalProperties") {
+            let schema = self.compile(value, &format!("{pointer}/additionalProperties"))?;
+            keywords.push(("additionalProperties", Keyword::AdditionalProperties { schema, properties: property_names, patterns: property_patterns }));
+        }
+        if let Some(value) = data.get("required") {
+            let JSONValue::Array { data } = value else {
+                return Err(Self::invalid(pointer, "required"))
+            };
+            let required = data.iter()
+                .map(|name| match name {
+                    JSONValue::String { string } => Ok(string.to_string()),
+                    _ => Err(Self::invalid(pointer, "required"))
+                })
+                .collect::<Result<_, _>>()?;
+            keywords.push(("required", Keyword::Required(required)));
+        }
+        if let Some(value) = data.get("minProperties") {
+            keywords.push(("minProperties", Keyword::MinProperties(Self::get_count(value, pointer, "minProperties")?)));
+        }
+        if let Some(value) = data.get("maxProperties") {
+            keywords.push(("maxProperties", Keyword::MaxProperties(Self::get_count(value, pointer, "maxProperties")?)));
+        }
+
+        let mut prefix_len = 0;
+        if let Some(value) = data.get("prefixItems") {
+            let items = self.compile_array(value, pointer, "prefixItems")?;
+            prefix_len = items.len();
+            keywords.push(("prefixItems", Keyword::PrefixItems(items)));
+        }
+        if let Some(value) = data.get("items") {
+            let schema = self.compile(value, &format!("{pointer}/items"))?;
+            keywords.push(("items", Keyword::Items { schema, prefix_len }));
+        }
+        if let Some(value) = data.get("contains") {
+            keywords.push(("contains", Keyword::Contains(self.compile(value, &format!("{pointer}/contains"))?)));
+        }
+        if let Some(value) = data.get("minItems") {
+            keywords.push(("minItems", Keyword::MinItems(Self::get_count(value, pointer, "minItems")?)));
+        }
+        if let Some(value) = data.get("maxItems") {
+            keywords.push(("maxItems", Keyword::MaxItems(Self::get_count(value, pointer, "maxItems")?)));
+        }
+        match data.get("uniqueItems") {
+            Some(JSONValue::True) => keywords.push(("uniqueItems", Keyword::UniqueItems)),
+            Some(JSONValue::False) | None => {}
+            Some(_) => return Err(Self::invalid(pointer, "uniqueItems"))
+        }
+
+        if let Some(value) = data.get("minimum") {
+            keywords.push(("minimum", Keyword::Minimum(Self::get_number(value, pointer, "minimum")?)));
+        }
+        if let Some(value) = data.get("maximum") {
+            keywords.push(("maximum", Keyword::Maximum(Self::get_number(value, pointer, "maximum")?)));
+        }
+        if let Some(value) = data.get("exclusiveMinimum") {
+            keywords.push(("exclusiveMinimum", Keyword::ExclusiveMinimum(Self::get_number(value, pointer, "exclusiveMinimum")?)));
+        }
+        if let Some(value) = data.get("exclusiveMaximum") {
+            keywords.push(("exclusiveMaximum", Keyword::ExclusiveMaximum(Self::get_number(value, pointer, "exclusiveMaximum")?)));
+        }
+        if let Some(value) = data.get("multipleOf") {
+            let multiple = Self::get_number(value, pointer, "multipleOf")?;
+            if multiple <= 0.0 {
+                return Err(Self::invalid(pointer, "multipleOf"));
+            }
+            keywords.push(("multipleOf", Keyword::MultipleOf(multiple)));
+        }
+
+        if let Some(value) = data.get("minLength") {
+            keywords.push(("minLength", Keyword::MinLength(Self::get_count(value, pointer, "minLength")?)));
+        }
+        if let Some(value) = data.get("maxLength") {
+            keywords.push(("maxLength", Keyword::MaxLength(Self::get_count(value, pointer, "maxLength")?)));
+        }
+        if let Some(value) = data.get("pattern") {
+            keywords.push(("pattern", Keyword::Pattern(Self::get_regex(value, pointer, "pattern")?)));
+        }
+
+        if let Some(value) = data.get("allOf") {
+            keywords.push(("allOf", Keyword::AllOf(self.compile_array(value, pointer, "allOf")?)));
+        }
+        if let Some(value) = data.get("anyOf") {
+            keywords.push(("anyOf", Keyword::AnyOf(self.compile_array(value, pointer, "anyOf")?)));
+        }
+        if let Some(value) = data.get("oneOf") {
+            keywords.push(("oneOf", Keyword::OneOf(self.compile_array(value, pointer, "oneOf")?)));
+        }
+        if let Some(value) = data.get("not") {
+            keywords.push(("not", Keyword::Not(self.compile(value, &format!("{pointer}/not"))?)));
+        }
+        Ok(keywords)
+    }
+}
+
+struct Validator<'a> {
+    nodes: &'a [Node],
+    instance_path: Vec<String>,
+    schema_path: Vec<String>,
+    errors: Vec<ValidationError>,
+}
+
+impl <'a> Validator<'a> {
+    fn new(nodes: &'a [Node]) -> Self {
+        Validator { nodes, instance_path: Vec::new(), schema_path: Vec::new(), errors: Vec::new() }
+    }
+
+    fn error(&mut self, message: String) {
+        let to_pointer = |tokens: &[String]| tokens.iter().map(|token| format!("/{}", escape_pointer_token(token))).collect();
+        self.errors.push(ValidationError {
+            instance_path: to_pointer(&self.instance_path),
+            schema_path: to_pointer(&self.schema_path),
+            message,
+        });
+    }
+
+    // Validates against a subschema reached through the given schema path segment
+    fn validate_at(&mut self, node: usize, instance: &JSONValue, schema_segment: String) -> bool {
+        self.schema_path.push(schema_segment);
+        let valid = self.validate(node, instance);
+        self.schema_path.pop();
+        valid
+    }
+
+    fn validate_child(&mut self, node: usize, instance: &JSONValue, schema_segment: String, instance_segment: String) -> bool {
+        self.instance_path.push(instance_segment);
+        let valid = self.validate_at(node, instance, schema_segment);
+        self.instance_path.pop();
+        valid
+    }
+
+    // Validates without recording any errors
+    fn check(&mut self, node: usize, instance: &JSONValue, schema_segment: String) -> bool {
+        let error_count = self.errors.len();
+        let valid = self.validate_at(node, instance, schema_segment);
+        self.errors.truncate(error_count);
+        valid
+    }
+
+    fn validate(&mut self, node: usize, instance: &JSONValue) -> bool {
+        let nodes = self.nodes;
+        let keywords = match &nodes[node] {
+            Node::Bool(true) => return true,
+            Node::Bool(false) => {
+                self.error("no value is allowed here".to_string());
+                return false
+            }
+            Node::Keywords(keywords) => keywords,
+        };
+        let error_count = self.errors.len();
+        for (name, keyword) in keywords {
+            self.schema_path.push(name.to_string());
+            self.validate_keyword(keyword, instance);
+            self.schema_path.pop();
+        }
+        self.errors.len() == error_count
+    }
+
+    fn validate_keyword(&mut self, keyword: &Keyword, instance: &JSONValue) {
+        match (keyword, instance) {
+            (Keyword::Type(types), _) => {
+                if !types.iter().any(|t| t.matches(instance)) {
+                    let expected: Vec<&str> = types.iter().map(InstanceType::name).collect();
+                    self.error(format!("expected {}, found {}", expected.join(" or "), InstanceType::of(instance).name()));
+                }
+            }
+            (Keyword::Enum(values), _) => {
+                if !values.contains(instance) {
+                    self.error("value is not one of the allowed values".to_string());
+                }
+            }
+            (Keyword::Const(value), _) => {
+                if value != instance {
+                    self.error("value is not equal to the constant".to_string());
+                }
+            }
+            (Keyword::Properties(properties), JSONValue::Object { data }) => {
+                for (key, node) in properties {
+                    if let Some(value) = data.get(key.as_str()) {
+                        self.validate_child(*node, value, key.clone(), key.clone());
+                    }
+                }
+            }
+            (Keyword::PatternProperties(patterns), JSONValue::Object { data }) => {
+                let mut keys: Vec<&JSONKey> = data.keys().collect();
+                keys.sort();
+                for (regex, node) in patterns {
+                    for key in keys.iter().filter(|key| regex.is_match(key)) {
+                        self.validate_child(*node, &data[*key], regex.as_str().to_string(), key.to_string());
+                    }
+                }
+            }
+            (Keyword::AdditionalProperties { schema, properties, patterns }, JSONValue::Object { data }) => {
+                let mut keys: Vec<&JSONKey> = data.keys()
+                    .filter(|key| !properties.iter().any(|property| property.as_str() == &***key) && !patterns.iter().any(|regex| regex.is_match(key)))
+                    .collect();
+                keys.sort();
+                for key in keys {
+                    self.instance_path.push(key.to_string());
+                    self.validate(*schema, &data[key]);
+                    self.instance_path.pop();
+                }
+            }
+            (Keyword::Required(required), JSONValue::Object { data }) => {
+                for key in required {
+                    if !data.contains_key(key.as_str()) {
+                        self.error(format!("missing required property {key:?}"));
+                    }
+                }
+            }
+            (Keyword::MinProperties(min), JSONValue::Object { data }) => {
+                if data.len() < *min {
+                    self.error(format!("expected at least {min} properties, found {}", data.len()));
+                }
+            }
+            (Keyword::MaxProperties(max), JSONValue::Object { data }) => {
+                if data.len() > *max {
+                    self.error(format!("expected at most {max} properties, found {}", data.len()));
+                }
+            }
+            (Keyword::PrefixItems(items), JSONValue::Array { data }) => {
+                for (i, (node, value)) in items.iter().zip(data).enumerate() {
+                    self.validate_child(*node, value, i.to_string(), i.to_string());
+                }
+            }
+            (Keyword::Items { schema, prefix_len }, JSONValue::Array { data }) => {
+                for (i, value) in data.iter().enumerate().skip(*prefix_len) {
+                    self.instance_path.push(i.to_string());
+                    self.validate(*schema, value);
+                    self.instance_path.pop();
+                }
+            }
+            (Keyword::Contains(node), JSONValue::Array { data }) => {
+                let mut found = false;
+                for value in data {
+                    let error_count = self.errors.len();
+                    found |= self.validate(*node, value);
+                    self.errors.truncate(error_count);
+                }
+                if !found {
+                    self.error("no item matches the contains schema".to_string());
+                }
+            }
+            (Keyword::MinItems(min), JSONValue::Array { data }) => {
+                if data.len() < *min {
+                    self.error(format!("expected at least {min} items, found {}", data.len()));
+                }
+            }
+            (Keyword::MaxItems(max), JSONValue::Array { data }) => {
+                if data.len() > *max {
+                    self.error(format!("expected at most {max} items, found {}", data.len()));
+                }
+            }
+            (Keyword::UniqueItems, JSONValue::Array { data }) => {
+                for (i, value) in data.iter().enumerate() {
+                    if let Some(j) = data[..i].iter().position(|other| other == value) {
+                        self.error(format!("items {j} and {i} are equal"));
+                        break;
+                    }
+                }
+            }
+            (Keyword::Minimum(min), JSONValue::Number { number }) => {
+                if number < min {
+                    self.error(format!("{number} is less than the minimum of {min}"));
+                }
+            }
+            (Keyword::Maximum(max), JSONValue::Number { number }) => {
+                if number > max {
+                    self.error(format!("{number} is greater than the maximum of {max}"));
+                }
+            }
+            (Keyword::ExclusiveMinimum(min), JSONValue::Number { number }) => {
+                if number <= min {
+                    self.error(format!("{number} is not greater than the exclusive minimum of {min}"));
+                }
+            }
+            (Keyword::ExclusiveMaximum(max), JSONValue::Number { number }) => {
+                if number >= max {
+                    self.error(format!("{number} is not less than the exclusive maximum of {max}"));
+                }
+            }
+            (Keyword::MultipleOf(multiple), JSONValue::Number { number }) => {
+                let quotient = number / multiple;
+                // Allow for rounding errors such as 0.3 / 0.1 = 2.9999999999999996
+                if (quotient - quotient.round()).abs() > 1e-9 * quotient.abs().max(1.0) {
+                    self.error(format!("{number} is not a multiple of {multiple}"));
+                }
+            }
+            (Keyword::MinLength(min), JSONValue::String { string }) => {
+                let length = string.chars().count();
+                if length < *min {
+                    self.error(format!("expected at least {min} characters, found {length}"));
+                }
+            }
+            (Keyword::MaxLength(max), JSONValue::String { string }) => {
+                let length = string.chars().count();
+                if length > *max {
+                    self.error(format!("expected at most {max} characters, found {length}"));
+                }
+            }
+            (Keyword::Pattern(regex), JSONValue::String { string }) => {
+                if !regex.is_match(string) {
+                    self.error(format!("{string:?} does not match the pattern {:?}", regex.as_str()));
+                }
+            }
+            (Keyword::AllOf(nodes), _) => {
+                for (i, node) in nodes.iter().enumerate() {
+                    self.validate_at(*node, instance, i.to_string());
+                }
+            }
+            (Keyword::AnyOf(nodes), _) => {
+                let mut matched = false;
+                for (i, node) in nodes.iter().enumerate() {
+                    if self.check(*node, instance, i.to_string()) {
+                        matched = true;
+                        break;
+                    }
+                }
+                if !matched {
+                    self.error("value does not match any of the schemas".to_string());
+                }
+            }
+            (Keyword::OneOf(nodes), _) => {
+                let mut matches = 0;
+                for (i, node) in nodes.iter().enumerate() {
+                    if self.check(*node, instance, i.to_string()) {
+                        matches += 1;
+                    }
+                }
+                if matches != 1 {
+                    self.error(format!("value matches {matches} of the schemas instead of exactly one"));
+                }
+            }
+            (Keyword::Not(node), _) => {
+                let error_count = self.errors.len();
+                let valid = self.validate(*node, instance);
+                self.errors.truncate(error_count);
+                if valid {
+                    self.error("value matches a schema it must not match".to_string());
+                }
+            }
+            (Keyword::Ref(node), _) => {
+                self.validate(*node, instance);
+            }
+            // Keywords which do not apply to the type of the instance are ignored
+            (_, _) => {}
+        }
+    }
+}
+
+impl Schema {
+    pub fn compile(schema: &JSONValue) -> Result<Schema, SchemaError> {
+        let mut compiler = Compiler::new(schema);
+        compiler.compile(schema, "")?;
+        if let Some(path) = compiler.find_cycle() {
+            return Err(SchemaError::ReferenceCycle { path })
+        }
+        Ok(Schema { nodes: compiler.nodes })
+    }
+
+    pub fn validate(&self, instance: &JSONValue) -> Result<(), Vec<ValidationError>> {
+        let mut validator = Validator::new(&self.nodes);
+        if validator.validate(0, instance) {
+            Ok(())
+        } else {
+            Err(validator.errors)
+        }
+    }
+
+    pub fn is_valid(&self, instance: &JSONValue) -> bool {
+        self.validate(instance).is_ok()
+    }
+}
@@ -0,0 +1,666 @@
+use std::fmt;
+use ::serde::de::{self, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor};
+use ::serde::ser::{self, Impossible, Serialize};
+use ::serde::{forward_to_deserialize_any, Deserialize};
+use rustc_hash::FxHashMap;
+use crate::byte_parser::ByteParser;
+use crate::stringify::{stringify_number, stringify_string};
+use crate::types::{JSONValue, ParseError};
+use crate::types::ParseError::Unknown;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    Parse(ParseError),
+    Message(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(err) => write!(f, "invalid JSON: {err:?}"),
+            Error::Message(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Error::Parse(err)
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+pub struct Deserializer<'de> {
+    parser: ByteParser<'de>,
+}
+
+impl <'de> Deserializer<'de> {
+    pub fn from_bytes(bytes: &'de [u8]) -> Self {
+        Deserializer { parser: ByteParser::new(bytes) }
+    }
+
+    // Checks that only whitespace remains after the deserialized value
+    pub fn end(&mut self) -> Result<(), Error> {
+        self.parser.consume_whitespace();
+        if self.parser.head < self.parser.data.len() {
+            Err(Error::Parse(Unknown))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn peek(&mut self) -> Result<u8, Error> {
+        self.parser.consume_whitespace();
+        Ok(*self.parser.data.get(self.parser.head).ok_or(Unknown)?)
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), Error> {
+        self.parser.consume_whitespace();
+        self.parser.assert_next_byte(byte)?;
+        self.parser.consume_whitespace();
+        Ok(())
+    }
+
+    fn parse_string(&mut self) -> Result<String, Error> {
+        self.expect(0x22)?; // "
+        Ok(self.parser.parse_string()?)
+    }
+
+    fn parse_number(&mut self) -> Result<f64, Error> {
+        self.parser.head += 1;
+        let number = self.parser.parse_number()?;
+        self.parser.consume_whitespace();
+        Ok(number)
+    }
+}
+
+pub fn from_bytes<'de, T: Deserialize<'de>>(bytes: &'de [u8]) -> Result<T, Error> {
+    let mut deserializer = Deserializer::from_bytes(bytes);
+    let value = T::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(value)
+}
+
+pub fn from_str<'de, T: Deserialize<'de>>(string: &'de str) -> Result<T, Error> {
+    from_bytes(string.as_bytes())
+}
+
+impl <'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.peek()? {
+            0x6e => { // n
+                self.parser.head += 1;
+                self.parser.parse_null()?;
+                visitor.visit_unit()
+            }
+            0x74 => { // t
+                self.parser.head += 1;
+                self.parser.parse_true()?;
+                visitor.visit_bool(true)
+            }
+            0x66 => { // f
+                self.parser.head += 1;
+                self.parser.parse_false()?;
+                visitor.visit_bool(false)
+            }
+            0x22 => visitor.visit_string(self.parse_string()?), // "
+            0x2d | 0x30..=0x39 => {
+                let number = self.parse_number()?;
+                // Integers are reported as such so that they can be deserialized into integer types
+                if number.fract() == 0.0 && number >= 0.0 && number < u64::MAX as f64 {
+                    visitor.visit_u64(number as u64)
+                } else if number.fract() == 0.0 && number >= i64::MIN as f64 && number < 0.0 {
+                    visitor.visit_i64(number as i64)
+                } else {
+                    visitor.visit_f64(number)
+                }
+            }
+            0x5b => { // [
+                self.expect(0x5b)?;
+                let value = visitor.visit_seq(Sequence { de: self, first: true })?;
+                self.expect(0x5d)?; // ]
+                Ok(value)
+            }
+            0x7b => { // {
+                self.expect(0x7b)?;
+                let value = visitor.visit_map(Map { de: self, first: true })?;
+                self.expect(0x7d)?; // }
+                Ok(value)
+            }
+            _ => Err(Error::Parse(Unknown))
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.peek()? == 0x6e {
+            self.parser.head += 1;
+            self.parser.parse_null()?;
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str, _variants: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        match self.peek()? {
+            0x22 => visitor.visit_enum(self.parse_string()?.into_deserializer()),
+            0x7b => {
+                self.expect(0x7b)?;
+                let value = visitor.visit_enum(Enum { de: self })?;
+                self.expect(0x7d)?;
+                Ok(value)
+            }
+            _ => Err(de::Error::custom("expected an enum"))
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct Sequence<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    first: bool,
+}
+
+impl <'de> SeqAccess<'de> for Sequence<'_, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+        if self.de.peek()? == 0x5d {
+            return Ok(None);
+        }
+        if !self.first {
+            self.de.expect(0x2c)?; // ,
+        }
+        self.first = false;
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+}
+
+struct Map<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+    first: bool,
+}
+
+impl <'de> MapAccess<'de> for Map<'_, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+        if self.de.peek()? == 0x7d {
+            return Ok(None);
+        }
+        if !self.first {
+            self.de.expect(0x2c)?; // ,
+        }
+        self.first = false;
+        let key: String = self.de.parse_string()?;
+        seed.deserialize(IntoDeserializer::<Error>::into_deserializer(key)).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        self.de.expect(0x3a)?; // :
+        seed.deserialize(&mut *self.de)
+    }
+}
+
+struct Enum<'a, 'de> {
+    de: &'a mut Deserializer<'de>,
+}
+
+impl <'de> de::EnumAccess<'de> for Enum<'_, 'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let variant: String = self.de.parse_string()?;
+        let value = seed.deserialize(IntoDeserializer::<Error>::into_deserializer(variant))?;
+        self.de.expect(0x3a)?; // :
+        Ok((value, self))
+    }
+}
+
+impl <'de> VariantAccess<'de> for Enum<'_, 'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        de::Deserialize::deserialize(self.de)
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self.de)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self.de, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(self.de, visitor)
+    }
+}
+
+pub struct Serializer {
+    output: String,
+}
+
+pub fn to_string<T: Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+    let mut serializer = Serializer { output: String::new() };
+    value.serialize(&mut serializer)?;
+    Ok(serializer.output)
+}
+
+impl Serializer {
+    fn push_separator(&mut self, first: &mut bool) {
+        if *first {
+            *first = false;
+        } else {
+            self.output.push(',');
+        }
+    }
+}
+
+impl <'a> ser::Serializer for &'a mut Serializer {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.output.push_str(if v { "true" } else { "false" });
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> { self.serialize_i64(v as i64) }
+    fn serialize_i16(self, v: i16) -> Result<(), Error> { self.serialize_i64(v as i64) }
+    fn serialize_i32(self, v: i32) -> Result<(), Error> { self.serialize_i64(v as i64) }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        self.output.push_str(&v.to_string());
+        Ok(())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> { self.serialize_u64(v as u64) }
+    fn serialize_u16(self, v: u16) -> Result<(), Error> { self.serialize_u64(v as u64) }
+    fn serialize_u32(self, v: u32) -> Result<(), Error> { self.serialize_u64(v as u64) }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.output.push_str(&v.to_string());
+        Ok(())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        self.serialize_f64(v as f64)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        // JSON cannot represent NaN or the infinities
+        if v.is_finite() {
+            stringify_number(&v, &mut self.output);
+        } else {
+            self.output.push_str("null");
+        }
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        stringify_string(v.encode_utf8(&mut [0; 4]), &mut self.output);
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        stringify_string(v, &mut self.output);
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        use ser::SerializeSeq;
+        let mut seq = self.serialize_seq(Some(v.len()))?;
+        for byte in v {
+            seq.serialize_element(byte)?;
+        }
+        seq.end()
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.output.push_str("null");
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<(), Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, _variant_index: u32, variant: &'static str, value: &T) -> Result<(), Error> {
+        self.output.push('{');
+        stringify_string(variant, &mut self.output);
+        self.output.push(':');
+        value.serialize(&mut *self)?;
+        self.output.push('}');
+        Ok(())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Compound<'a>, Error> {
+        self.output.push('[');
+        Ok(Compound { ser: self, first: true, close: "]" })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Compound<'a>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<Compound<'a>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, _len: usize) -> Result<Compound<'a>, Error> {
+        self.output.push('{');
+        stringify_string(variant, &mut self.output);
+        self.output.push_str(":[");
+        Ok(Compound { ser: self, first: true, close: "]}" })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Compound<'a>, Error> {
+        self.output.push('{');
+        Ok(Compound { ser: self, first: true, close: "}" })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Compound<'a>, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str, _len: usize) -> Result<Compound<'a>, Error> {
+        self.output.push('{');
+        stringify_string(variant, &mut self.output);
+        self.output.push_str(":{");
+        Ok(Compound { ser: self, first: true, close: "}}" })
+    }
+}
+
+pub struct Compound<'a> {
+    ser: &'a mut Serializer,
+    first: bool,
+    close: &'static str,
+}
+
+impl Compound<'_> {
+    fn element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.ser.push_separator(&mut self.first);
+        value.serialize(&mut *self.ser)
+    }
+
+    fn member<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), Error> {
+        self.ser.push_separator(&mut self.first);
+        stringify_string(key, &mut self.ser.output);
+        self.ser.output.push(':');
+        value.serialize(&mut *self.ser)
+    }
+
+    fn close(self) -> Result<(), Error> {
+        self.ser.output.push_str(self.close);
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> { self.element(value) }
+    fn end(self) -> Result<(), Error> { self.close() }
+}
+
+impl ser::SerializeTuple for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> { self.element(value) }
+    fn end(self) -> Result<(), Error> { self.close() }
+}
+
+impl ser::SerializeTupleStruct for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> { self.element(value) }
+    fn end(self) -> Result<(), Error> { self.close() }
+}
+
+impl ser::SerializeTupleVariant for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> { self.element(value) }
+    fn end(self) -> Result<(), Error> { self.close() }
+}
+
+impl ser::SerializeMap for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        self.ser.push_separator(&mut self.first);
+        key.serialize(MapKeySerializer { ser: &mut *self.ser })
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.ser.output.push(':');
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> { self.close() }
+}
+
+impl ser::SerializeStruct for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> { self.member(key, value) }
+    fn end(self) -> Result<(), Error> { self.close() }
+}
+
+impl ser::SerializeStructVariant for Compound<'_> {
+    type Ok = ();
+    type Error = Error;
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), Error> { self.member(key, value) }
+    fn end(self) -> Result<(), Error> { self.close() }
+}
+
+// Object keys must be strings, so only types which have an obvious string form are accepted
+struct MapKeySerializer<'a> {
+    ser: &'a mut Serializer,
+}
+
+impl MapKeySerializer<'_> {
+    fn key(self, key: &str) -> Result<(), Error> {
+        stringify_string(key, &mut self.ser.output);
+        Ok(())
+    }
+
+    fn unsupported() -> Error {
+        Error::Message("object keys must be strings".to_string())
+    }
+}
+
+impl ser::Serializer for MapKeySerializer<'_> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Impossible<(), Error>;
+    type SerializeTuple = Impossible<(), Error>;
+    type SerializeTupleStruct = Impossible<(), Error>;
+    type SerializeTupleVariant = Impossible<(), Error>;
+    type SerializeMap = Impossible<(), Error>;
+    type SerializeStruct = Impossible<(), Error>;
+    type SerializeStructVariant = Impossible<(), Error>;
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> { self.key(v) }
+    fn serialize_char(self, v: char) -> Result<(), Error> { self.key(v.encode_utf8(&mut [0; 4])) }
+    fn serialize_bool(self, v: bool) -> Result<(), Error> { self.key(if v { "true" } else { "false" }) }
+    fn serialize_i8(self, v: i8) -> Result<(), Error> { self.key(&v.to_string()) }
+    fn serialize_i16(self, v: i16) -> Result<(), Error> { self.key(&v.to_string()) }
+    fn serialize_i32(self, v: i32) -> Result<(), Error> { self.key(&v.to_string()) }
+    fn serialize_i64(self, v: i64) -> Result<(), Error> { self.key(&v.to_string()) }
+    fn serialize_u8(self, v: u8) -> Result<(), Error> { self.key(&v.to_string()) }
+    fn serialize_u16(self, v: u16) -> Result<(), Error> { self.key(&v.to_string()) }
+    fn serialize_u32(self, v: u32) -> Result<(), Error> { self.key(&v.to_string()) }
+    fn serialize_u64(self, v: u64) -> Result<(), Error> { self.key(&v.to_string()) }
+    fn serialize_f32(self, _v: f32) -> Result<(), Error> { Err(Self::unsupported()) }
+    fn serialize_f64(self, _v: f64) -> Result<(), Error> { Err(Self::unsupported()) }
+    fn serialize_bytes(self, _v: &[u8]) -> Result<(), Error> { Err(Self::unsupported()) }
+    fn serialize_none(self) -> Result<(), Error> { Err(Self::unsupported()) }
+    fn serialize_some<T: Serialize + ?Sized>(self, _value: &T) -> Result<(), Error> { Err(Self::unsupported()) }
+    fn serialize_unit(self) -> Result<(), Error> { Err(Self::unsupported()) }
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> { Err(Self::unsupported()) }
+
+    fn serialize_unit_variant(self, _name: &'static str, _variant_index: u32, variant: &'static str) -> Result<(), Error> {
+        self.key(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _name: &'static str, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _value: &T) -> Result<(), Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Error> { Err(Self::unsupported()) }
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Error> { Err(Self::unsupported()) }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeTupleStruct, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeTupleVariant, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Error> { Err(Self::unsupported()) }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct, Error> {
+        Err(Self::unsupported())
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, _variant_index: u32, _variant: &'static str, _len: usize) -> Result<Self::SerializeStructVariant, Error> {
+        Err(Self::unsupported())
+    }
+}
+
+impl Serialize for JSONValue {
+    fn serialize<S: ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            JSONValue::False => serializer.serialize_bool(false),
+            JSONValue::True => serializer.serialize_bool(true),
+            JSONValue::Null => serializer.serialize_unit(),
+            JSONValue::Object { data } => serializer.collect_map(data.iter().map(|(key, value)| (&**key, value))),
+            JSONValue::Array { data } => serializer.collect_seq(data),
+            JSONValue::Number { number } => serializer.serialize_f64(*number),
+            JSONValue::String { string } => serializer.serialize_str(string),
+        }
+    }
+}
+
+struct JSONValueVisitor;
+
+impl <'de> Visitor<'de> for JSONValueVisitor {
+    type Value = JSONValue;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any JSON value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<JSONValue, E> {
+        Ok(if v { JSONValue::True } else { JSONValue::False })
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<JSONValue, E> {
+        Ok(JSONValue::Number { number: v as f64 })
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<JSONValue, E> {
+        Ok(JSONValue::Number { number: v as f64 })
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<JSONValue, E> {
+        Ok(JSONValue::Number { number: v })
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<JSONValue, E> {
+        Ok(JSONValue::String { string: v.into() })
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<JSONValue, E> {
+        Ok(JSONValue::String { string: v.into() })
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<JSONValue, E> {
+        Ok(JSONValue::Null)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<JSONValue, E> {
+        Ok(JSONValue::Null)
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<JSONValue, D::Error> {
+        Deserialize::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<JSONValue, A::Error> {
+        let mut data = Vec::new();
+        while let Some(value) = seq.next_element()? {
+            data.push(value);
+        }
+        Ok(JSONValue::Array { data })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<JSONValue, A::Error> {
+        let mut data = FxHashMap::default();
+        while let Some((key, value)) = map.next_entry::<String, JSONValue>()? {
+            data.insert(key.into(), value);
+        }
+        Ok(JSONValue::Object { data })
+    }
+}
+
+impl <'de> Deserialize<'de> for JSONValue {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<JSONValue, D::Error> {
+        deserializer.deserialize_any(JSONValueVisitor)
+    }
+}
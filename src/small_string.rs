@@ -0,0 +1,183 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::Arc;
+
+// Strings of up to this many bytes are stored inline. 22 bytes of text, one for the length and one
+// for the variant make 24, the size of `String`, so object members are no bigger than with `String`
+// keys. That covers every key in citm_catalog.json, 86% of the keys in twitter.json and about 60% of
+// the string values in both; 30 bytes would add 8 to every member to inline 13% more twitter keys.
+pub const INLINE_CAPACITY: usize = 22;
+
+// An immutable string used for string values and object keys. Short strings live inline and need
+// no allocation, longer ones are reference counted so that clones (and interned keys) share storage.
+#[derive(Clone)]
+pub struct SmallString(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    Inline { len: u8, bytes: [u8; INLINE_CAPACITY] },
+    Heap(Arc<str>),
+}
+
+impl SmallString {
+    pub fn new() -> Self {
+        SmallString(Repr::Inline { len: 0, bytes: [0; INLINE_CAPACITY] })
+    }
+
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            // SAFETY: inline bytes are always copied from a str, and `len` never splits a character
+            Repr::Inline { len, bytes } => unsafe { std::str::from_utf8_unchecked(&bytes[..*len as usize]) },
+            Repr::Heap(string) => string,
+        }
+    }
+
+    pub fn is_inline(&self) -> bool {
+        matches!(self.0, Repr::Inline { .. })
+    }
+}
+
+impl Default for SmallString {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for SmallString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SmallString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+// Lets maps keyed by `SmallString` be queried with a plain `&str`
+impl Borrow<str> for SmallString {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for SmallString {
+    fn from(string: &str) -> Self {
+        if string.len() <= INLINE_CAPACITY {
+            let mut bytes = [0; INLINE_CAPACITY];
+            bytes[..string.len()].copy_from_slice(string.as_bytes());
+            SmallString(Repr::Inline { len: string.len() as u8, bytes })
+        } else {
+            SmallString(Repr::Heap(string.into()))
+        }
+    }
+}
+
+impl From<&String> for SmallString {
+    fn from(string: &String) -> Self {
+        string.as_str().into()
+    }
+}
+
+impl From<String> for SmallString {
+    fn from(string: String) -> Self {
+        if string.len() <= INLINE_CAPACITY {
+            string.as_str().into()
+        } else {
+            SmallString(Repr::Heap(string.into()))
+        }
+    }
+}
+
+impl From<Arc<str>> for SmallString {
+    fn from(string: Arc<str>) -> Self {
+        SmallString(Repr::Heap(string))
+    }
+}
+
+impl From<SmallString> for String {
+    fn from(string: SmallString) -> Self {
+        string.as_str().to_string()
+    }
+}
+
+impl PartialEq for SmallString {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SmallString {}
+
+impl PartialEq<str> for SmallString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SmallString {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for SmallString {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<SmallString> for str {
+    fn eq(&self, other: &SmallString) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<SmallString> for &str {
+    fn eq(&self, other: &SmallString) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialEq<SmallString> for String {
+    fn eq(&self, other: &SmallString) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialOrd for SmallString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SmallString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+// Has to match the hash of `str` for `Borrow<str>` lookups to work
+impl Hash for SmallString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl fmt::Debug for SmallString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for SmallString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
@@ -0,0 +1,146 @@
+use std::ops::Range;
+use rustc_hash::FxHashMap;
+use crate::cst::{parse_document, List, Node};
+use crate::json5;
+use crate::schema::escape_pointer_token;
+use crate::types::{Dialect, JSONValue, ParseError};
+
+// A line and column, both counted from 1. Columns count characters, not bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    // The position of a byte offset into `text`. Offsets inside a character are that character's
+    // position, and offsets past the end are the end of `text`.
+    pub fn of(text: &str, offset: usize) -> Position {
+        Lines::new(text).position(offset)
+    }
+}
+
+// The offsets at which the lines of a text start. Lines end at any JSON5 line terminator, with
+// "\r\n" counted as one.
+struct Lines<'a> {
+    text: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut starts = vec![0];
+        let mut chars = text.char_indices().peekable();
+        while let Some((i, char)) = chars.next() {
+            if char == '\r' && chars.peek().is_some_and(|&(_, next)| next == '\n') {
+                continue
+            }
+            if json5::is_line_terminator(char) {
+                starts.push(i + char.len_utf8());
+            }
+        }
+        Lines { text, starts }
+    }
+
+    fn position(&self, offset: usize) -> Position {
+        let offset = self.text.floor_char_boundary(offset);
+        let line = self.starts.partition_point(|&start| start <= offset);
+        let line_start = self.starts[line - 1];
+        Position { line, column: self.text[line_start..offset].chars().count() + 1 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub bytes: Range<usize>,
+    pub start: Position,
+    pub end: Position, // Just past the value
+}
+
+// Where each value of a document was written, keyed by JSON Pointer. The pointers are the ones
+// `Schema::validate` reports as `instance_path`, so validation errors can be traced to the source.
+#[derive(Debug, Clone, Default)]
+pub struct SourceMap {
+    spans: FxHashMap<String, Span>,
+}
+
+impl SourceMap {
+    pub fn get(&self, pointer: &str) -> Option<&Span> {
+        self.spans.get(pointer)
+    }
+
+    pub fn len(&self) -> usize {
+        self.spans.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Span)> {
+        self.spans.iter().map(|(pointer, span)| (pointer.as_str(), span))
+    }
+}
+
+// Parses `text` along with the span of every value in it. With duplicate keys the span is that of
+// the member that ends up in the value.
+pub fn parse_with_spans(text: &str, dialect: Dialect) -> Result<(JSONValue, SourceMap), ParseError> {
+    let document = parse_document(text, dialect)?;
+    let mut mapper = Mapper { lines: Lines::new(text), spans: FxHashMap::default() };
+    mapper.node(&document.root, document.before.len(), &mut String::new());
+    Ok((document.value(), SourceMap { spans: mapper.spans }))
+}
+
+struct Mapper<'a> {
+    lines: Lines<'a>,
+    spans: FxHashMap<String, Span>,
+}
+
+impl Mapper<'_> {
+    // Records the node starting at `offset` and everything in it, and returns where it ends
+    fn node(&mut self, node: &Node, offset: usize, pointer: &mut String) -> usize {
+        let end = match node {
+            Node::Scalar { raw } => offset + raw.len(),
+            Node::Array(list) => self.list(list, offset, pointer, |mapper, element, offset, i, pointer| {
+                mapper.child(&i.to_string(), element, offset, pointer)
+            }),
+            Node::Object(list) => {
+                let last: FxHashMap<&str, usize> = list.items.iter().enumerate().map(|(i, item)| (item.item.key.as_str(), i)).collect();
+                self.list(list, offset, pointer, |mapper, member, offset, i, pointer| {
+                    let offset = offset + member.raw_key.len() + member.before_colon.len() + 1 + member.after_colon.len();
+                    // A member overridden by a later one with the same key is skipped over
+                    if last[member.key.as_str()] != i {
+                        let mut text = String::new();
+                        member.value.write(&mut text);
+                        return offset + text.len()
+                    }
+                    mapper.child(&escape_pointer_token(&member.key), &member.value, offset, pointer)
+                })
+            }
+        };
+        let span = Span { bytes: offset..end, start: self.lines.position(offset), end: self.lines.position(end) };
+        self.spans.insert(pointer.clone(), span);
+        end
+    }
+
+    fn child(&mut self, token: &str, node: &Node, offset: usize, pointer: &mut String) -> usize {
+        let len = pointer.len();
+        pointer.push('/');
+        pointer.push_str(token);
+        let end = self.node(node, offset, pointer);
+        pointer.truncate(len);
+        end
+    }
+
+    fn list<T>(&mut self, list: &List<T>, offset: usize, pointer: &mut String, mut item_end: impl FnMut(&mut Self, &T, usize, usize, &mut String) -> usize) -> usize {
+        let mut offset = offset + 1;
+        for (i, item) in list.items.iter().enumerate() {
+            offset = item_end(self, &item.item, offset + item.before.len(), i, pointer) + item.after.len();
+            if i + 1 < list.items.len() || list.trailing_comma {
+                offset += 1;
+            }
+            offset += item.trailing.len();
+        }
+        offset + list.end.len() + 1
+    }
+}
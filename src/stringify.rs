@@ -1,12 +1,17 @@
+use std::fmt;
 use rustc_hash::FxHashMap;
-use crate::types::{JSONValue};
+use crate::types::{JSONKey, JSONValue};
 
-fn stringify_number(number: &f64, out_string: &mut String) {
-    // JSON numbers should never have NaNs or Infinities in them
-    out_string.push_str(&ryu::Buffer::new().format_finite(*number));
+pub(crate) fn stringify_number(number: &f64, out_string: &mut String) {
+    // JSON cannot represent the NaNs and Infinities that JSON5 allows, so they are written as null
+    if number.is_finite() {
+        out_string.push_str(ryu::Buffer::new().format_finite(*number));
+    } else {
+        out_string.push_str("null");
+    }
 }
 
-fn stringify_string(string: &String, out_string: &mut String) {
+pub(crate) fn stringify_string(string: &str, out_string: &mut String) {
     out_string.push('"');
     for char in string.chars() {
         match char {
@@ -50,7 +55,7 @@ fn stringify_string(string: &String, out_string: &mut String) {
     out_string.push('"');
 }
 
-fn stringify_object(object: &FxHashMap<String, JSONValue>, out_string: &mut String) {
+fn stringify_object(object: &FxHashMap<JSONKey, JSONValue>, out_string: &mut String) {
     out_string.push('{');
     let mut is_first = true;
     for (key, value) in object {
@@ -92,8 +97,73 @@ fn stringify_internal(value: &JSONValue, out_string: &mut String) {
     }
 }
 
+// Writes the value as JSON. The output is lossy for the NaNs and Infinities that JSON5 input can
+// produce: JSON has no spelling for them, so they are written as null.
 pub fn stringify(value: &JSONValue) -> String {
     let mut string = String::new();
     stringify_internal(value, &mut string);
     string
-}
\ No newline at end of file
+}
+
+fn push_indent(depth: usize, out_string: &mut String) {
+    out_string.push('\n');
+    for _ in 0..depth {
+        out_string.push_str("  ");
+    }
+}
+
+fn stringify_pretty_internal(value: &JSONValue, depth: usize, out_string: &mut String) {
+    match value {
+        JSONValue::Object { data: object } if !object.is_empty() => {
+            out_string.push('{');
+            let mut is_first = true;
+            for (key, value) in object {
+                if is_first {
+                    is_first = false;
+                } else {
+                    out_string.push(',');
+                }
+                push_indent(depth + 1, out_string);
+                stringify_string(key, out_string);
+                out_string.push_str(": ");
+                stringify_pretty_internal(value, depth + 1, out_string);
+            }
+            push_indent(depth, out_string);
+            out_string.push('}');
+        }
+        JSONValue::Array { data: array } if !array.is_empty() => {
+            out_string.push('[');
+            let mut is_first = true;
+            for value in array {
+                if is_first {
+                    is_first = false;
+                } else {
+                    out_string.push(',');
+                }
+                push_indent(depth + 1, out_string);
+                stringify_pretty_internal(value, depth + 1, out_string);
+            }
+            push_indent(depth, out_string);
+            out_string.push(']');
+        }
+        _ => stringify_internal(value, out_string),
+    }
+}
+
+// Like stringify but with every member and element on its own line, indented by two spaces
+pub fn stringify_pretty(value: &JSONValue) -> String {
+    let mut string = String::new();
+    stringify_pretty_internal(value, 0, &mut string);
+    string
+}
+
+// `{}` writes the minified form and `{:#}` the pretty form
+impl fmt::Display for JSONValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str(&stringify_pretty(self))
+        } else {
+            f.write_str(&stringify(self))
+        }
+    }
+}
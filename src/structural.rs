@@ -0,0 +1,226 @@
+// Stage 1 of parsing: find every byte the parser may need to jump to, so whitespace and string
+// bodies can be skipped without inspecting each byte. The index holds the positions of
+// structural characters and atom starts outside strings, and of every unescaped quote.
+
+const BLOCK: usize = 64;
+
+#[derive(Default)]
+struct Masks {
+    quote: u64,
+    backslash: u64,
+    structural: u64,
+    whitespace: u64,
+}
+
+fn classify_portable(block: &[u8; BLOCK]) -> Masks {
+    let mut masks = Masks::default();
+    for (i, byte) in block.iter().enumerate() {
+        let bit = 1 << i;
+        match byte {
+            b'"' => masks.quote |= bit,
+            b'\\' => masks.backslash |= bit,
+            b'{' | b'}' | b'[' | b']' | b':' | b',' => masks.structural |= bit,
+            b' ' | b'\t' | b'\n' | b'\r' => masks.whitespace |= bit,
+            _ => {}
+        }
+    }
+    masks
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+    use super::{Masks, BLOCK};
+
+    #[target_feature(enable = "sse2")]
+    fn eq_sse2(chunk: __m128i, byte: u8) -> u64 {
+        _mm_movemask_epi8(_mm_cmpeq_epi8(chunk, _mm_set1_epi8(byte as i8))) as u16 as u64
+    }
+
+    #[target_feature(enable = "sse2")]
+    pub(super) fn classify_sse2(block: &[u8; BLOCK]) -> Masks {
+        let mut masks = Masks::default();
+        for i in 0..BLOCK / 16 {
+            // SAFETY: the block holds 64 bytes so every 16 byte load is in bounds
+            let chunk = unsafe { _mm_loadu_si128(block.as_ptr().add(i * 16) as *const __m128i) };
+            let shift = i * 16;
+            masks.quote |= eq_sse2(chunk, b'"') << shift;
+            masks.backslash |= eq_sse2(chunk, b'\\') << shift;
+            masks.structural |= (eq_sse2(chunk, b'{') | eq_sse2(chunk, b'}') | eq_sse2(chunk, b'[')
+                | eq_sse2(chunk, b']') | eq_sse2(chunk, b':') | eq_sse2(chunk, b',')) << shift;
+            masks.whitespace |= (eq_sse2(chunk, b' ') | eq_sse2(chunk, b'\t') | eq_sse2(chunk, b'\n')
+                | eq_sse2(chunk, b'\r')) << shift;
+        }
+        masks
+    }
+
+    #[target_feature(enable = "avx2")]
+    fn eq_avx2(chunk: __m256i, byte: u8) -> u64 {
+        _mm256_movemask_epi8(_mm256_cmpeq_epi8(chunk, _mm256_set1_epi8(byte as i8))) as u32 as u64
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) fn classify_avx2(block: &[u8; BLOCK]) -> Masks {
+        let mut masks = Masks::default();
+        for i in 0..BLOCK / 32 {
+            // SAFETY: the block holds 64 bytes so every 32 byte load is in bounds
+            let chunk = unsafe { _mm256_loadu_si256(block.as_ptr().add(i * 32) as *const __m256i) };
+            let shift = i * 32;
+            masks.quote |= eq_avx2(chunk, b'"') << shift;
+            masks.backslash |= eq_avx2(chunk, b'\\') << shift;
+            masks.structural |= (eq_avx2(chunk, b'{') | eq_avx2(chunk, b'}') | eq_avx2(chunk, b'[')
+                | eq_avx2(chunk, b']') | eq_avx2(chunk, b':') | eq_avx2(chunk, b',')) << shift;
+            masks.whitespace |= (eq_avx2(chunk, b' ') | eq_avx2(chunk, b'\t') | eq_avx2(chunk, b'\n')
+                | eq_avx2(chunk, b'\r')) << shift;
+        }
+        masks
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Classifier {
+    Portable,
+    #[cfg(target_arch = "x86_64")]
+    Sse2,
+    #[cfg(target_arch = "x86_64")]
+    Avx2,
+}
+
+impl Classifier {
+    // Picks the widest instruction set supported by the running CPU
+    pub(crate) fn detect() -> Self {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx2") {
+                return Classifier::Avx2;
+            }
+            if is_x86_feature_detected!("sse2") {
+                return Classifier::Sse2;
+            }
+        }
+        Classifier::Portable
+    }
+
+    fn classify(self, block: &[u8; BLOCK]) -> Masks {
+        match self {
+            Classifier::Portable => classify_portable(block),
+            // SAFETY: these variants are only constructed when the CPU supports the instructions
+            #[cfg(target_arch = "x86_64")]
+            Classifier::Sse2 => unsafe { x86::classify_sse2(block) },
+            #[cfg(target_arch = "x86_64")]
+            Classifier::Avx2 => unsafe { x86::classify_avx2(block) },
+        }
+    }
+}
+
+// Bits of the characters preceded by an odd number of backslashes. `escape_carry` records whether
+// the first character of the next block is escaped.
+fn escaped_mask(mut backslash: u64, escape_carry: &mut bool) -> u64 {
+    let mut escaped = 0;
+    if *escape_carry {
+        escaped |= 1;
+        backslash &= !1;
+    }
+    *escape_carry = false;
+    while backslash != 0 {
+        let i = backslash.trailing_zeros();
+        backslash &= backslash - 1;
+        if i == 63 {
+            *escape_carry = true;
+        } else {
+            escaped |= 1 << (i + 1);
+            backslash &= !(1 << (i + 1));
+        }
+    }
+    escaped
+}
+
+// Each bit becomes the xor of itself and all lower bits, marking the ranges between quote pairs
+fn prefix_xor(mut bits: u64) -> u64 {
+    bits ^= bits << 1;
+    bits ^= bits << 2;
+    bits ^= bits << 4;
+    bits ^= bits << 8;
+    bits ^= bits << 16;
+    bits ^= bits << 32;
+    bits
+}
+
+pub(crate) fn structural_index(data: &[u8], classifier: Classifier) -> Vec<u32> {
+    let mut index = Vec::with_capacity(data.len() / 4);
+    let mut escape_carry = false;
+    let mut in_string_carry = 0u64; // All ones when the previous block ended inside a string
+    let mut follows_separator_carry = 1u64; // The first byte of the input starts an atom
+    let mut padded = [b' '; BLOCK];
+    for (block_number, chunk) in data.chunks(BLOCK).enumerate() {
+        let block: &[u8; BLOCK] = match chunk.try_into() {
+            Ok(block) => block,
+            Err(_) => {
+                padded[..chunk.len()].copy_from_slice(chunk);
+                &padded
+            }
+        };
+        let masks = classifier.classify(block);
+        let quotes = masks.quote & !escaped_mask(masks.backslash, &mut escape_carry);
+        let in_string = prefix_xor(quotes) ^ in_string_carry;
+        in_string_carry = ((in_string as i64) >> 63) as u64;
+        let separators = masks.whitespace | masks.structural | quotes;
+        let atom_starts = !separators & (separators << 1 | follows_separator_carry);
+        follows_separator_carry = separators >> 63;
+        let mut bits = ((masks.structural | atom_starts) & !in_string) | quotes;
+        let base = (block_number * BLOCK) as u32;
+        while bits != 0 {
+            index.push(base + bits.trailing_zeros());
+            bits &= bits - 1;
+        }
+    }
+    // Padding can never produce entries, but trim anything past the end defensively
+    while index.last().is_some_and(|&position| position as usize >= data.len()) {
+        index.pop();
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference_index(data: &[u8]) -> Vec<u32> {
+        structural_index(data, Classifier::Portable)
+    }
+
+    #[test]
+    fn simple_document() {
+        let data = br#"{"a": [1, true], "b\"c": "d\\"}"#;
+        let expected = vec![0, 1, 3, 4, 6, 7, 8, 10, 14, 15, 17, 22, 23, 25, 29, 30];
+        assert_eq!(expected, reference_index(data));
+    }
+
+    #[test]
+    fn escapes_across_blocks() {
+        for len in 0..4 {
+            let mut data = vec![b'"'];
+            data.resize(63 - len, b'a');
+            data.extend(std::iter::repeat_n(b'\\', len * 2 + 1));
+            data.extend(br#"" x", 1]"#);
+            let index = reference_index(&data);
+            assert_eq!(0, index[0]);
+            let close = data.len() - 5;
+            assert_eq!(data[close], b'"');
+            assert_eq!(&[close as u32, close as u32 + 1, close as u32 + 3, close as u32 + 4], &index[1..]);
+        }
+    }
+
+    #[test]
+    fn classifiers_agree() {
+        let mut data = Vec::new();
+        for i in 0..300 {
+            data.extend(format!(r#"{{"key{i}" : [ {i}, "va\\\"l{i}", null ]}},"#).bytes());
+            data.extend(std::iter::repeat_n(b' ', i % 7));
+        }
+        let detected = Classifier::detect();
+        for start in 0..BLOCK {
+            assert_eq!(reference_index(&data[start..]), structural_index(&data[start..], detected));
+        }
+    }
+}
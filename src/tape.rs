@@ -0,0 +1,344 @@
+use std::fmt;
+use crate::byte_parser::{ByteParser, Sink};
+use crate::convert::ToJson;
+use crate::types::{JSONValue, ParseError};
+use crate::types::ParseError::Unknown;
+
+// A parsed document stored as a flat list of entries instead of a tree of nodes. Each entry holds
+// a tag in its top byte and a payload in the remaining 56 bits:
+// - null, true and false take one entry with no payload
+// - numbers take two entries, the second holding the bits of the f64
+// - strings take two entries, the first holding the offset into the string buffer and the second its length
+// - arrays and objects start with an entry holding the index just past their end entry, and end
+//   with an entry holding the index of their start entry. Object members are a string key followed by a value.
+pub struct Tape {
+    entries: Vec<u64>,
+    strings: Vec<u8>, // Always valid UTF-8, checked as each string is written
+}
+
+const TAG_SHIFT: u32 = 56;
+const PAYLOAD_MASK: u64 = (1 << TAG_SHIFT) - 1;
+
+const TAG_NULL: u8 = b'n';
+const TAG_TRUE: u8 = b't';
+const TAG_FALSE: u8 = b'f';
+const TAG_NUMBER: u8 = b'd';
+const TAG_STRING: u8 = b'"';
+const TAG_START_ARRAY: u8 = b'[';
+const TAG_END_ARRAY: u8 = b']';
+const TAG_START_OBJECT: u8 = b'{';
+const TAG_END_OBJECT: u8 = b'}';
+
+fn entry(tag: u8, payload: usize) -> u64 {
+    (tag as u64) << TAG_SHIFT | payload as u64
+}
+
+struct TapeWriter {
+    tape: Tape,
+}
+
+impl TapeWriter {
+    fn write_string(&mut self, parser: &mut ByteParser) -> Result<(), ParseError> {
+        let start = self.tape.strings.len();
+        parser.parse_string_bytes(&mut self.tape.strings)?;
+        std::str::from_utf8(&self.tape.strings[start..]).or(Err(Unknown))?;
+        self.tape.entries.push(entry(TAG_STRING, start));
+        self.tape.entries.push((self.tape.strings.len() - start) as u64);
+        Ok(())
+    }
+
+    fn begin(&mut self) -> usize {
+        self.tape.entries.push(0);
+        self.tape.entries.len() - 1
+    }
+
+    fn end(&mut self, start: usize, start_tag: u8, end_tag: u8) {
+        self.tape.entries.push(entry(end_tag, start));
+        self.tape.entries[start] = entry(start_tag, self.tape.entries.len());
+    }
+}
+
+// Everything is written to the tape as it is found, so there are no values to hand back
+impl Sink for TapeWriter {
+    type Value = ();
+    type Key = ();
+
+    fn null(&mut self) {
+        self.tape.entries.push(entry(TAG_NULL, 0));
+    }
+
+    fn boolean(&mut self, value: bool) {
+        self.tape.entries.push(entry(if value { TAG_TRUE } else { TAG_FALSE }, 0));
+    }
+
+    fn number(&mut self, number: f64) {
+        self.tape.entries.push(entry(TAG_NUMBER, 0));
+        self.tape.entries.push(number.to_bits());
+    }
+
+    fn string(&mut self, parser: &mut ByteParser) -> Result<(), ParseError> {
+        self.write_string(parser)
+    }
+
+    fn key(&mut self, parser: &mut ByteParser) -> Result<(), ParseError> {
+        self.write_string(parser)
+    }
+
+    fn begin_array(&mut self) -> usize {
+        self.begin()
+    }
+
+    fn element(&mut self, _: ()) {}
+
+    fn end_array(&mut self, start: usize) {
+        self.end(start, TAG_START_ARRAY, TAG_END_ARRAY)
+    }
+
+    fn begin_object(&mut self) -> usize {
+        self.begin()
+    }
+
+    fn member(&mut self, _: (), _: ()) {}
+
+    fn end_object(&mut self, start: usize) {
+        self.end(start, TAG_START_OBJECT, TAG_END_OBJECT)
+    }
+}
+
+pub fn parse_bytes_to_tape(bytes: &[u8]) -> Result<Tape, ParseError> {
+    let mut parser = ByteParser::with_structural_index(bytes);
+    let mut writer = TapeWriter {
+        tape: Tape { entries: Vec::with_capacity(bytes.len() / 4), strings: Vec::with_capacity(bytes.len() / 2) },
+    };
+    parser.walk(&mut writer)?;
+    if parser.head < bytes.len() {
+        return Err(Unknown)
+    }
+    Ok(writer.tape)
+}
+
+impl Tape {
+    pub fn root(&self) -> TapeValue<'_> {
+        TapeValue { tape: self, index: 0 }
+    }
+
+    // The number of u64 entries, useful to estimate memory use
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn tag(&self, index: usize) -> u8 {
+        (self.entries[index] >> TAG_SHIFT) as u8
+    }
+
+    fn payload(&self, index: usize) -> usize {
+        (self.entries[index] & PAYLOAD_MASK) as usize
+    }
+
+    // The index of the entry following the value starting at `index`
+    fn skip(&self, index: usize) -> usize {
+        match self.tag(index) {
+            TAG_NUMBER | TAG_STRING => index + 2,
+            TAG_START_ARRAY | TAG_START_OBJECT => self.payload(index),
+            _ => index + 1,
+        }
+    }
+
+    fn string(&self, index: usize) -> &str {
+        let start = self.payload(index);
+        let len = self.entries[index + 1] as usize;
+        // SAFETY: every string is checked to be valid UTF-8 before its entry is written
+        unsafe { std::str::from_utf8_unchecked(&self.strings[start..start + len]) }
+    }
+}
+
+impl fmt::Debug for Tape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tape").field("root", &self.root()).finish()
+    }
+}
+
+// A view of one value within a tape. Copying it is free and nothing is materialized until asked for.
+#[derive(Clone, Copy)]
+pub struct TapeValue<'t> {
+    tape: &'t Tape,
+    index: usize,
+}
+
+impl<'t> TapeValue<'t> {
+    pub fn is_null(&self) -> bool {
+        self.tape.tag(self.index) == TAG_NULL
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.tape.tag(self.index) {
+            TAG_TRUE => Some(true),
+            TAG_FALSE => Some(false),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.tape.tag(self.index) {
+            TAG_NUMBER => Some(f64::from_bits(self.tape.entries[self.index + 1])),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&'t str> {
+        match self.tape.tag(self.index) {
+            TAG_STRING => Some(self.tape.string(self.index)),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<TapeArray<'t>> {
+        match self.tape.tag(self.index) {
+            TAG_START_ARRAY => Some(TapeArray { tape: self.tape, index: self.index }),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<TapeObject<'t>> {
+        match self.tape.tag(self.index) {
+            TAG_START_OBJECT => Some(TapeObject { tape: self.tape, index: self.index }),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<TapeValue<'t>> {
+        self.as_object()?.get(key)
+    }
+}
+
+impl ToJson for TapeValue<'_> {
+    fn to_json(&self) -> JSONValue {
+        match self.tape.tag(self.index) {
+            TAG_NULL => JSONValue::Null,
+            TAG_TRUE => JSONValue::True,
+            TAG_FALSE => JSONValue::False,
+            TAG_NUMBER => JSONValue::Number { number: f64::from_bits(self.tape.entries[self.index + 1]) },
+            TAG_STRING => JSONValue::String { string: self.tape.string(self.index).into() },
+            TAG_START_ARRAY => JSONValue::Array { data: TapeArray { tape: self.tape, index: self.index }.iter().map(|value| value.to_json()).collect() },
+            _ => JSONValue::Object {
+                data: TapeObject { tape: self.tape, index: self.index }.iter().map(|(key, value)| ((*key).into(), value.to_json())).collect()
+            },
+        }
+    }
+}
+
+impl fmt::Debug for TapeValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_json())
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct TapeArray<'t> {
+    tape: &'t Tape,
+    index: usize,
+}
+
+impl<'t> TapeArray<'t> {
+    pub fn iter(&self) -> TapeArrayIter<'t> {
+        TapeArrayIter { tape: self.tape, index: self.index + 1 }
+    }
+
+    // Counting elements walks the array, skipping over nested containers
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tape.tag(self.index + 1) == TAG_END_ARRAY
+    }
+
+    pub fn get(&self, index: usize) -> Option<TapeValue<'t>> {
+        self.iter().nth(index)
+    }
+}
+
+impl<'t> IntoIterator for TapeArray<'t> {
+    type Item = TapeValue<'t>;
+    type IntoIter = TapeArrayIter<'t>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct TapeArrayIter<'t> {
+    tape: &'t Tape,
+    index: usize,
+}
+
+impl<'t> Iterator for TapeArrayIter<'t> {
+    type Item = TapeValue<'t>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.tape.tag(self.index) == TAG_END_ARRAY {
+            return None
+        }
+        let value = TapeValue { tape: self.tape, index: self.index };
+        self.index = self.tape.skip(self.index);
+        Some(value)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct TapeObject<'t> {
+    tape: &'t Tape,
+    index: usize,
+}
+
+impl<'t> TapeObject<'t> {
+    // Members are visited in document order
+    pub fn iter(&self) -> TapeObjectIter<'t> {
+        TapeObjectIter { tape: self.tape, index: self.index + 1 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tape.tag(self.index + 1) == TAG_END_OBJECT
+    }
+
+    // Finds a member by a linear scan. With duplicate keys the last one wins, as in `parse_bytes`.
+    pub fn get(&self, key: &str) -> Option<TapeValue<'t>> {
+        self.iter().filter(|(member, _)| *member == key).last().map(|(_, value)| value)
+    }
+}
+
+impl<'t> IntoIterator for TapeObject<'t> {
+    type Item = (&'t str, TapeValue<'t>);
+    type IntoIter = TapeObjectIter<'t>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct TapeObjectIter<'t> {
+    tape: &'t Tape,
+    index: usize,
+}
+
+impl<'t> Iterator for TapeObjectIter<'t> {
+    type Item = (&'t str, TapeValue<'t>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.tape.tag(self.index) == TAG_END_OBJECT {
+            return None
+        }
+        let key = self.tape.string(self.index);
+        let value = TapeValue { tape: self.tape, index: self.index + 2 };
+        self.index = self.tape.skip(self.index + 2);
+        Some((key, value))
+    }
+}
@@ -1,21 +1,137 @@
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
 use rustc_hash::FxHashMap;
+use crate::small_string::SmallString;
+
+// Object keys are `String`s. The `interning` feature makes them `SmallString`s instead, which store
+// short keys inline and let long ones share one allocation, see `KeyInterner`. `cargo bench --bench
+// memory --features interning` shows what sharing saves on documents that repeat their keys.
+#[cfg(not(feature = "interning"))]
+pub type JSONKey = String;
+#[cfg(feature = "interning")]
+pub type JSONKey = SmallString;
 
 // JSON specification: https://datatracker.ietf.org/doc/html/rfc7159
 #[allow(dead_code)]
-#[derive(PartialEq, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub enum JSONValue {
     False,
     True,
     Null,
-    Object { data: FxHashMap<String, JSONValue> },
+    Object { data: FxHashMap<JSONKey, JSONValue> },
     Array { data: Vec<JSONValue> },
     Number { number: f64 },
-    String { string: String },
+    String { string: SmallString },
+}
+
+// Numbers are equal when `compare_numbers` says so, which keeps equality reflexive for NaN
+impl PartialEq for JSONValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (JSONValue::Number { number: a }, JSONValue::Number { number: b }) => compare_numbers(*a, *b) == Ordering::Equal,
+            (JSONValue::String { string: a }, JSONValue::String { string: b }) => a == b,
+            (JSONValue::Array { data: a }, JSONValue::Array { data: b }) => a == b,
+            (JSONValue::Object { data: a }, JSONValue::Object { data: b }) => a == b,
+            _ => self.rank() == other.rank(),
+        }
+    }
 }
 
-// NaN can never be a valid JSON number when parsed
 impl Eq for JSONValue {}
 
+impl JSONValue {
+    // Position of each kind of value in the total order: null < booleans < numbers < strings < arrays < objects
+    fn rank(&self) -> u8 {
+        match self {
+            JSONValue::Null => 0,
+            JSONValue::False => 1,
+            JSONValue::True => 2,
+            JSONValue::Number { .. } => 3,
+            JSONValue::String { .. } => 4,
+            JSONValue::Array { .. } => 5,
+            JSONValue::Object { .. } => 6,
+        }
+    }
+}
+
+// The order of numbers behind Eq, Ord and Hash. Numbers compare numerically, so -0.0 equals 0.0.
+// The NaNs that JSON5 allows are ordered by `f64::total_cmp`: a NaN only equals a NaN with the
+// same bits, and positive NaNs sort after Infinity and negative ones before -Infinity.
+fn compare_numbers(a: f64, b: f64) -> Ordering {
+    a.partial_cmp(&b).unwrap_or_else(|| a.total_cmp(&b))
+}
+
+fn sorted_members(data: &FxHashMap<JSONKey, JSONValue>) -> Vec<(&JSONKey, &JSONValue)> {
+    let mut members: Vec<_> = data.iter().collect();
+    members.sort_unstable_by_key(|(key, _)| *key);
+    members
+}
+
+impl Hash for JSONValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rank().hash(state);
+        match self {
+            JSONValue::False | JSONValue::True | JSONValue::Null => {}
+            // -0.0 and 0.0 are equal so they have to hash the same, see `compare_numbers`
+            JSONValue::Number { number } => (if *number == 0.0 { 0.0f64 } else { *number }).to_bits().hash(state),
+            JSONValue::String { string } => string.hash(state),
+            JSONValue::Array { data } => data.hash(state),
+            // Members are hashed in key order since equality ignores insertion order
+            JSONValue::Object { data } => {
+                data.len().hash(state);
+                for (key, value) in sorted_members(data) {
+                    key.hash(state);
+                    value.hash(state);
+                }
+            }
+        }
+    }
+}
+
+// Numbers are compared numerically, arrays element by element, and objects by their members in key order
+impl Ord for JSONValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (JSONValue::Number { number: a }, JSONValue::Number { number: b }) => compare_numbers(*a, *b),
+            (JSONValue::String { string: a }, JSONValue::String { string: b }) => a.cmp(b),
+            (JSONValue::Array { data: a }, JSONValue::Array { data: b }) => a.cmp(b),
+            (JSONValue::Object { data: a }, JSONValue::Object { data: b }) => sorted_members(a).cmp(&sorted_members(b)),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl PartialOrd for JSONValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// The syntax accepted by the parsers
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Dialect {
+    #[default]
+    Json, // RFC 8259
+    Jsonc, // JSON with comments and trailing commas, as in VS Code settings and tsconfig files
+    Json5, // https://spec.json5.org
+}
+
+impl Dialect {
+    pub fn allows_comments(&self) -> bool {
+        *self != Dialect::Json
+    }
+
+    pub fn allows_trailing_commas(&self) -> bool {
+        *self != Dialect::Json
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
 #[allow(dead_code)]
 #[derive(PartialEq, Clone, Debug)]
 pub(crate) enum TokenValue {
@@ -30,14 +146,12 @@ pub(crate) enum TokenValue {
     ValueSeparator, // ,
     Number(f64), // a number as specified in Section 6 
     String(String), // a string as specified in Section 7
+    Identifier(String), // an unquoted JSON5 key or literal such as Infinity
 }
 
 impl TokenValue {
     pub(crate) fn can_be_value_start(&self) -> bool {
-        match self {
-            TokenValue::False | TokenValue::True | TokenValue::Number(_) | TokenValue::String(_) | TokenValue::Null | TokenValue::BeginObject | TokenValue::BeginArray => true,
-            _ => false
-        }
+        matches!(self, TokenValue::False | TokenValue::True | TokenValue::Number(_) | TokenValue::String(_) | TokenValue::Identifier(_) | TokenValue::Null | TokenValue::BeginObject | TokenValue::BeginArray)
     }
 }
 
@@ -59,5 +173,6 @@ impl Token {
 pub enum ParseError {
     Unknown,
     BadState, // The program has entered an invalid state. This should never happen
-    ParseNumberError(std::num::ParseFloatError)
+    ParseNumberError(std::num::ParseFloatError),
+    UnterminatedComment { offset: usize }, // The byte offset of a `/*` that is never closed
 }
\ No newline at end of file
@@ -0,0 +1,71 @@
+use myjson::json;
+use myjson::types::JSONValue;
+
+#[test]
+fn scalar_accessors() {
+    assert!(json!(null).is_null());
+    assert!(!json!(false).is_null());
+    assert_eq!(Some(true), json!(true).as_bool());
+    assert_eq!(Some(false), json!(false).as_bool());
+    assert_eq!(None, json!(0).as_bool());
+    assert_eq!(Some(1.5), json!(1.5).as_f64());
+    assert_eq!(None, json!("1.5").as_f64());
+    assert_eq!(Some(-42), json!(-42).as_i64());
+    assert_eq!(None, json!(1.5).as_i64());
+    assert_eq!(None, json!(1e300).as_i64());
+    assert_eq!(Some("abc"), json!("abc").as_str());
+    assert_eq!(None, json!(null).as_str());
+}
+
+#[test]
+fn container_accessors() {
+    let mut value = json!({ "a": [1, 2] });
+    assert_eq!(1, value.as_object().unwrap().len());
+    assert_eq!(None, value.as_array());
+    assert_eq!(Some(&vec![json!(1), json!(2)]), value["a"].as_array());
+    value.get_mut("a").unwrap().as_array_mut().unwrap().push(json!(3));
+    value.as_object_mut().unwrap().insert("b".into(), json!(true));
+    assert_eq!(json!({ "a": [1, 2, 3], "b": true }), value);
+}
+
+#[test]
+fn get() {
+    let value = json!({ "a": { "b": null } });
+    assert_eq!(Some(&json!({ "b": null })), value.get("a"));
+    assert_eq!(Some(&JSONValue::Null), value.get("a").unwrap().get("b"));
+    assert_eq!(None, value.get("c"));
+    assert_eq!(None, json!([1]).get("a"));
+}
+
+#[test]
+fn chained_index() {
+    let value = json!({ "a": [{ "b": "c" }] });
+    assert_eq!(Some("c"), value["a"][0]["b"].as_str());
+    assert!(value["a"][1]["b"].is_null());
+    assert!(value["missing"]["b"][3].is_null());
+    assert!(value["a"]["b"].is_null());
+    assert!(json!("abc")[0].is_null());
+}
+
+#[test]
+fn index_mut() {
+    let mut value = json!(null);
+    value["a"]["b"] = json!(1);
+    value["list"] = json!([1, 2]);
+    value["list"][1] = json!("two");
+    assert_eq!(json!({ "a": { "b": 1 }, "list": [1, "two"] }), value);
+}
+
+#[test]
+#[should_panic]
+fn index_mut_out_of_bounds() {
+    let mut value = json!([]);
+    value[0] = json!(1);
+}
+
+#[test]
+#[should_panic]
+fn index_mut_wrong_type() {
+    let mut value = json!(1);
+    value["a"] = json!(1);
+}
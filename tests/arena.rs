@@ -0,0 +1,48 @@
+use myjson::{parse_bytes, parse_bytes_in, ToJson};
+use myjson::arena::{ArenaValue, Bump};
+
+#[test]
+fn matches_parse_bytes() {
+    let arena = Bump::new();
+    for input in ["null", " true ", "-1.5e2", r#""aé\n""#, "[]", "{}", r#"{"a": [1, {"b": [[], {}]}, "c"], "d": {"e": null}}"#] {
+        assert_eq!(parse_bytes(input.as_bytes()).unwrap(), parse_bytes_in(&arena, input.as_bytes()).unwrap().to_json(), "{input}");
+    }
+    let citm_catalog = std::fs::read_to_string("data/citm_catalog.json").unwrap();
+    assert_eq!(parse_bytes(citm_catalog.as_bytes()).unwrap(), parse_bytes_in(&arena, citm_catalog.as_bytes()).unwrap().to_json());
+}
+
+#[test]
+fn invalid_documents() {
+    let arena = Bump::new();
+    for input in ["", "[1,]", r#"{"a" 1}"#, "[1] 2", r#""\ud800""#, "{1: 2}", "[[1]"] {
+        assert!(parse_bytes_in(&arena, input.as_bytes()).is_err(), "{input}");
+    }
+}
+
+#[test]
+fn structure() {
+    let arena = Bump::new();
+    let value = parse_bytes_in(&arena, br#"{"a": [1, "x", [true]], "b": null, "a": false}"#).unwrap();
+    let ArenaValue::Object { data } = value else { panic!("expected an object") };
+    assert_eq!(vec!["a", "b", "a"], data.iter().map(|(key, _)| *key).collect::<Vec<_>>());
+    assert_eq!(Some(false), value.get("a").and_then(|value| value.as_bool()));
+    assert!(value.get("b").unwrap().is_null());
+    assert!(value.get("c").is_none());
+    let array = data[0].1.as_array().unwrap();
+    assert_eq!(Some(1.0), array[0].as_f64());
+    assert_eq!(Some("x"), array[1].as_str());
+    assert_eq!(ArenaValue::Array { data: &[ArenaValue::True] }, array[2]);
+}
+
+#[test]
+fn arena_reuse() {
+    let mut arena = Bump::new();
+    let input = br#"{"key": ["some", "strings", 1, 2, 3]}"#;
+    parse_bytes_in(&arena, input).unwrap();
+    let used = arena.allocated_bytes();
+    for _ in 0..100 {
+        arena.reset();
+        assert_eq!(Some("strings"), parse_bytes_in(&arena, input).unwrap().get("key").unwrap().as_array().unwrap()[1].as_str());
+    }
+    assert_eq!(used, arena.allocated_bytes());
+}
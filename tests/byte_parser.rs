@@ -1,8 +1,8 @@
-use myjson::{parse, parse_bytes};
-use myjson::types::{JSONValue, ParseError};
+use rustc_hash::FxHashMap;
+use myjson::parse_bytes;
+use myjson::types::JSONValue;
 
-use std::str::Chars;
-use myjson::types::JSONValue::{False, Null, Number, String, True};
+use myjson::types::JSONValue::{Array, False, Null, Number, Object, String, True};
 
 fn assert_parse(expected: JSONValue, input: &str) {
     assert_eq!(Ok(expected), parse_bytes(input.as_bytes()));
@@ -35,6 +35,7 @@ fn num_zero_decimal() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn num_negative() {
     assert_parse(Number { number: -3.14 }, "-3.14");
 }
@@ -149,39 +150,39 @@ fn num_fail_decimal_e() {
 
 #[test]
 fn string_empty() {
-    assert_parse(String { string: "".to_string()}, r#""""#);
+    assert_parse(String { string: "".into() }, r#""""#);
 }
 #[test]
 fn string_nonempty() {
-    assert_parse(String { string: "1".to_string()}, r#""1""#);
-    assert_parse(String { string: "123".to_string()}, r#""123""#);
-    assert_parse(String { string: " abc ".to_string()}, r#"" abc ""#);
+    assert_parse(String { string: "1".into() }, r#""1""#);
+    assert_parse(String { string: "123".into() }, r#""123""#);
+    assert_parse(String { string: " abc ".into() }, r#"" abc ""#);
 }
 
 #[test]
 fn string_unicode() {
-    assert_parse(String { string: "軅".to_string()}, r#""軅""#);
-    assert_parse(String { string: "쎨".to_string()}, r#""쎨""#);
-    assert_parse(String { string: "🫸🏿".to_string()}, r#""🫸🏿""#);
+    assert_parse(String { string: "軅".into() }, r#""軅""#);
+    assert_parse(String { string: "쎨".into() }, r#""쎨""#);
+    assert_parse(String { string: "🫸🏿".into() }, r#""🫸🏿""#);
 }
 
 #[test]
 fn string_escape() {
-    assert_parse(String { string: "\r".to_string()}, r#""\r""#);
-    assert_parse(String { string: "\n".to_string()}, r#""\n""#);
-    assert_parse(String { string: "\t".to_string()}, r#""\t""#);
-    assert_parse(String { string: "/".to_string()}, r#""\/""#);
-    assert_parse(String { string: "\\".to_string()}, r#""\\""#);
-    assert_parse(String { string: "\u{0008}".to_string()}, r#""\b""#);
-    assert_parse(String { string: "\u{000C}".to_string()}, r#""\f""#);
-    assert_parse(String { string: "\"".to_string()}, r#""\"""#);
-    assert_parse(String { string: "\u{0000}".to_string()}, r#""\u0000""#);
-    assert_parse(String { string: "\u{ABCD}".to_string()}, r#""\uABCD""#);
-    assert_parse(String { string: "\u{ABCD}".to_string()}, r#""\uabcd""#);
-    assert_parse(String { string: "\u{1523}".to_string()}, r#""\u1523""#);
-    assert_parse(String { string: "\u{6561}".to_string()}, r#""\u6561""#);
-    assert_parse(String { string: "\u{FFFF}".to_string()}, r#""\uFFFF""#);
-    assert_parse(String { string: "𝄞".to_string()}, r#""\uD834\uDD1E""#);
+    assert_parse(String { string: "\r".into() }, r#""\r""#);
+    assert_parse(String { string: "\n".into() }, r#""\n""#);
+    assert_parse(String { string: "\t".into() }, r#""\t""#);
+    assert_parse(String { string: "/".into() }, r#""\/""#);
+    assert_parse(String { string: "\\".into() }, r#""\\""#);
+    assert_parse(String { string: "\u{0008}".into() }, r#""\b""#);
+    assert_parse(String { string: "\u{000C}".into() }, r#""\f""#);
+    assert_parse(String { string: "\"".into() }, r#""\"""#);
+    assert_parse(String { string: "\u{0000}".into() }, r#""\u0000""#);
+    assert_parse(String { string: "\u{ABCD}".into() }, r#""\uABCD""#);
+    assert_parse(String { string: "\u{ABCD}".into() }, r#""\uabcd""#);
+    assert_parse(String { string: "\u{1523}".into() }, r#""\u1523""#);
+    assert_parse(String { string: "\u{6561}".into() }, r#""\u6561""#);
+    assert_parse(String { string: "\u{FFFF}".into() }, r#""\uFFFF""#);
+    assert_parse(String { string: "𝄞".into() }, r#""\uD834\uDD1E""#);
 }
 
 #[test]
@@ -247,4 +248,39 @@ fn bad_true_false_null() {
     assert_parse_fail("nul");
     assert_parse_fail("nulll");
     assert_parse_fail("Null");
-}
\ No newline at end of file
+}
+
+#[test]
+fn long_strings_across_blocks() {
+    for len in 55..75 {
+        let plain = "a".repeat(len);
+        let escaped = format!("{}\\\"\\\\", "b".repeat(len));
+        let input = format!("[\"{plain}\",  \"{escaped}\" , {{\"{plain}\":\n\t{len}}}]");
+        let mut object = FxHashMap::default();
+        object.insert(plain.as_str().into(), Number { number: len as f64 });
+        let expected = Array { data: vec![
+            JSONValue::String { string: plain.into() },
+            JSONValue::String { string: format!("{}\"\\", "b".repeat(len)).into() },
+            Object { data: object },
+        ] };
+        assert_parse(expected, &input);
+    }
+}
+
+#[test]
+fn sample_document() {
+    // The other sample files contain numbers the two parsers round differently
+    let input = std::fs::read_to_string("data/citm_catalog.json").unwrap();
+    assert_eq!(myjson::parse(input.chars()), parse_bytes(input.as_bytes()));
+}
+
+#[test]
+fn string_specials_at_every_offset() {
+    for offset in 0..20 {
+        let prefix = "é".repeat(offset / 2) + &"x".repeat(offset % 2);
+        assert_parse(String { string: format!("{prefix}\n{prefix}\"").into() }, &format!("\"{prefix}\\n{prefix}\\\"\""));
+        assert_parse_fail(&format!("\"{prefix}\u{1f}{prefix}\""));
+        assert_parse_fail(&format!("\"{prefix}\\n{prefix}\t\""));
+        assert_parse_fail(&format!("\"{prefix}\\n{prefix}"));
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::{BTreeMap, HashMap};
+use rustc_hash::FxHashMap;
+use myjson::{from_json, parse_bytes, to_json, FromJson, ToJson};
+use myjson::convert::{ConversionError, ConversionErrorKind};
+use myjson::types::{JSONValue, PathSegment};
+
+fn json(input: &str) -> JSONValue {
+    parse_bytes(input.as_bytes()).unwrap()
+}
+
+fn assert_round_trip<T: ToJson + FromJson + PartialEq + std::fmt::Debug>(value: T, expected: &str) {
+    assert_eq!(json(expected), value.to_json());
+    assert_eq!(Ok(value), T::from_json(&json(expected)));
+}
+
+fn assert_from_json_fail<T: FromJson + std::fmt::Debug>(input: &str, path: Vec<PathSegment>, kind: ConversionErrorKind) {
+    assert_eq!(ConversionError { path, kind }, from_json::<T>(&json(input)).unwrap_err());
+}
+
+fn expected(expected: &'static str, found: &'static str) -> ConversionErrorKind {
+    ConversionErrorKind::ExpectedType { expected, found }
+}
+
+#[test]
+fn primitives() {
+    assert_round_trip(true, "true");
+    assert_round_trip(false, "false");
+    assert_round_trip((), "null");
+    assert_round_trip("abc".to_string(), r#""abc""#);
+    assert_round_trip('a', r#""a""#);
+    assert_round_trip(1.5f64, "1.5");
+    assert_round_trip(1.5f32, "1.5");
+    assert_round_trip(-3i8, "-3");
+    assert_round_trip(u64::MAX >> 12, "4503599627370495");
+    assert_round_trip(42usize, "42");
+    assert_eq!(json(r#""abc""#), to_json("abc"));
+    assert_eq!(JSONValue::Null, f64::NAN.to_json());
+    assert_eq!(JSONValue::Null, f64::INFINITY.to_json());
+}
+
+#[test]
+fn primitive_errors() {
+    assert_from_json_fail::<bool>("1", vec![], expected("boolean", "number"));
+    assert_from_json_fail::<String>("null", vec![], expected("string", "null"));
+    assert_from_json_fail::<char>(r#""ab""#, vec![], expected("character", "string"));
+    assert_from_json_fail::<i32>("1.5", vec![], expected("integer", "number"));
+    assert_from_json_fail::<u8>("256", vec![], ConversionErrorKind::OutOfRange);
+    assert_from_json_fail::<u8>("-1", vec![], ConversionErrorKind::OutOfRange);
+    assert_from_json_fail::<f64>("[]", vec![], expected("number", "array"));
+    assert_from_json_fail::<f32>("1e39", vec![], ConversionErrorKind::OutOfRange);
+}
+
+#[test]
+fn integer_limits() {
+    // The largest doubles below 2^63 and 2^64, and the powers of two themselves
+    assert_eq!(Ok(i64::MAX - 1023), from_json::<i64>(&json("9223372036854774784")));
+    assert_eq!(Ok(i64::MIN), from_json::<i64>(&json("-9223372036854775808")));
+    assert_from_json_fail::<i64>("9223372036854775808", vec![], ConversionErrorKind::OutOfRange);
+    assert_eq!(Ok(u64::MAX - 2047), from_json::<u64>(&json("18446744073709549568")));
+    assert_from_json_fail::<u64>("18446744073709551616", vec![], ConversionErrorKind::OutOfRange);
+}
+
+#[test]
+fn integers_beyond_f64_precision() {
+    // Integers up to 2^53 round-trip exactly, larger ones are rounded
+    assert_round_trip(1u64 << 53, "9007199254740992");
+    assert_round_trip(-(1i64 << 53), "-9007199254740992");
+    assert_eq!(json("9007199254740992"), ((1u64 << 53) + 1).to_json());
+    assert_eq!(Ok(1u64 << 53), from_json::<u64>(&((1u64 << 53) + 1).to_json()));
+    assert_round_trip(i64::MIN, "-9223372036854775808");
+    // The maxima round up to a power of two that no longer fits
+    assert_eq!(json("18446744073709551616"), u64::MAX.to_json());
+    assert_eq!(Err(ConversionError::new(ConversionErrorKind::OutOfRange)), from_json::<u64>(&u64::MAX.to_json()));
+    assert_eq!(Err(ConversionError::new(ConversionErrorKind::OutOfRange)), from_json::<i64>(&i64::MAX.to_json()));
+}
+
+#[test]
+fn option() {
+    assert_round_trip(Some(1u8), "1");
+    assert_round_trip(None::<u8>, "null");
+    assert_round_trip(vec![Some(true), None], "[true, null]");
+}
+
+#[test]
+fn sequences() {
+    assert_round_trip(vec![1, 2, 3], "[1, 2, 3]");
+    assert_round_trip(Vec::<String>::new(), "[]");
+    assert_round_trip([1u8, 2], "[1, 2]");
+    assert_round_trip((1u8, "a".to_string(), false), r#"[1, "a", false]"#);
+    assert_round_trip(vec![vec![1], vec![]], "[[1], []]");
+    assert_eq!(json("[1, 2]"), to_json(&[1, 2][..]));
+    assert_round_trip(Box::new(1u8), "1");
+}
+
+#[test]
+fn sequence_errors() {
+    assert_from_json_fail::<Vec<u8>>(r#"[1, "a"]"#, vec![PathSegment::Index(1)], expected("integer", "string"));
+    assert_from_json_fail::<[u8; 3]>("[1, 2]", vec![], ConversionErrorKind::WrongLength { expected: 3, found: 2 });
+    assert_from_json_fail::<(u8, bool)>("[1, 2]", vec![PathSegment::Index(1)], expected("boolean", "number"));
+    assert_from_json_fail::<(u8, bool)>("[1]", vec![], ConversionErrorKind::WrongLength { expected: 2, found: 1 });
+    assert_from_json_fail::<Vec<u8>>("{}", vec![], expected("array", "object"));
+}
+
+#[test]
+fn maps() {
+    assert_round_trip(HashMap::from([("a".to_string(), 1u8), ("b".to_string(), 2)]), r#"{"a": 1, "b": 2}"#);
+    assert_round_trip(BTreeMap::from([("a".to_string(), vec![true])]), r#"{"a": [true]}"#);
+    assert_round_trip(FxHashMap::from_iter([("a".to_string(), None::<u8>)]), r#"{"a": null}"#);
+    assert_eq!(json(r#"{"a": 1}"#), BTreeMap::from([("a", 1)]).to_json());
+}
+
+#[test]
+fn nested_error_path() {
+    assert_from_json_fail::<BTreeMap<String, Vec<Option<u8>>>>(
+        r#"{"a": [1, null, "x"]}"#,
+        vec![PathSegment::Key("a".to_string()), PathSegment::Index(2)],
+        expected("integer", "string"),
+    );
+    let err = from_json::<BTreeMap<String, Vec<u8>>>(&json(r#"{"a b": [true]}"#)).unwrap_err();
+    assert_eq!(r#"at ["a b"][0]: expected integer, found boolean"#, err.to_string());
+}
+
+#[test]
+fn from_impls() {
+    assert_eq!(JSONValue::True, true.into());
+    assert_eq!(json("3"), JSONValue::from(3u8));
+    assert_eq!(JSONValue::Null, f64::NAN.into());
+    assert_eq!(json(r#""a""#), "a".into());
+    assert_eq!(json(r#""b""#), "b".to_string().into());
+    assert_eq!(JSONValue::Null, Option::<i32>::None.into());
+    assert_eq!(json(r#"[1,null]"#), vec![Some(1), None].into());
+    let mut map = HashMap::new();
+    map.insert("a", vec![true]);
+    assert_eq!(json(r#"{"a":[true]}"#), map.into());
+    let mut tree = BTreeMap::new();
+    tree.insert("b".to_string(), "c");
+    assert_eq!(json(r#"{"b":"c"}"#), tree.into());
+    assert_eq!(json("[0,1,4]"), (0..3).map(|i| i * i).collect::<JSONValue>());
+}
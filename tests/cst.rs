@@ -0,0 +1,158 @@
+use myjson::{json, parse_bytes, parse_document};
+use myjson::cst::EditError;
+use myjson::types::{Dialect, ParseError, PathSegment};
+
+fn key(key: &str) -> PathSegment {
+    PathSegment::Key(key.to_string())
+}
+
+fn index(index: usize) -> PathSegment {
+    PathSegment::Index(index)
+}
+
+const CONFIG: &str = r#"// Service configuration
+{
+  "name": "api",   // shown in dashboards
+  "port": 8080,
+  /* Upstreams are tried in order */
+  "upstreams": [
+    "a.internal",
+    "b.internal", // fallback
+  ],
+  "limits": { "rps": 1e3, "burst": 0x10 },
+}
+"#;
+
+#[test]
+fn round_trips_byte_for_byte() {
+    for (input, dialect) in [
+        (CONFIG, Dialect::Json5),
+        (CONFIG, Dialect::Jsonc),
+        ("  {\"a\" :[ 1 ,2,{ } , [] ],\"b\":\"\\u00e9\"}\n\n", Dialect::Json),
+        ("{unquoted: 'single', +1: 2}", Dialect::Json5),
+        ("[]", Dialect::Json),
+        ("0", Dialect::Json),
+    ] {
+        if let Ok(document) = parse_document(input, dialect) {
+            assert_eq!(input, document.to_string());
+        }
+    }
+    let citm_catalog = std::fs::read_to_string("data/citm_catalog.json").unwrap();
+    let document = parse_document(&citm_catalog, Dialect::Json).unwrap();
+    assert_eq!(citm_catalog, document.to_string());
+    assert_eq!(parse_bytes(citm_catalog.as_bytes()).unwrap(), document.value());
+}
+
+#[test]
+fn invalid_documents() {
+    for input in ["", "[1,]", "{\"a\" 1}", "[1] 2", "{'a': 1}", "[01]", "[\"a\\x\"]", "{\"a\": tru}"] {
+        assert!(parse_document(input, Dialect::Json).is_err(), "{input}");
+    }
+    assert!(parse_document("{+1: 2}", Dialect::Json5).is_err());
+    assert_eq!(Err(ParseError::UnterminatedComment { offset: 4 }), parse_document("[1, /* 2]", Dialect::Jsonc).map(|_| ()));
+}
+
+#[test]
+fn get() {
+    let document = parse_document(CONFIG, Dialect::Json5).unwrap();
+    assert_eq!(Some(json!("api")), document.get(&[key("name")]));
+    assert_eq!(Some(json!("b.internal")), document.get(&[key("upstreams"), index(1)]));
+    assert_eq!(Some(json!({ "rps": 1000, "burst": 16 })), document.get(&[key("limits")]));
+    assert_eq!(None, document.get(&[key("upstreams"), index(2)]));
+    assert_eq!(None, document.get(&[key("name"), key("first")]));
+    assert_eq!(Some(document.value()), document.get(&[]));
+}
+
+#[test]
+fn set_scalar_keeps_everything_else() {
+    let mut document = parse_document(CONFIG, Dialect::Json5).unwrap();
+    document.set(&[key("port")], &json!(9090)).unwrap();
+    document.set(&[key("upstreams"), index(0)], &json!("c.internal")).unwrap();
+    // Numbers are written the way `stringify` writes them
+    assert_eq!(CONFIG.replace("8080", "9090.0").replace("a.internal", "c.internal"), document.to_string());
+}
+
+#[test]
+fn set_adds_members_and_elements() {
+    let mut document = parse_document("{\n  \"a\": 1 // one\n}\n", Dialect::Jsonc).unwrap();
+    document.set(&[key("b")], &json!({ "c": [true] })).unwrap();
+    assert_eq!("{\n  \"a\": 1, // one\n  \"b\": {\n    \"c\": [\n      true\n    ]\n  }\n}\n", document.to_string());
+
+    let mut document = parse_document(r#"{"list": [1, 2], "empty": []}"#, Dialect::Json).unwrap();
+    document.set(&[key("list"), index(2)], &json!(false)).unwrap();
+    document.set(&[key("empty"), index(0)], &json!({ "x": null })).unwrap();
+    document.set(&[key("new")], &json!("value")).unwrap();
+    assert_eq!(r#"{"list": [1, 2, false], "empty": [{"x":null}], "new": "value"}"#, document.to_string());
+}
+
+#[test]
+fn set_errors() {
+    let mut document = parse_document(r#"{"a": [1]}"#, Dialect::Json).unwrap();
+    assert_eq!(Err(EditError::NotFound(vec![key("b")])), document.set(&[key("b"), key("c")], &json!(1)));
+    assert_eq!(Err(EditError::NotFound(vec![key("a"), index(2)])), document.set(&[key("a"), index(2)], &json!(1)));
+    assert_eq!(Err(EditError::NotFound(vec![key("a"), key("b")])), document.set(&[key("a"), key("b")], &json!(1)));
+    assert_eq!("nothing at .a.b", EditError::NotFound(vec![key("a"), key("b")]).to_string());
+    assert_eq!(r#"{"a": [1]}"#, document.to_string());
+}
+
+#[test]
+fn set_root() {
+    let mut document = parse_document("// comment\n[1]\n", Dialect::Jsonc).unwrap();
+    document.set(&[], &json!({ "a": true })).unwrap();
+    assert_eq!("// comment\n{\"a\":true}\n", document.to_string());
+}
+
+#[test]
+fn insert() {
+    let mut document = parse_document(r#"[ "b", "d" ]"#, Dialect::Json).unwrap();
+    document.insert(&[index(1)], &json!("c")).unwrap();
+    document.insert(&[index(0)], &json!("a")).unwrap();
+    document.insert(&[index(4)], &json!("e")).unwrap();
+    assert_eq!(r#"[ "a", "b", "c", "d", "e" ]"#, document.to_string());
+    assert_eq!(Err(EditError::NotFound(vec![index(6)])), document.insert(&[index(6)], &json!("g")));
+
+    let mut document = parse_document("[\n    \"b\"\n]", Dialect::Json).unwrap();
+    document.insert(&[index(0)], &json!("a")).unwrap();
+    assert_eq!("[\n    \"a\",\n    \"b\"\n]", document.to_string());
+}
+
+#[test]
+fn remove() {
+    let mut document = parse_document(CONFIG, Dialect::Json5).unwrap();
+    assert_eq!(Ok(json!("b.internal")), document.remove(&[key("upstreams"), index(1)]));
+    assert_eq!(Ok(json!(8080)), document.remove(&[key("port")]));
+    assert_eq!(Ok(json!({ "rps": 1000, "burst": 16 })), document.remove(&[key("limits")]));
+    assert_eq!(r#"// Service configuration
+{
+  "name": "api",   // shown in dashboards
+  /* Upstreams are tried in order */
+  "upstreams": [
+    "a.internal",
+  ],
+}
+"#, document.to_string());
+    assert_eq!(Err(EditError::NotFound(vec![key("port")])), document.remove(&[key("port")]));
+    assert_eq!(Err(EditError::RemoveRoot), document.remove(&[]));
+}
+
+#[test]
+fn remove_keeps_comments_of_other_members() {
+    let mut document = parse_document("{\n  \"a\": 1, // about a\n  \"b\": 2\n}", Dialect::Jsonc).unwrap();
+    document.remove(&[key("b")]).unwrap();
+    assert_eq!("{\n  \"a\": 1 // about a\n}", document.to_string());
+
+    let mut document = parse_document("[1, 2, 3]", Dialect::Json).unwrap();
+    document.remove(&[index(0)]).unwrap();
+    document.remove(&[index(1)]).unwrap();
+    assert_eq!("[2]", document.to_string());
+    document.remove(&[index(0)]).unwrap();
+    assert_eq!("[]", document.to_string());
+}
+
+#[test]
+fn duplicate_keys() {
+    let mut document = parse_document(r#"{"a": 1, "a": 2}"#, Dialect::Json).unwrap();
+    assert_eq!(Some(json!(2)), document.get(&[key("a")]));
+    document.set(&[key("a")], &json!(3)).unwrap();
+    assert_eq!(r#"{"a": 1, "a": 3.0}"#, document.to_string());
+}
@@ -0,0 +1,97 @@
+use myjson::{assert_json_eq, parse_bytes};
+use myjson::diff::{diff, diff_with_options, format_path, render_diff, ArrayDiffMode, DiffOptions, Difference, PathSegment};
+use myjson::types::JSONValue;
+
+fn json(input: &str) -> JSONValue {
+    parse_bytes(input.as_bytes()).unwrap()
+}
+
+fn key(key: &str) -> PathSegment {
+    PathSegment::Key(key.to_string())
+}
+
+fn with_arrays(arrays: ArrayDiffMode) -> DiffOptions {
+    DiffOptions { arrays }
+}
+
+#[test]
+fn diff_equal() {
+    assert_eq!(Vec::<Difference>::new(), diff(&json(r#"{"a": [1, {"b": null}]}"#), &json(r#"{"a": [1, {"b": null}]}"#)));
+}
+
+#[test]
+fn diff_scalar() {
+    assert_eq!(vec![Difference::Changed { path: vec![], old: json("1"), new: json("2") }], diff(&json("1"), &json("2")));
+    assert_eq!(vec![Difference::Changed { path: vec![], old: json("[]"), new: json("{}") }], diff(&json("[]"), &json("{}")));
+}
+
+#[test]
+fn diff_object() {
+    assert_eq!(vec![
+        Difference::Removed { path: vec![key("a")], value: json("1") },
+        Difference::Changed { path: vec![key("b"), key("c")], old: json("true"), new: json("false") },
+        Difference::Added { path: vec![key("d")], value: json(r#""x""#) },
+    ], diff(&json(r#"{"a": 1, "b": {"c": true}}"#), &json(r#"{"b": {"c": false}, "d": "x"}"#)));
+}
+
+#[test]
+fn diff_ordered_array() {
+    assert_eq!(vec![
+        Difference::Changed { path: vec![PathSegment::Index(1)], old: json("2"), new: json("3") },
+        Difference::Added { path: vec![PathSegment::Index(2)], value: json("4") },
+    ], diff(&json("[1, 2]"), &json("[1, 3, 4]")));
+    assert_eq!(vec![
+        Difference::Removed { path: vec![PathSegment::Index(1)], value: json("2") },
+    ], diff(&json("[1, 2]"), &json("[1]")));
+}
+
+#[test]
+fn diff_unordered_array() {
+    let options = with_arrays(ArrayDiffMode::Unordered);
+    assert!(diff_with_options(&json("[1, 2, 3]"), &json("[3, 1, 2]"), &options).is_empty());
+    assert_eq!(vec![
+        Difference::Removed { path: vec![PathSegment::Index(1)], value: json("1") },
+        Difference::Added { path: vec![PathSegment::Index(0)], value: json("4") },
+    ], diff_with_options(&json("[1, 1, 2]"), &json("[4, 1, 2]"), &options));
+}
+
+#[test]
+fn diff_array_by_key() {
+    let options = with_arrays(ArrayDiffMode::ByKey("id".to_string()));
+    assert_eq!(vec![
+        Difference::Changed { path: vec![PathSegment::Index(0), key("name")], old: json(r#""b""#), new: json(r#""c""#) },
+        Difference::Added { path: vec![PathSegment::Index(2)], value: json(r#"{"id": 3}"#) },
+        Difference::Removed { path: vec![PathSegment::Index(0)], value: json(r#"{"id": 1}"#) },
+        Difference::Removed { path: vec![PathSegment::Index(2)], value: json("true") },
+    ], diff_with_options(
+        &json(r#"[{"id": 1}, {"id": 2, "name": "b"}, true, null]"#),
+        &json(r#"[{"id": 2, "name": "c"}, null, {"id": 3}]"#),
+        &options,
+    ));
+}
+
+#[test]
+fn format_paths() {
+    assert_eq!(".", format_path(&[]));
+    assert_eq!(".a[0].b_1", format_path(&[key("a"), PathSegment::Index(0), key("b_1")]));
+    assert_eq!(r#"["a b"]["\""][""]"#, format_path(&[key("a b"), key("\""), key("")]));
+}
+
+#[test]
+fn render() {
+    let differences = diff(&json(r#"{"a": 1, "b": [true]}"#), &json(r#"{"b": [false], "c": null}"#));
+    assert_eq!("--- old\n+++ new\n- .a: 1.0\n- .b[0]: true\n+ .b[0]: false\n+ .c: null\n", render_diff(&differences, false));
+    assert_eq!("--- old\n+++ new\n\x1b[31m- .: 1.0\x1b[0m\n\x1b[32m+ .: 2.0\x1b[0m\n", render_diff(&diff(&json("1"), &json("2")), true));
+    assert_eq!("", render_diff(&[], true));
+}
+
+#[test]
+fn assert_json_eq_passes() {
+    assert_json_eq!(json(r#"{"a": [1]}"#), json(r#"{"a": [1]}"#));
+}
+
+#[test]
+#[should_panic(expected = "- .a[0]: 1.0\n+ .a[0]: 2.0")]
+fn assert_json_eq_fails() {
+    assert_json_eq!(json(r#"{"a": [1]}"#), json(r#"{"a": [2]}"#));
+}
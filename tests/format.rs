@@ -0,0 +1,90 @@
+use myjson::{format_document, parse_document, stringify_pretty, parse_bytes};
+use myjson::types::{Dialect, ParseError};
+
+const MESSY: &str = r#"// Service configuration
+
+{ "name": "api",   // shown in dashboards
+     "port": 8080,
+
+  /* Upstreams are tried in order */
+  "upstreams": [ "a.internal", "b.internal", // fallback
+  ],
+  "limits": { "rps": 1e3, "burst": 0x10 }, "empty": [ ], "todo": { // nothing yet
+  }
+  // end of config
+} // trailing
+"#;
+
+const FORMATTED: &str = r#"// Service configuration
+
+{
+  "name": "api", // shown in dashboards
+  "port": 8080,
+
+  /* Upstreams are tried in order */
+  "upstreams": [
+    "a.internal",
+    "b.internal", // fallback
+  ],
+  "limits": {
+    "rps": 1e3,
+    "burst": 0x10
+  },
+  "empty": [],
+  "todo": {
+    // nothing yet
+  }
+  // end of config
+} // trailing
+"#;
+
+#[test]
+fn keeps_comments_with_their_members() {
+    assert_eq!(FORMATTED, format_document(MESSY, Dialect::Json5, "  ").unwrap());
+}
+
+#[test]
+fn is_idempotent() {
+    assert_eq!(FORMATTED, format_document(FORMATTED, Dialect::Json5, "  ").unwrap());
+}
+
+#[test]
+fn matches_stringify_pretty_without_comments() {
+    // Objects with a single member, since `stringify_pretty` writes members in hash order
+    let input = r#"{"a":[1.5,{"b":null},"c",[]],"e":{"f":{}}}"#;
+    let expected = stringify_pretty(&parse_bytes(input.as_bytes()).unwrap()) + "\n";
+    assert_eq!(expected, format_document(input, Dialect::Json, "  ").unwrap());
+}
+
+#[test]
+fn indent() {
+    assert_eq!("[\n\t{\n\t\t\"a\": 1\n\t}\n]\n", format_document(r#"[{"a": 1}]"#, Dialect::Json, "\t").unwrap());
+    assert_eq!("{\n    \"a\": [\n        true\n    ]\n}\n", format_document(r#"{"a": [true]}"#, Dialect::Json, "    ").unwrap());
+}
+
+#[test]
+fn comments_in_awkward_places() {
+    let input = "{ key /* k */ : // after colon\n 'v', list: [1, /* two */ 2, 3 // three\n] }";
+    let expected = "{\n  key /* k */: // after colon\n    'v',\n  list: [\n    1,\n    /* two */ 2,\n    3 // three\n  ]\n}\n";
+    assert_eq!(expected, format_document(input, Dialect::Json5, "  ").unwrap());
+    assert_eq!(expected, format_document(expected, Dialect::Json5, "  ").unwrap());
+}
+
+#[test]
+fn keeps_the_value() {
+    let document = parse_document(MESSY, Dialect::Json5).unwrap();
+    let formatted = parse_document(&document.format("  "), Dialect::Json5).unwrap();
+    assert_eq!(document.value(), formatted.value());
+}
+
+#[test]
+fn invalid_input() {
+    assert_eq!(Err(ParseError::Unknown), format_document("{\"a\": }", Dialect::Json, "  "));
+    assert_eq!(Err(ParseError::UnterminatedComment { offset: 5 }), format_document("[1,  /* 2 ]", Dialect::Jsonc, "  "));
+}
+
+#[test]
+fn minify() {
+    let document = parse_document(MESSY, Dialect::Json5).unwrap();
+    assert_eq!(r#"{"name":"api","port":8080,"upstreams":["a.internal","b.internal"],"limits":{"rps":1e3,"burst":0x10},"empty":[],"todo":{}}"#, document.minify());
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "interning")]
+use myjson::{parse_bytes, parse_bytes_interned};
+use myjson::intern::KeyInterner;
+use myjson::types::JSONValue;
+
+// Keys longer than the inline capacity of `JSONKey`, which are the only ones that are shared
+const ID: &str = "a_rather_long_identifier";
+const NAME: &str = "an_even_longer_display_name";
+
+fn key_ptr(value: &JSONValue, name: &str) -> *const u8 {
+    value.as_object().unwrap().get_key_value(name).unwrap().0.as_ptr()
+}
+
+#[test]
+fn matches_parse_bytes() {
+    let mut interner = KeyInterner::new();
+    let citm_catalog = std::fs::read_to_string("data/citm_catalog.json").unwrap();
+    assert_eq!(parse_bytes(citm_catalog.as_bytes()).unwrap(), parse_bytes_interned(citm_catalog.as_bytes(), &mut interner).unwrap());
+    assert!(!interner.is_empty());
+}
+
+#[test]
+fn repeated_keys_share_storage() {
+    let mut interner = KeyInterner::new();
+    let input = format!(r#"[{{"{ID}": 1, "{NAME}": "a", "id": 1}}, {{"{ID}": 2, "{NAME}": "b", "id": 2}}]"#);
+    let value = parse_bytes_interned(input.as_bytes(), &mut interner).unwrap();
+    assert_eq!(2, interner.len());
+    assert_eq!(key_ptr(&value[0], ID), key_ptr(&value[1], ID));
+    assert_eq!(key_ptr(&value[0], NAME), key_ptr(&value[1], NAME));
+
+    // Keys are shared between documents parsed with the same interner
+    let other = parse_bytes_interned(format!(r#"{{"{ID}": 3}}"#).as_bytes(), &mut interner).unwrap();
+    assert_eq!(key_ptr(&value[0], ID), key_ptr(&other, ID));
+    assert_eq!(2, interner.len());
+
+    interner.clear();
+    assert!(interner.is_empty());
+    let other = parse_bytes_interned(format!(r#"{{"{ID}": 3}}"#).as_bytes(), &mut interner).unwrap();
+    assert_ne!(key_ptr(&value[0], ID), key_ptr(&other, ID));
+}
+
+#[test]
+fn without_interner() {
+    let value = parse_bytes(format!(r#"[{{"{ID}": 1}}, {{"{ID}": 2}}]"#).as_bytes()).unwrap();
+    assert_ne!(key_ptr(&value[0], ID), key_ptr(&value[1], ID));
+}
+
+#[test]
+fn invalid_documents() {
+    let mut interner = KeyInterner::new();
+    for input in ["", r#"{"a" 1}"#, r#"{"\ud800": 1}"#, "{1: 2}", r#"{"a": 1} 2"#] {
+        assert!(parse_bytes_interned(input.as_bytes(), &mut interner).is_err(), "{input}");
+    }
+}
@@ -0,0 +1,132 @@
+use myjson::{json, parse_bytes, parse_bytes_with_dialect, parse_with_dialect, stringify};
+use myjson::types::{Dialect, JSONValue};
+
+// Parses with both parsers, which have to agree
+fn json5(input: &str) -> Option<JSONValue> {
+    let chars = parse_with_dialect(input.chars(), Dialect::Json5).ok();
+    let bytes = parse_bytes_with_dialect(input.as_bytes(), Dialect::Json5).ok();
+    assert_eq!(chars, bytes, "{input}");
+    bytes
+}
+
+fn assert_json5(expected: JSONValue, input: &str) {
+    assert_eq!(Some(expected), json5(input), "{input}");
+}
+
+fn assert_json5_fail(input: &str) {
+    assert_eq!(None, json5(input), "{input}");
+}
+
+#[test]
+fn strict_json_is_still_accepted() {
+    let citm_catalog = std::fs::read_to_string("data/citm_catalog.json").unwrap();
+    assert_eq!(parse_bytes(citm_catalog.as_bytes()).ok(), parse_bytes_with_dialect(citm_catalog.as_bytes(), Dialect::Json5).ok());
+    assert_json5(json!({ "a": [1, -2.5e3, "x", true, false, null] }), r#"{"a": [1, -2.5e3, "x", true, false, null]}"#);
+}
+
+#[test]
+fn strict_is_the_default() {
+    for input in ["{a: 1}", "[1,]", "'a'", "// c\n1", "0x10", ".5", "Infinity"] {
+        assert!(parse_bytes(input.as_bytes()).is_err(), "{input}");
+        assert!(parse_with_dialect(input.chars(), Dialect::Json).is_err(), "{input}");
+    }
+}
+
+#[test]
+fn comments() {
+    assert_json5(json!([1, 2]), "// leading\n[1, /* inline */ 2] // trailing");
+    assert_json5(json!({ "a": 1 }), "/* multi\n line */ {/**/a/***/: 1}");
+    assert_json5(json!(1), "1 // no newline at the end");
+    assert_json5_fail("[1] /* unterminated");
+    assert_json5_fail("[1] /*/");
+    assert_json5_fail("[1 / 2]");
+}
+
+#[test]
+fn whitespace() {
+    assert_json5(json!([1, 2]), "\u{feff}[1,\u{a0}\u{b}\u{c}\u{2028}\u{2003}2]");
+}
+
+#[test]
+fn trailing_commas() {
+    assert_json5(json!([1, 2]), "[1, 2, ]");
+    assert_json5(json!({ "a": 1 }), "{a: 1,}");
+    assert_json5_fail("[,]");
+    assert_json5_fail("{,}");
+    assert_json5_fail("[1,,]");
+    assert_json5_fail("[1,}");
+}
+
+#[test]
+fn identifier_keys() {
+    assert_json5(json!({ "a": 1, "$b": 2, "_c3": 3, "ünï": 4 }), "{a: 1, $b: 2, _c3: 3, ünï: 4}");
+    assert_json5(json!({ "true": 1, "null": 2, "NaN": 3 }), "{true: 1, null: 2, NaN: 3}");
+    assert_json5(json!({ "ab": 1 }), r"{\u0061b: 1}");
+    assert_json5_fail(r"{\u0031: 1}");
+    assert_json5_fail("{1a: 1}");
+    assert_json5_fail("{a-b: 1}");
+    assert_json5_fail("{a b: 1}");
+    assert_json5_fail("[a]");
+    assert_json5_fail("undefined");
+}
+
+#[test]
+fn strings() {
+    assert_json5(json!({ "a": "it's" }), r#"{'a': "it's"}"#);
+    assert_json5(json!("say \"hi\""), r#"'say "hi"'"#);
+    assert_json5(json!("\u{b}\0\u{41}\u{e9}'?"), r"'\v\0\x41\xe9\'\?'");
+    assert_json5(json!("line one line two"), "'line one \\\nline two'");
+    assert_json5(json!("ab"), "'a\\\r\nb'");
+    assert_json5(json!("tab\there"), "'tab\there'");
+    assert_json5_fail("'unterminated");
+    assert_json5_fail("'line\nbreak'");
+    assert_json5_fail(r"'\01'");
+    assert_json5_fail(r"'\1'");
+    assert_json5_fail(r"'\x4'");
+    assert_json5_fail(r#"'mismatched""#);
+}
+
+#[test]
+fn numbers() {
+    assert_json5(json!([255, -16, 10]), "[0xff, -0X10, +0xA]");
+    assert_json5(json!([0.5, 5, -0.25, 1]), "[.5, 5., -.25, +1]");
+    assert_json5(json!([1e3, 1.5e-3]), "[1e3, +.15e-2]");
+    assert_json5(JSONValue::Number { number: f64::INFINITY }, "Infinity");
+    assert_json5(JSONValue::Number { number: f64::NEG_INFINITY }, "-Infinity");
+    for input in ["NaN", "-NaN", "+NaN"] {
+        let Some(JSONValue::Number { number }) = json5(input) else { panic!("{input}") };
+        assert!(number.is_nan(), "{input}");
+    }
+    for input in ["0x", "0xg", ".", "+", "1e", "01", "1.2.3", "--1", "Infinityx", "+-1"] {
+        assert_json5_fail(input);
+    }
+}
+
+#[test]
+fn non_finite_numbers_stringify_as_null() {
+    assert_eq!("[null,null]", stringify(&json5("[Infinity, NaN]").unwrap()));
+}
+
+#[test]
+fn config_file() {
+    let input = r#"
+// Deployment settings
+{
+    name: 'service',
+    replicas: 3,
+    ratio: .75,
+    mask: 0xFF,
+    description: 'a long \
+description',
+    tags: ['a', "b",],
+}
+"#;
+    assert_json5(json!({
+        "name": "service",
+        "replicas": 3,
+        "ratio": 0.75,
+        "mask": 255,
+        "description": "a long description",
+        "tags": ["a", "b"],
+    }), input);
+}
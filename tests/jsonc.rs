@@ -0,0 +1,65 @@
+use myjson::{json, parse_bytes, parse_bytes_with_dialect, parse_with_dialect};
+use myjson::types::{Dialect, JSONValue, ParseError};
+
+// Parses with both parsers, which have to agree
+fn jsonc(input: &str) -> Result<JSONValue, ParseError> {
+    let chars = parse_with_dialect(input.chars(), Dialect::Jsonc);
+    let bytes = parse_bytes_with_dialect(input.as_bytes(), Dialect::Jsonc);
+    assert_eq!(chars, bytes, "{input}");
+    bytes
+}
+
+#[test]
+fn comments() {
+    assert_eq!(Ok(json!([1, 2])), jsonc("// leading\n[1, /* inline */ 2] // trailing"));
+    assert_eq!(Ok(json!({ "a": "// not a comment /* */" })), jsonc(r#"{"a": /**/ "// not a comment /* */"}"#));
+    assert_eq!(Ok(json!({ "a": 1 })), jsonc("{\n  // line\r\n  \"a\": 1 /* block\n spanning lines */\n}"));
+    assert_eq!(Ok(json!(1)), jsonc("1 /***/"));
+    assert!(jsonc("[1 / 2]").is_err());
+    assert!(jsonc("[1] /").is_err());
+}
+
+#[test]
+fn trailing_commas() {
+    assert_eq!(Ok(json!({ "a": [1, 2] })), jsonc(r#"{"a": [1, 2,],}"#));
+    assert!(jsonc("[,]").is_err());
+    assert!(jsonc("[1,,]").is_err());
+    assert!(jsonc(r#"{"a": 1,,}"#).is_err());
+}
+
+#[test]
+fn only_comments_and_commas_are_extensions() {
+    for input in ["{a: 1}", "'a'", "0x10", ".5", "Infinity", "\u{feff}1"] {
+        assert_eq!(Err(ParseError::Unknown), jsonc(input), "{input}");
+    }
+}
+
+#[test]
+fn comments_are_rejected_in_strict_json() {
+    assert!(parse_bytes(b"// c\n1").is_err());
+    assert!(parse_bytes(b"[1, 2,]").is_err());
+}
+
+#[test]
+fn unterminated_block_comment() {
+    assert_eq!(Err(ParseError::UnterminatedComment { offset: 4 }), jsonc("[1, /* 2]"));
+    assert_eq!(Err(ParseError::UnterminatedComment { offset: 4 }), jsonc("[1] /*/"));
+    assert_eq!(Err(ParseError::UnterminatedComment { offset: 2 }), jsonc("{ /*"));
+    // Offsets are in bytes, not characters
+    assert_eq!(Err(ParseError::UnterminatedComment { offset: 11 }), jsonc("[\"ééé\", /* é"));
+    assert_eq!(Err(ParseError::UnterminatedComment { offset: 5 }), parse_with_dialect("1 //\n/*".chars(), Dialect::Json5));
+}
+
+#[test]
+fn tsconfig() {
+    let input = r#"{
+  "compilerOptions": {
+    /* Language and environment */
+    "target": "es2022", // Set the JavaScript language version
+    "lib": ["dom", "es2022",],
+    // "jsx": "preserve",
+    "strict": true,
+  },
+}"#;
+    assert_eq!(Ok(json!({ "compilerOptions": { "target": "es2022", "lib": ["dom", "es2022"], "strict": true } })), jsonc(input));
+}
@@ -0,0 +1,57 @@
+use myjson::{json, parse_bytes, parse_bytes_lazy};
+use myjson::types::ParseError;
+
+#[test]
+fn lookup() {
+    let input = br#" {"skip": [1, {"a": "]}"}, "\"]"], "user": {"name": "a\nb", "age": 42, "admin": false, "tags": ["x", "y"]}, "n": null} "#;
+    let root = parse_bytes_lazy(input).unwrap();
+    let user = root.get("user").unwrap().unwrap();
+    assert_eq!(Some("a\nb".to_string()), user.get("name").unwrap().unwrap().as_str().unwrap());
+    assert_eq!(Some(42.0), user.get("age").unwrap().unwrap().as_f64().unwrap());
+    assert_eq!(Some(false), user.get("admin").unwrap().unwrap().as_bool().unwrap());
+    assert_eq!(Some("y".to_string()), user.get_path(&["tags"]).unwrap().unwrap().get_index(1).unwrap().unwrap().as_str().unwrap());
+    assert!(root.get("n").unwrap().unwrap().is_null().unwrap());
+    assert!(root.get("missing").unwrap().is_none());
+    assert!(root.get_path(&["user", "tags", "x"]).unwrap().is_none());
+    assert!(root.get_index(0).unwrap().is_none());
+    assert_eq!(None, user.as_str().unwrap());
+    assert_eq!(br#"[1, {"a": "]}"}, "\"]"]"#, root.get("skip").unwrap().unwrap().raw().unwrap());
+}
+
+#[test]
+fn iteration() {
+    let root = parse_bytes_lazy(br#"{"a": [1, [2], "3"], "b": {}}"#).unwrap();
+    let keys: Vec<String> = root.members().unwrap().map(|member| member.unwrap().0).collect();
+    assert_eq!(vec!["a", "b"], keys);
+    let elements: Vec<_> = root.get("a").unwrap().unwrap().elements().unwrap().map(|element| element.unwrap().parse().unwrap()).collect();
+    assert_eq!(vec![json!(1), json!([2]), json!("3")], elements);
+    assert_eq!(0, root.get("b").unwrap().unwrap().members().unwrap().count());
+    assert!(root.elements().is_none());
+}
+
+#[test]
+fn parse_matches_parse_bytes() {
+    let citm_catalog = std::fs::read_to_string("data/citm_catalog.json").unwrap();
+    let root = parse_bytes_lazy(citm_catalog.as_bytes()).unwrap();
+    let expected = parse_bytes(citm_catalog.as_bytes()).unwrap();
+    assert_eq!(expected, root.parse().unwrap());
+    assert_eq!(expected["performances"][3], root.get_path(&["performances"]).unwrap().unwrap().get_index(3).unwrap().unwrap().parse().unwrap());
+}
+
+#[test]
+fn errors_surface_when_accessed() {
+    // The broken member is never read so the lookup succeeds
+    let root = parse_bytes_lazy(br#"{"bad": [tru, 1.e], "good": 1}"#).unwrap();
+    assert_eq!(Some(1.0), root.get("good").unwrap().unwrap().as_f64().unwrap());
+    assert!(root.get("bad").unwrap().unwrap().parse().is_err());
+    assert_eq!(Err(ParseError::Unknown), root.get("bad").unwrap().unwrap().get_index(0).unwrap().unwrap().as_bool());
+
+    assert!(parse_bytes_lazy(b"  ").is_err());
+    assert!(parse_bytes_lazy(br#"{"a": 1,}"#).unwrap().get("b").is_err());
+    assert!(parse_bytes_lazy(br#"{"a": [1, 2"#).unwrap().get("b").is_err());
+    assert!(parse_bytes_lazy(br#"{"a" 1}"#).unwrap().get("a").is_err());
+    assert!(parse_bytes_lazy(b"[1 2]").unwrap().get_index(1).is_err());
+    assert!(parse_bytes_lazy(b"[1,]").unwrap().get_index(1).is_err());
+    assert!(parse_bytes_lazy(br#"["a\"#).unwrap().get_index(0).is_err());
+    assert!(parse_bytes_lazy(b"12x").unwrap().as_f64().is_err());
+}
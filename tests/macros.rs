@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+use myjson::{json, parse_bytes};
+use myjson::types::JSONValue;
+
+fn parse(input: &str) -> JSONValue {
+    parse_bytes(input.as_bytes()).unwrap()
+}
+
+#[test]
+fn literals() {
+    assert_eq!(JSONValue::Null, json!(null));
+    assert_eq!(JSONValue::True, json!(true));
+    assert_eq!(JSONValue::False, json!(false));
+    assert_eq!(JSONValue::Number { number: 1.5 }, json!(1.5));
+    assert_eq!(JSONValue::Number { number: -3.0 }, json!(-3));
+    assert_eq!(JSONValue::String { string: "abc".into() }, json!("abc"));
+    assert_eq!(JSONValue::Array { data: vec![] }, json!([]));
+    assert_eq!(parse("{}"), json!({}));
+}
+
+#[test]
+fn nested() {
+    let value = json!({
+        "a": [1, 2.5, "three", null, true, false],
+        "b": { "c": {}, "d": [[], [{}]] },
+        "e f": null
+    });
+    assert_eq!(parse(r#"{"a":[1,2.5,"three",null,true,false],"b":{"c":{},"d":[[],[{}]]},"e f":null}"#), value);
+}
+
+#[test]
+fn trailing_commas() {
+    assert_eq!(parse("[1,2]"), json!([1, 2,]));
+    assert_eq!(parse(r#"{"a":1,"b":[true]}"#), json!({ "a": 1, "b": [true,], }));
+}
+
+#[test]
+fn interpolation() {
+    let name = "ferris";
+    let count = 3u8;
+    let tags = vec!["a", "b"];
+    let mut scores = BTreeMap::new();
+    scores.insert("x".to_string(), 1.0);
+    let missing: Option<i32> = None;
+    let nested = json!([null]);
+    let value = json!({
+        "name": name,
+        "count": count + 1,
+        "tags": tags,
+        "scores": scores,
+        "missing": missing,
+        "len": tags.len(),
+        "nested": nested,
+        "computed": if count > 2 { "big" } else { "small" }
+    });
+    assert_eq!(parse(r#"{"name":"ferris","count":4,"tags":["a","b"],"scores":{"x":1},"missing":null,"len":2,"nested":[null],"computed":"big"}"#), value);
+    assert_eq!(parse(r#"[4,"ferris",[0]]"#), json!([count + 1, name, [count - 3]]));
+}
+
+#[test]
+fn computed_keys() {
+    let key = "dynamic".to_string();
+    let prefix = "p";
+    assert_eq!(parse(r#"{"dynamic":1,"p_1":2}"#), json!({ key: 1, (format!("{prefix}_1")): 2 }));
+}
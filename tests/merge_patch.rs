@@ -0,0 +1,70 @@
+use myjson::{create_merge_patch, merge_patch, parse_bytes};
+use myjson::types::JSONValue;
+
+fn json(input: &str) -> JSONValue {
+    parse_bytes(input.as_bytes()).unwrap()
+}
+
+fn assert_merge_patch(original: &str, patch: &str, expected: &str) {
+    let mut target = json(original);
+    merge_patch(&mut target, &json(patch));
+    assert_eq!(json(expected), target);
+}
+
+fn assert_round_trip(source: &str, target: &str) {
+    let source = json(source);
+    let target = json(target);
+    let patch = create_merge_patch(&source, &target);
+    let mut patched = source.clone();
+    merge_patch(&mut patched, &patch);
+    assert_eq!(target, patched, "patch was {:?}", patch);
+}
+
+#[test]
+fn merge_patch_rfc_example() {
+    assert_merge_patch(
+        r#"{"title": "Goodbye!", "author": {"givenName": "John", "familyName": "Doe"}, "tags": ["example", "sample"], "content": "This will be unchanged"}"#,
+        r#"{"title": "Hello!", "phoneNumber": "+01-123-456-7890", "author": {"familyName": null}, "tags": ["example"]}"#,
+        r#"{"title": "Hello!", "author": {"givenName": "John"}, "tags": ["example"], "content": "This will be unchanged", "phoneNumber": "+01-123-456-7890"}"#,
+    );
+}
+
+#[test]
+fn merge_patch_rfc_appendix() {
+    assert_merge_patch(r#"{"a":"b"}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#);
+    assert_merge_patch(r#"{"a":"b"}"#, r#"{"b":"c"}"#, r#"{"a":"b","b":"c"}"#);
+    assert_merge_patch(r#"{"a":"b"}"#, r#"{"a":null}"#, r#"{}"#);
+    assert_merge_patch(r#"{"a":"b","b":"c"}"#, r#"{"a":null}"#, r#"{"b":"c"}"#);
+    assert_merge_patch(r#"{"a":["b"]}"#, r#"{"a":"c"}"#, r#"{"a":"c"}"#);
+    assert_merge_patch(r#"{"a":"c"}"#, r#"{"a":["b"]}"#, r#"{"a":["b"]}"#);
+    assert_merge_patch(r#"{"a":{"b":"c"}}"#, r#"{"a":{"b":"d","c":null}}"#, r#"{"a":{"b":"d"}}"#);
+    assert_merge_patch(r#"{"a":[{"b":"c"}]}"#, r#"{"a":[1]}"#, r#"{"a":[1]}"#);
+    assert_merge_patch(r#"["a","b"]"#, r#"["c","d"]"#, r#"["c","d"]"#);
+    assert_merge_patch(r#"{"a":"b"}"#, r#"["c"]"#, r#"["c"]"#);
+    assert_merge_patch(r#"{"a":"foo"}"#, r#"null"#, r#"null"#);
+    assert_merge_patch(r#"{"a":"foo"}"#, r#""bar""#, r#""bar""#);
+    assert_merge_patch(r#"{"e":null}"#, r#"{"a":1}"#, r#"{"e":null,"a":1}"#);
+    assert_merge_patch(r#"[1,2]"#, r#"{"a":"b","c":null}"#, r#"{"a":"b"}"#);
+    assert_merge_patch(r#"{}"#, r#"{"a":{"bb":{"ccc":null}}}"#, r#"{"a":{"bb":{}}}"#);
+}
+
+#[test]
+fn create_merge_patch_minimal() {
+    assert_eq!(json(r#"{}"#), create_merge_patch(&json(r#"{"a":1}"#), &json(r#"{"a":1}"#)));
+    assert_eq!(json(r#"{"a":null}"#), create_merge_patch(&json(r#"{"a":1,"b":2}"#), &json(r#"{"b":2}"#)));
+    assert_eq!(json(r#"{"a":{"c":3}}"#), create_merge_patch(&json(r#"{"a":{"b":2}}"#), &json(r#"{"a":{"b":2,"c":3}}"#)));
+    assert_eq!(json(r#"[2]"#), create_merge_patch(&json(r#"[1]"#), &json(r#"[2]"#)));
+}
+
+#[test]
+fn create_merge_patch_round_trip() {
+    assert_round_trip(r#"{"a":"b"}"#, r#"{"a":"c"}"#);
+    assert_round_trip(r#"{"a":"b"}"#, r#"{"b":"c"}"#);
+    assert_round_trip(r#"{"a":"b","b":"c"}"#, r#"{"b":"c"}"#);
+    assert_round_trip(r#"{"a":["b"]}"#, r#"{"a":"c"}"#);
+    assert_round_trip(r#"{"a":{"b":"c"}}"#, r#"{"a":{"b":"d"}}"#);
+    assert_round_trip(r#"{"a":"b"}"#, r#"["c"]"#);
+    assert_round_trip(r#"{"a":"foo"}"#, r#"null"#);
+    assert_round_trip(r#"[1,2]"#, r#"{"a":"b"}"#);
+    assert_round_trip(r#"{"a":1}"#, r#"{"a":{"b":{"c":true}}}"#);
+}
@@ -21,7 +21,7 @@ fn parse_literal() {
     assert_parse(False, "false");
     assert_parse(Null, "null");
     assert_parse(Number { number: 0. }, "0");
-    assert_parse( JSONValue::String { string: "".to_string() }, r#""""#);
+    assert_parse( JSONValue::String { string: "".into() }, r#""""#);
 }
 
 #[test]
@@ -36,18 +36,18 @@ fn parse_empty_object() {
 
 #[test]
 fn parse_varied_array() {
-    assert_parse(Array { data: vec![Number { number: 0. }, myjson::types::JSONValue::String { string: "".to_string() }, Array{ data: vec![] }, Object { data: Default::default() }, True, False, Null] }, r#"[0.0, "", [], {}, true, false, null]"#);
+    assert_parse(Array { data: vec![Number { number: 0. }, myjson::types::JSONValue::String { string: "".into() }, Array{ data: vec![] }, Object { data: Default::default() }, True, False, Null] }, r#"[0.0, "", [], {}, true, false, null]"#);
 }
 
 #[test]
 fn parse_object() {
     assert_parse(Object {
         data: FxHashMap::from_iter([
-            ("a".to_string(), Array { data: vec![] }),
-            ("b".to_string(), Object{ data: Default::default() }),
-            ("c".to_string(), Number { number: 0. }),
-            ("d".to_string(), Array { data: vec![Object{ data: Default::default() }] }),
-            ("e".to_string(), myjson::types::JSONValue::String { string: "f".to_string() })
+            ("a".into(), Array { data: vec![] }),
+            ("b".into(), Object{ data: Default::default() }),
+            ("c".into(), Number { number: 0. }),
+            ("d".into(), Array { data: vec![Object{ data: Default::default() }] }),
+            ("e".into(), myjson::types::JSONValue::String { string: "f".into() })
         ])
     }, r#"{"a": [], "b": {}, "c": 0.0, "d": [{}], "e": "f"}"#)
 }
@@ -108,4 +108,12 @@ fn parse_no_bad_object() {
     assert_parse_fail(r#"{{}}"#);
     assert_parse_fail(r#"{"a}"#);
     assert_parse_fail(r#"{"a":true)}"#);
-}
\ No newline at end of file
+}
+
+#[test]
+fn from_str_and_try_from() {
+    assert_eq!(Ok(Array { data: vec![True, Null] }), "[true, null]".parse::<JSONValue>());
+    assert!("[true,".parse::<JSONValue>().is_err());
+    assert_eq!(Ok(Number { number: 1.0 }), JSONValue::try_from(&b"1"[..]));
+    assert!(JSONValue::try_from(&b"nul"[..]).is_err());
+}
@@ -0,0 +1,131 @@
+use myjson::{json, query, Query};
+use myjson::query::QueryError;
+use myjson::types::JSONValue;
+
+fn run(source: &str, input: &JSONValue) -> Vec<JSONValue> {
+    query(source, input).unwrap_or_else(|error| panic!("{source}: {error}"))
+}
+
+fn input() -> JSONValue {
+    json!({
+        "name": "api",
+        "servers": [
+            { "host": "a.internal", "port": 80, "tags": ["web"] },
+            { "host": "b.internal", "port": 443, "tags": [] },
+            { "host": "c.internal", "port": 8080, "tags": ["web", "canary"] }
+        ],
+        "odd key": null
+    })
+}
+
+#[test]
+fn paths() {
+    let input = input();
+    assert_eq!(vec![input.clone()], run(".", &input));
+    assert_eq!(vec![json!("api")], run(".name", &input));
+    assert_eq!(vec![json!("b.internal")], run(".servers[1].host", &input));
+    assert_eq!(vec![json!("c.internal")], run(".servers[-1].host", &input));
+    assert_eq!(vec![json!("c.internal")], run(".servers.[2] | .host", &input));
+    assert_eq!(vec![json!(null)], run(r#".["odd key"]"#, &input));
+    assert_eq!(vec![json!(null)], run(r#"."odd key""#, &input));
+    assert_eq!(vec![json!(null)], run(".missing.deeper", &input));
+    assert_eq!(vec![json!(null)], run(".servers[10]", &input));
+}
+
+#[test]
+fn iteration_and_pipes() {
+    let input = input();
+    let hosts = vec![json!("a.internal"), json!("b.internal"), json!("c.internal")];
+    assert_eq!(hosts, run(".servers[] | .host", &input));
+    assert_eq!(hosts, run(".servers[].host", &input));
+    assert_eq!(vec![json!("api"), json!(80)], run(".name, .servers[0].port", &input));
+    assert_eq!(vec![json!([1, [2]]), json!(1), json!([2]), json!(2)], run("..", &json!([1, [2]])));
+    assert_eq!(vec![json!(1), json!(2)], run(".[]", &json!({ "a": 1 })).into_iter().chain(run(".[]", &json!([2]))).collect::<Vec<_>>());
+}
+
+#[test]
+fn select_and_map() {
+    let input = input();
+    assert_eq!(vec![json!("b.internal"), json!("c.internal")], run(".servers[] | select(.port > 100) | .host", &input));
+    assert_eq!(vec![json!("c.internal")], run(r#".servers[] | select(.tags[] == "canary") | .host"#, &input));
+    assert_eq!(vec![json!([80, 443, 8080])], run("[.servers[].port]", &input));
+    assert_eq!(vec![json!([81, 444, 8081])], run("map(.port + 1)", &input["servers"]));
+    assert_eq!(vec![json!([1, 0, 2])], run(".servers | map(.tags | length)", &input));
+}
+
+#[test]
+fn builtins() {
+    let input = input();
+    assert_eq!(vec![json!(["name", "odd key", "servers"])], run("keys", &input));
+    assert_eq!(vec![json!([0, 1, 2])], run(".servers | keys", &input));
+    assert_eq!(vec![json!(3), json!(3), json!(0), json!(5)], run(".servers, .name, null, -5 | length", &input));
+    assert_eq!(vec![json!(true), json!(false)], run(r#"has("name"), (.servers | has(3))"#, &input));
+    assert_eq!(vec![json!("object"), json!("array"), json!("boolean")], run(".,  .servers, true | type", &input));
+    assert_eq!(vec![json!(8603)], run("[.servers[].port] | add", &input));
+    assert_eq!(vec![json!(["web", "web", "canary"])], run("[.servers[].tags] | add", &input));
+    assert_eq!(Vec::<JSONValue>::new(), run("empty", &input));
+    assert_eq!(vec![json!(false), json!(true)], run("true, null | not", &input));
+}
+
+#[test]
+fn construction() {
+    let input = input();
+    assert_eq!(vec![json!({ "name": "api", "count": 3 })], run("{name, count: (.servers | length)}", &input));
+    assert_eq!(vec![json!({ "a.internal": 80 })], run("{(.servers[0].host): .servers[0].port}", &input));
+    assert_eq!(vec![json!({ "a": 1, "b": 3 }), json!({ "a": 2, "b": 3 })], run(r#"{"a": (1, 2), b: 3}"#, &input));
+    assert_eq!(vec![json!([]), json!({})], run("[], {}", &input));
+    assert_eq!(vec![json!([1, "two", null, true, 2.5e3])], run(r#"[1, "two", null, true, 2.5e3]"#, &input));
+}
+
+#[test]
+fn operators() {
+    let null = json!(null);
+    assert_eq!(vec![json!(7), json!(1), json!(12), json!(1.5), json!(1)], run("3 + 4, 4 - 3, 3 * 4, 3 / 2, 7 % 3", &null));
+    assert_eq!(vec![json!(7)], run("1 + 2 * 3", &null));
+    assert_eq!(vec![json!(-2)], run("-(1 + 1)", &null));
+    assert_eq!(vec![json!("ab"), json!([1, 2]), json!([2]), json!({ "a": 1, "b": 2 })], run(r#""a" + "b", [1] + [2], [1, 2] - [1], {a: 1} + {b: 2}"#, &null));
+    assert_eq!(vec![json!(true), json!(false), json!(true), json!(true)], run(r#"1 == 1.0, 1 != 1, "a" < "b", [1] >= null"#, &null));
+    assert_eq!(vec![json!(false), json!(true), json!(true)], run("true and false, false or 1, null or true", &null));
+    // The right operand is the outer loop, as in jq
+    assert_eq!(vec![json!(11), json!(12), json!(21), json!(22)], run("(1, 2) + (10, 20)", &null));
+}
+
+#[test]
+fn optional() {
+    let input = json!([1, { "a": 2 }]);
+    assert_eq!(vec![json!(2)], run(".[] | .a?", &input));
+    assert_eq!(Vec::<JSONValue>::new(), run(".a?", &input));
+}
+
+#[test]
+fn runtime_errors() {
+    let input = input();
+    let error = |source: &str| query(source, &input).unwrap_err().to_string();
+    assert_eq!(r#"cannot index array with string ("host")"#, error(".servers.host"));
+    assert_eq!("cannot iterate over string (\"api\")", error(".name[]"));
+    assert_eq!("number (1.0) and string (\"a\") cannot be added", error(r#"1 + "a""#));
+    assert_eq!("number (1.0) and number (0.0) cannot be divided because the divisor is zero", error("1 / 0"));
+    assert_eq!("object keys must be strings, found number (1.0)", error("{(1): 2}"));
+}
+
+#[test]
+fn syntax_errors() {
+    let error = |source: &str| match Query::compile(source) {
+        Err(QueryError::Syntax { offset, message }) => (offset, message),
+        other => panic!("{source}: {other:?}"),
+    };
+    assert_eq!((5, "expected `]`, found the end of the query".to_string()), error(".a[1 "));
+    assert_eq!((3, "unexpected `)`".to_string()), error(".a )"));
+    assert_eq!((0, "unknown function `frobnicate`".to_string()), error("frobnicate"));
+    assert_eq!((0, "`select` takes 1 argument(s)".to_string()), error("select"));
+    assert_eq!((2, "unexpected character `#`".to_string()), error(". # comment"));
+    assert_eq!((1, "unterminated string".to_string()), error(r#"."abc"#));
+    assert_eq!((4, "expected a filter, found the end of the query".to_string()), error(".a |"));
+}
+
+#[test]
+fn compiled_queries_are_reusable() {
+    let query = Query::compile(".[0]").unwrap();
+    assert_eq!(vec![json!(1)], query.run(&json!([1])).unwrap());
+    assert_eq!(vec![json!("x")], query.run(&json!(["x", "y"])).unwrap());
+}
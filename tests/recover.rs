@@ -0,0 +1,93 @@
+use myjson::{json, parse_recovering};
+use myjson::recover::Diagnostic;
+use myjson::types::Dialect;
+
+fn messages(text: &str, dialect: Dialect) -> Vec<(usize, usize, String)> {
+    parse_recovering(text, dialect).1.into_iter().map(|d| (d.span.start, d.span.end, d.message)).collect()
+}
+
+fn diagnostic(start: usize, end: usize, message: &str) -> (usize, usize, String) {
+    (start, end, message.to_string())
+}
+
+#[test]
+fn valid_input_has_no_diagnostics() {
+    let (value, diagnostics) = parse_recovering(r#" {"a": [1, true, null], "b": "c"} "#, Dialect::Json);
+    assert_eq!(json!({ "a": [1, true, null], "b": "c" }), value);
+    assert!(diagnostics.is_empty());
+    let (value, diagnostics) = parse_recovering("// c\n{a: 'b', c: [0x10,],}", Dialect::Json5);
+    assert_eq!(json!({ "a": "b", "c": [16] }), value);
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn missing_commas_and_colons() {
+    let text = r#"{"a": 1 "b" 2, "c": [1 2]}"#;
+    let (value, diagnostics) = parse_recovering(text, Dialect::Json);
+    assert_eq!(json!({ "a": 1, "b": 2, "c": [1, 2] }), value);
+    assert_eq!(vec![
+        Diagnostic { span: 8..8, message: "missing comma".to_string() },
+        Diagnostic { span: 12..12, message: "expected `:`".to_string() },
+        Diagnostic { span: 23..23, message: "missing comma".to_string() },
+    ], diagnostics);
+}
+
+#[test]
+fn unclosed_brackets() {
+    let (value, diagnostics) = parse_recovering(r#"{"a": [1, {"b": 2"#, Dialect::Json);
+    assert_eq!(json!({ "a": [1, { "b": 2 }] }), value);
+    assert_eq!(vec!["unclosed object", "unclosed array", "unclosed object"], diagnostics.iter().map(|d| d.message.as_str()).collect::<Vec<_>>());
+    assert_eq!(vec![10..11, 6..7, 0..1], diagnostics.into_iter().map(|d| d.span).collect::<Vec<_>>());
+}
+
+#[test]
+fn mismatched_brackets() {
+    let (value, _) = parse_recovering(r#"{"a": [1, 2}, "b": 3}"#, Dialect::Json);
+    assert_eq!(json!({ "a": [1, 2] }), value);
+    assert_eq!(vec![diagnostic(11, 12, "expected `]`"), diagnostic(12, 21, "unexpected content after the value")], messages(r#"{"a": [1, 2}, "b": 3}"#, Dialect::Json));
+    // A no-break space is not JSON whitespace, and the span has to end on a character boundary
+    assert_eq!(vec![diagnostic(1, 3, "unexpected content after the value")], messages("1\u{a0}", Dialect::Json));
+    assert_eq!(vec![diagnostic(2, 5, "unexpected content after the value")], messages("1 \u{2028}", Dialect::Json));
+}
+
+#[test]
+fn invalid_values_become_null() {
+    let (value, diagnostics) = parse_recovering(r#"[tru, 01, "ok", @, "open"#, Dialect::Json);
+    assert_eq!(json!([null, null, "ok", null, null]), value);
+    assert_eq!(vec![
+        Diagnostic { span: 1..4, message: "invalid value".to_string() },
+        Diagnostic { span: 6..8, message: "invalid value".to_string() },
+        Diagnostic { span: 16..17, message: "invalid value".to_string() },
+        Diagnostic { span: 19..24, message: "unterminated string".to_string() },
+        Diagnostic { span: 0..1, message: "unclosed array".to_string() },
+    ], diagnostics);
+}
+
+#[test]
+fn stray_separators() {
+    assert_eq!(vec![diagnostic(1, 2, "unexpected comma"), diagnostic(4, 5, "unexpected comma"), diagnostic(6, 7, "trailing comma")], messages("[,1,,2,]", Dialect::Json));
+    assert!(messages("[1,]", Dialect::Jsonc).is_empty());
+    assert_eq!(vec![diagnostic(1, 1, "expected a key"), diagnostic(11, 11, "expected a value")], messages(r#"{: 1, "a": }"#, Dialect::Json));
+    assert_eq!(vec![diagnostic(2, 3, "unexpected colon")], messages("[1:2]", Dialect::Json));
+    assert_eq!(vec![diagnostic(0, 0, "expected a value")], messages("", Dialect::Json));
+}
+
+#[test]
+fn keys() {
+    let (value, diagnostics) = parse_recovering(r#"{a: 1, "b": 2, [3]: 4}"#, Dialect::Json);
+    assert_eq!(json!({ "b": 2 }), value);
+    assert_eq!(vec!["invalid key", "expected a key"], diagnostics.iter().map(|d| d.message.as_str()).collect::<Vec<_>>());
+}
+
+#[test]
+fn comments() {
+    assert_eq!(vec![diagnostic(1, 8, "comments are not allowed in JSON")], messages("[/* c */1]", Dialect::Json));
+    let (value, diagnostics) = parse_recovering("[1, /* never closed", Dialect::Jsonc);
+    assert_eq!(json!([1]), value);
+    assert_eq!(vec![diagnostic(4, 19, "unterminated comment"), diagnostic(0, 1, "unclosed array")], diagnostics.into_iter().map(|d| (d.span.start, d.span.end, d.message)).collect::<Vec<_>>());
+}
+
+#[test]
+fn display() {
+    assert_eq!("missing comma at 3..3", Diagnostic { span: 3..3, message: "missing comma".to_string() }.to_string());
+}
@@ -0,0 +1,195 @@
+use myjson::parse_bytes;
+use myjson::schema::{Schema, SchemaError, ValidationError};
+use myjson::types::JSONValue;
+
+fn json(input: &str) -> JSONValue {
+    parse_bytes(input.as_bytes()).unwrap()
+}
+
+fn compile(schema: &str) -> Schema {
+    Schema::compile(&json(schema)).unwrap()
+}
+
+fn assert_valid(schema: &str, instance: &str) {
+    let res = compile(schema).validate(&json(instance));
+    assert!(res.is_ok(), "Expected {instance} to be valid, found {:?}", res.unwrap_err());
+}
+
+fn assert_invalid(schema: &str, instance: &str) {
+    assert!(!compile(schema).is_valid(&json(instance)), "Expected {instance} to be invalid");
+}
+
+fn error_paths(schema: &str, instance: &str) -> Vec<(String, String)> {
+    compile(schema)
+        .validate(&json(instance))
+        .unwrap_err()
+        .into_iter()
+        .map(|ValidationError { instance_path, schema_path, .. }| (instance_path, schema_path))
+        .collect()
+}
+
+fn paths(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+    expected.iter().map(|(a, b)| (a.to_string(), b.to_string())).collect()
+}
+
+#[test]
+fn boolean_schemas() {
+    assert_valid("true", r#"{"a": 1}"#);
+    assert_invalid("false", "null");
+    assert_valid("{}", "[1, 2]");
+}
+
+#[test]
+fn types() {
+    assert_valid(r#"{"type": "string"}"#, r#""a""#);
+    assert_invalid(r#"{"type": "string"}"#, "1");
+    assert_valid(r#"{"type": "integer"}"#, "1.0");
+    assert_invalid(r#"{"type": "integer"}"#, "1.5");
+    assert_valid(r#"{"type": "number"}"#, "1");
+    assert_valid(r#"{"type": ["null", "boolean"]}"#, "null");
+    assert_valid(r#"{"type": ["null", "boolean"]}"#, "false");
+    assert_invalid(r#"{"type": ["null", "boolean"]}"#, "{}");
+    assert_valid(r#"{"type": "object"}"#, "{}");
+    assert_valid(r#"{"type": "array"}"#, "[]");
+}
+
+#[test]
+fn enum_and_const() {
+    assert_valid(r#"{"enum": [1, "a", [true]]}"#, "[true]");
+    assert_invalid(r#"{"enum": [1, "a", [true]]}"#, "[false]");
+    assert_valid(r#"{"const": {"a": null}}"#, r#"{"a": null}"#);
+    assert_invalid(r#"{"const": {"a": null}}"#, r#"{"a": 1}"#);
+}
+
+#[test]
+fn object_keywords() {
+    let schema = r#"{
+        "properties": {"a": {"type": "integer"}},
+        "patternProperties": {"^x-": {"type": "string"}},
+        "additionalProperties": false,
+        "required": ["a"]
+    }"#;
+    assert_valid(schema, r#"{"a": 1, "x-b": "c"}"#);
+    assert_invalid(schema, r#"{"x-b": "c"}"#);
+    assert_invalid(schema, r#"{"a": 1, "x-b": 1}"#);
+    assert_invalid(schema, r#"{"a": 1, "b": 1}"#);
+    assert_valid(r#"{"minProperties": 1, "maxProperties": 2}"#, r#"{"a": 1}"#);
+    assert_invalid(r#"{"minProperties": 1, "maxProperties": 2}"#, r#"{}"#);
+    assert_invalid(r#"{"minProperties": 1, "maxProperties": 2}"#, r#"{"a": 1, "b": 2, "c": 3}"#);
+}
+
+#[test]
+fn array_keywords() {
+    let schema = r#"{"prefixItems": [{"type": "string"}], "items": {"type": "number"}, "minItems": 1, "maxItems": 3}"#;
+    assert_valid(schema, r#"["a", 1, 2]"#);
+    assert_invalid(schema, r#"[1, 1, 2]"#);
+    assert_invalid(schema, r#"["a", "b"]"#);
+    assert_invalid(schema, r#"[]"#);
+    assert_invalid(schema, r#"["a", 1, 2, 3]"#);
+    assert_valid(r#"{"uniqueItems": true}"#, r#"[1, "1", [1]]"#);
+    assert_invalid(r#"{"uniqueItems": true}"#, r#"[{"a": 1}, {"a": 1}]"#);
+    assert_valid(r#"{"contains": {"const": 2}}"#, r#"[1, 2]"#);
+    assert_invalid(r#"{"contains": {"const": 2}}"#, r#"[1, 3]"#);
+}
+
+#[test]
+fn numeric_bounds() {
+    let schema = r#"{"minimum": 1, "exclusiveMaximum": 10, "multipleOf": 0.5}"#;
+    assert_valid(schema, "1");
+    assert_valid(schema, "9.5");
+    assert_invalid(schema, "0.5");
+    assert_invalid(schema, "10");
+    assert_invalid(schema, "2.25");
+    assert_valid(r#"{"exclusiveMinimum": 0, "maximum": 1}"#, "1");
+    assert_invalid(r#"{"exclusiveMinimum": 0, "maximum": 1}"#, "0");
+    assert_valid(r#"{"multipleOf": 0.1}"#, "0.3");
+    assert_valid(r#"{"minimum": 5}"#, r#""not a number""#);
+}
+
+#[test]
+fn string_bounds() {
+    let schema = r#"{"minLength": 2, "maxLength": 3, "pattern": "^[a-z]+$"}"#;
+    assert_valid(schema, r#""ab""#);
+    assert_invalid(schema, r#""a""#);
+    assert_invalid(schema, r#""abcd""#);
+    assert_invalid(schema, r#""AB""#);
+    assert_valid(r#"{"maxLength": 2}"#, r#""🫸🏿""#);
+    assert_valid(r#"{"pattern": "b"}"#, r#""abc""#);
+}
+
+#[test]
+fn references() {
+    let schema = r##"{
+        "$defs": {
+            "node": {"type": "object", "properties": {"children": {"type": "array", "items": {"$ref": "#/$defs/node"}}}, "required": ["name"]},
+            "named": {"$anchor": "named", "properties": {"name": {"type": "string"}}}
+        },
+        "allOf": [{"$ref": "#/$defs/node"}, {"$ref": "#named"}]
+    }"##;
+    assert_valid(schema, r#"{"name": "a", "children": [{"name": "b", "children": []}]}"#);
+    assert_invalid(schema, r#"{"name": "a", "children": [{"children": []}]}"#);
+    assert_invalid(schema, r#"{"name": 1}"#);
+    assert_valid(r##"{"properties": {"a": {"$ref": "#"}}, "type": "object"}"##, r#"{"a": {"a": {}}}"#);
+    assert_invalid(r##"{"properties": {"a": {"$ref": "#"}}, "type": "object"}"##, r#"{"a": {"a": 1}}"#);
+}
+
+#[test]
+fn combinators() {
+    let any_of = r#"{"anyOf": [{"type": "string"}, {"minimum": 0}]}"#;
+    assert_valid(any_of, r#""a""#);
+    assert_valid(any_of, "1");
+    assert_invalid(any_of, "-1");
+    let one_of = r#"{"oneOf": [{"type": "integer"}, {"minimum": 0}]}"#;
+    assert_valid(one_of, "1.5");
+    assert_valid(one_of, "-1");
+    assert_invalid(one_of, "1");
+    assert_invalid(one_of, "-1.5");
+    assert_valid(r#"{"not": {"type": "null"}}"#, "1");
+    assert_invalid(r#"{"not": {"type": "null"}}"#, "null");
+    assert_valid(r#"{"allOf": [{"type": "number"}, {"minimum": 0}]}"#, "1");
+    assert_invalid(r#"{"allOf": [{"type": "number"}, {"minimum": 0}]}"#, "-1");
+}
+
+#[test]
+fn reports_every_failure() {
+    let schema = r##"{
+        "$defs": {"positive": {"exclusiveMinimum": 0}},
+        "type": "object",
+        "properties": {
+            "a/b": {"type": "string"},
+            "c": {"items": {"$ref": "#/$defs/positive"}}
+        },
+        "required": ["d"]
+    }"##;
+    assert_eq!(paths(&[
+        ("/a~1b", "/properties/a~1b/type"),
+        ("/c/1", "/properties/c/items/$ref/exclusiveMinimum"),
+        ("", "/required"),
+    ]), error_paths(schema, r#"{"a/b": 1, "c": [1, -1]}"#));
+    assert_eq!(paths(&[("", "/anyOf")]), error_paths(r#"{"anyOf": [false]}"#, "1"));
+}
+
+#[test]
+fn error_messages() {
+    let errors = compile(r#"{"type": ["string", "null"]}"#).validate(&json("1")).unwrap_err();
+    assert_eq!("expected string or null, found integer", errors[0].message);
+}
+
+#[test]
+fn invalid_schemas() {
+    assert_eq!(Err(SchemaError::InvalidKeyword { path: "".to_string(), keyword: "type".to_string() }), Schema::compile(&json(r#"{"type": "str"}"#)).map(|_| ()));
+    assert_eq!(Err(SchemaError::InvalidKeyword { path: "/properties/a".to_string(), keyword: "minLength".to_string() }), Schema::compile(&json(r#"{"properties": {"a": {"minLength": -1}}}"#)).map(|_| ()));
+    assert_eq!(Err(SchemaError::InvalidPattern { path: "".to_string(), pattern: "(".to_string() }), Schema::compile(&json(r#"{"pattern": "("}"#)).map(|_| ()));
+    assert_eq!(Err(SchemaError::UnresolvedReference { path: "".to_string(), reference: "#/$defs/a".to_string() }), Schema::compile(&json(r##"{"$ref": "#/$defs/a"}"##)).map(|_| ()));
+    assert!(Schema::compile(&json("1")).is_err());
+}
+
+#[test]
+fn reference_cycles() {
+    let cycle = |path: &str| Err(SchemaError::ReferenceCycle { path: path.to_string() });
+    assert_eq!(cycle(""), Schema::compile(&json(r##"{"$ref": "#"}"##)).map(|_| ()));
+    assert_eq!(cycle("/$defs/a"), Schema::compile(&json(r##"{"$defs": {"a": {"$ref": "#/$defs/a"}}, "$ref": "#/$defs/a"}"##)).map(|_| ()));
+    assert_eq!(cycle("/$defs/b/not"), Schema::compile(&json(r##"{"$defs": {"a": {"anyOf": [{"type": "string"}, {"$ref": "#/$defs/b"}]}, "b": {"not": {"$ref": "#/$defs/a"}}}, "$ref": "#/$defs/a"}"##)).map(|_| ()));
+    // Recursion through a property or an item moves on to a smaller part of the instance
+    assert!(Schema::compile(&json(r##"{"items": {"$ref": "#"}, "allOf": [{"properties": {"a": {"$ref": "#"}}}]}"##)).is_ok());
+}
@@ -0,0 +1,109 @@
+#![cfg(feature = "serde")]
+
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use myjson::parse_bytes;
+use myjson::serde::{from_str, to_string};
+use myjson::types::JSONValue;
+
+fn json(input: &str) -> JSONValue {
+    parse_bytes(input.as_bytes()).unwrap()
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct Tweet {
+    id: u64,
+    text: String,
+    retweeted: bool,
+    coordinates: Option<(f64, f64)>,
+    tags: Vec<String>,
+    user: User,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct User {
+    #[serde(rename = "screen_name")]
+    name: String,
+    followers: i32,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Point(i8, i8),
+    Rect { w: u8, h: u8 },
+}
+
+fn tweet() -> Tweet {
+    Tweet {
+        id: 12345,
+        text: "hello \"world\"\n".to_string(),
+        retweeted: false,
+        coordinates: Some((1.5, -2.0)),
+        tags: vec!["a".to_string(), "b".to_string()],
+        user: User { name: "me".to_string(), followers: -1 },
+    }
+}
+
+#[test]
+fn deserialize_struct() {
+    let input = r#" {"id": 12345, "text": "hello \"world\"\n", "retweeted": false, "coordinates": [1.5, -2.0],
+        "tags": ["a", "b"], "extra": {"ignored": [null]}, "user": {"screen_name": "me", "followers": -1}} "#;
+    assert_eq!(Ok(tweet()), from_str::<Tweet>(input));
+}
+
+#[test]
+fn deserialize_primitives() {
+    assert_eq!(Ok(None), from_str::<Option<u8>>("null"));
+    assert_eq!(Ok(Some(3)), from_str::<Option<u8>>("3"));
+    assert_eq!(Ok(-3.25), from_str::<f32>("-325e-2"));
+    assert_eq!(Ok('x'), from_str::<char>(r#""x""#));
+    assert_eq!(Ok(()), from_str::<()>("null"));
+    assert_eq!(Ok(BTreeMap::from([("a".to_string(), vec![1u8]), ("b".to_string(), vec![])])), from_str(r#"{"a": [1], "b": []}"#));
+}
+
+#[test]
+fn deserialize_enum() {
+    assert_eq!(Ok(Shape::Empty), from_str::<Shape>(r#""Empty""#));
+    assert_eq!(Ok(Shape::Circle(1.0)), from_str::<Shape>(r#"{"Circle": 1}"#));
+    assert_eq!(Ok(Shape::Point(1, -1)), from_str::<Shape>(r#"{"Point": [1, -1]}"#));
+    assert_eq!(Ok(Shape::Rect { w: 1, h: 2 }), from_str::<Shape>(r#"{"Rect": {"w": 1, "h": 2}}"#));
+}
+
+#[test]
+fn deserialize_fail() {
+    assert!(from_str::<u8>("256").is_err());
+    assert!(from_str::<u8>("1.5").is_err());
+    assert!(from_str::<Vec<u8>>("[1,]").is_err());
+    assert!(from_str::<Vec<u8>>("[1 2]").is_err());
+    assert!(from_str::<User>(r#"{"screen_name": "a"}"#).is_err());
+    assert!(from_str::<User>(r#"{"screen_name": "a", "followers": 1,}"#).is_err());
+    assert!(from_str::<bool>("true false").is_err());
+    assert!(from_str::<Shape>(r#""Other""#).is_err());
+}
+
+#[test]
+fn serialize() {
+    let expected = r#"{"id":12345,"text":"hello \"world\"\n","retweeted":false,"coordinates":[1.5,-2.0],"tags":["a","b"],"user":{"screen_name":"me","followers":-1}}"#;
+    assert_eq!(Ok(expected.to_string()), to_string(&tweet()));
+    assert_eq!(Ok("null".to_string()), to_string(&None::<u8>));
+    assert_eq!(Ok("null".to_string()), to_string(&f64::NAN));
+    assert_eq!(Ok(r#"{"1":true}"#.to_string()), to_string(&BTreeMap::from([(1, true)])));
+    assert!(to_string(&BTreeMap::from([(vec![1], true)])).is_err());
+}
+
+#[test]
+fn serialize_enum() {
+    assert_eq!(Ok(r#""Empty""#.to_string()), to_string(&Shape::Empty));
+    assert_eq!(Ok(r#"{"Circle":1.0}"#.to_string()), to_string(&Shape::Circle(1.0)));
+    assert_eq!(Ok(r#"{"Point":[1,-1]}"#.to_string()), to_string(&Shape::Point(1, -1)));
+    assert_eq!(Ok(r#"{"Rect":{"w":1,"h":2}}"#.to_string()), to_string(&Shape::Rect { w: 1, h: 2 }));
+}
+
+#[test]
+fn json_value_round_trip() {
+    let value = json(r#"{"a": [1, 2.5, "x", null, true, false, {}]}"#);
+    assert_eq!(Ok(value.clone()), from_str::<JSONValue>(&to_string(&value).unwrap()));
+    assert_eq!(Ok(value), from_str::<JSONValue>(r#"{"a": [1, 2.5, "x", null, true, false, {}]}"#));
+}
@@ -0,0 +1,57 @@
+use std::collections::HashSet;
+use myjson::parse_bytes;
+use myjson::types::JSONValue;
+use myjson::small_string::{SmallString, INLINE_CAPACITY};
+
+#[test]
+fn same_size_as_string() {
+    assert_eq!(size_of::<String>(), size_of::<SmallString>());
+}
+
+#[test]
+fn inline_up_to_capacity() {
+    assert!(SmallString::new().is_inline());
+    let short = "a".repeat(INLINE_CAPACITY);
+    assert!(SmallString::from(short.as_str()).is_inline());
+    assert!(SmallString::from(short.clone()).is_inline());
+    let long = "a".repeat(INLINE_CAPACITY + 1);
+    assert!(!SmallString::from(long.as_str()).is_inline());
+    assert!(!SmallString::from(long.clone()).is_inline());
+    assert_eq!(long, SmallString::from(long.as_str()));
+}
+
+#[test]
+fn multibyte_characters() {
+    for len in 0..12 {
+        let string = "é".repeat(len);
+        let small = SmallString::from(string.as_str());
+        assert_eq!(string.len() <= INLINE_CAPACITY, small.is_inline());
+        assert_eq!(string, small);
+        assert_eq!(len, small.chars().count());
+    }
+}
+
+#[test]
+fn behaves_like_str() {
+    let inline = SmallString::from("key");
+    let heap = SmallString::from("a key that does not fit inline");
+    assert_eq!("key", inline);
+    assert_eq!(inline, SmallString::from("key".to_string()));
+    assert!(heap < inline);
+    assert_eq!("\"key\"", format!("{inline:?}"));
+    assert_eq!("a key that does not fit inline", heap.to_string());
+    assert_eq!("a key that does not fit inline", String::from(heap.clone()));
+    let set: HashSet<SmallString> = [inline, heap].into_iter().collect();
+    assert!(set.contains("key"));
+    assert!(set.contains("a key that does not fit inline"));
+}
+
+#[test]
+fn parsed_strings() {
+    let value = parse_bytes(br#"{"short": "value", "a key that does not fit inline": "a value that does not fit inline"}"#).unwrap();
+    let JSONValue::String { string } = &value["short"] else { panic!("expected a string") };
+    assert!(string.is_inline());
+    let JSONValue::String { string } = &value["a key that does not fit inline"] else { panic!("expected a string") };
+    assert!(!string.is_inline());
+    assert_eq!("a value that does not fit inline", string);
+}
@@ -0,0 +1,103 @@
+use myjson::{json, parse_with_spans};
+use myjson::schema::Schema;
+use myjson::spans::{Position, Span};
+use myjson::types::{Dialect, ParseError};
+
+const CONFIG: &str = "// Service configuration
+{
+  \"name\": \"api\",
+  \"ports\": [80, 443],
+  \"tls\": { \"cert\": \"ünïcode.pem\", \"key\": null },
+  \"a/b~c\": true
+}
+";
+
+fn position(line: usize, column: usize) -> Position {
+    Position { line, column }
+}
+
+fn text<'a>(source: &'a str, span: &Span) -> &'a str {
+    &source[span.bytes.clone()]
+}
+
+#[test]
+fn spans_of_every_value() {
+    let (value, spans) = parse_with_spans(CONFIG, Dialect::Jsonc).unwrap();
+    assert_eq!(json!({ "name": "api", "ports": [80, 443], "tls": { "cert": "ünïcode.pem", "key": null }, "a/b~c": true }), value);
+    assert_eq!(9, spans.len());
+    assert!(text(CONFIG, spans.get("").unwrap()).starts_with("{\n  \"name\""));
+    assert_eq!("\"api\"", text(CONFIG, spans.get("/name").unwrap()));
+    assert_eq!("[80, 443]", text(CONFIG, spans.get("/ports").unwrap()));
+    assert_eq!("443", text(CONFIG, spans.get("/ports/1").unwrap()));
+    assert_eq!("null", text(CONFIG, spans.get("/tls/key").unwrap()));
+    assert_eq!("true", text(CONFIG, spans.get("/a~1b~0c").unwrap()));
+    assert_eq!(None, spans.get("/ports/2"));
+}
+
+#[test]
+fn lines_and_columns() {
+    let (_, spans) = parse_with_spans(CONFIG, Dialect::Jsonc).unwrap();
+    let root = spans.get("").unwrap();
+    assert_eq!((position(2, 1), position(7, 2)), (root.start, root.end));
+    let ports = spans.get("/ports/0").unwrap();
+    assert_eq!((position(4, 13), position(4, 15)), (ports.start, ports.end));
+    // Columns count characters
+    let key = spans.get("/tls/key").unwrap();
+    assert_eq!(position(5, 42), key.start);
+    assert_eq!(key.start, Position::of(CONFIG, key.bytes.start));
+}
+
+#[test]
+fn line_terminators() {
+    // "\r\n" is one line break, a lone "\r", U+2028 and U+2029 are line breaks too
+    let source = "[1,\r\n2,\r3,\u{2028}4,\u{2029}5]";
+    let (_, spans) = parse_with_spans(source, Dialect::Json5).unwrap();
+    let starts: Vec<_> = (0..5).map(|i| spans.get(&format!("/{i}")).unwrap().start).collect();
+    assert_eq!(vec![position(1, 2), position(2, 1), position(3, 1), position(4, 1), position(5, 1)], starts);
+    for span in spans.iter().map(|(_, span)| span) {
+        assert_eq!(span.start, Position::of(source, span.bytes.start));
+    }
+}
+
+#[test]
+fn position_of_any_offset() {
+    // Offsets inside a character belong to it, offsets past the end are the end
+    assert_eq!(position(1, 2), Position::of("aé", 2));
+    assert_eq!(position(1, 3), Position::of("aé", 3));
+    assert_eq!(position(2, 2), Position::of("a\nb", 10));
+}
+
+#[test]
+fn duplicate_keys() {
+    let source = r#"{"a": {"x": 1}, "a": {"y": 2}}"#;
+    let (value, spans) = parse_with_spans(source, Dialect::Json).unwrap();
+    assert_eq!(json!({ "a": { "y": 2 } }), value);
+    assert_eq!(r#"{"y": 2}"#, text(source, spans.get("/a").unwrap()));
+    assert_eq!(None, spans.get("/a/x"));
+    assert_eq!(vec!["", "/a", "/a/y"], { let mut pointers: Vec<_> = spans.iter().map(|(pointer, _)| pointer).collect(); pointers.sort(); pointers });
+}
+
+#[test]
+fn json5() {
+    let source = "{unquoted: 'single', hex: 0x1F, list: [+1,],}";
+    let (_, spans) = parse_with_spans(source, Dialect::Json5).unwrap();
+    assert_eq!("'single'", text(source, spans.get("/unquoted").unwrap()));
+    assert_eq!("0x1F", text(source, spans.get("/hex").unwrap()));
+    assert_eq!("+1", text(source, spans.get("/list/0").unwrap()));
+}
+
+#[test]
+fn locates_validation_errors() {
+    let schema = Schema::compile(&json!({ "properties": { "ports": { "items": { "maximum": 100 } } } })).unwrap();
+    let (value, spans) = parse_with_spans(CONFIG, Dialect::Jsonc).unwrap();
+    let errors = schema.validate(&value).unwrap_err();
+    assert_eq!(1, errors.len());
+    let span = spans.get(&errors[0].instance_path).unwrap();
+    assert_eq!("443", text(CONFIG, span));
+    assert_eq!(position(4, 17), span.start);
+}
+
+#[test]
+fn invalid_input() {
+    assert_eq!(Err(ParseError::Unknown), parse_with_spans("[1,", Dialect::Json).map(|_| ()));
+}
@@ -1,5 +1,5 @@
 use rustc_hash::FxHashMap;
-use myjson::stringify;
+use myjson::{stringify, stringify_pretty};
 use myjson::types::JSONValue;
 use myjson::types::JSONValue::{Array, False, Null, Number, Object, True};
 
@@ -24,19 +24,19 @@ fn stringify_false() {
 
 #[test]
 fn stringify_string() {
-    assert_stringify(r#""""#, JSONValue::String { string: "".to_string() });
-    assert_stringify(r#""abc""#, JSONValue::String { string: "abc".to_string() });
-    for i in (0x0..=0x7).into_iter().chain((0xe..=0x1f).into_iter()) {
-        assert_stringify(&format!("\"\\u{i:0>4x}\""), JSONValue::String { string: <char>::from_u32(i).unwrap().to_string() });
+    assert_stringify(r#""""#, JSONValue::String { string: "".into() });
+    assert_stringify(r#""abc""#, JSONValue::String { string: "abc".into() });
+    for i in (0x0..=0x7).chain(0xe..=0x1f) {
+        assert_stringify(&format!("\"\\u{i:0>4x}\""), JSONValue::String { string: <char>::from_u32(i).unwrap().to_string().into() });
     }
-    assert_stringify("\"\\u000b\"", JSONValue::String { string: "\u{000b}".to_string() });
-    assert_stringify("\"\\b\"", JSONValue::String { string: "\u{0008}".to_string() });
-    assert_stringify("\"\\f\"", JSONValue::String { string: "\u{000C}".to_string() });
-    assert_stringify("\"\\n\"", JSONValue::String { string: "\u{000A}".to_string() });
-    assert_stringify("\"\\r\"", JSONValue::String { string: "\u{000D}".to_string() });
-    assert_stringify("\"\\t\"", JSONValue::String { string: "\u{0009}".to_string() });
-    assert_stringify(r#""\"""#, JSONValue::String { string: "\"".to_string() });
-    assert_stringify(r#""\\""#, JSONValue::String { string: "\\".to_string() });
+    assert_stringify("\"\\u000b\"", JSONValue::String { string: "\u{000b}".into() });
+    assert_stringify("\"\\b\"", JSONValue::String { string: "\u{0008}".into() });
+    assert_stringify("\"\\f\"", JSONValue::String { string: "\u{000C}".into() });
+    assert_stringify("\"\\n\"", JSONValue::String { string: "\u{000A}".into() });
+    assert_stringify("\"\\r\"", JSONValue::String { string: "\u{000D}".into() });
+    assert_stringify("\"\\t\"", JSONValue::String { string: "\u{0009}".into() });
+    assert_stringify(r#""\"""#, JSONValue::String { string: "\"".into() });
+    assert_stringify(r#""\\""#, JSONValue::String { string: "\\".into() });
 }
 
 #[test]
@@ -50,8 +50,8 @@ fn stringify_array() {
 #[test]
 fn stringify_object() {
     assert_stringify(r#"{}"#, Object { data: FxHashMap::default() });
-    assert_stringify(r#"{"a":true}"#, Object { data: FxHashMap::from_iter([("a".to_string(), True)]) });
-    assert_stringify(r#"{"a":true,"b":false}"#, Object { data: FxHashMap::from_iter([("a".to_string(), True), ("b".to_string(), False)]) });
+    assert_stringify(r#"{"a":true}"#, Object { data: FxHashMap::from_iter([("a".into(), True)]) });
+    assert_stringify(r#"{"a":true,"b":false}"#, Object { data: FxHashMap::from_iter([("a".into(), True), ("b".into(), False)]) });
 }
 
 #[test]
@@ -62,4 +62,27 @@ fn stringify_number() {
     // library.
     assert_stringify("0.0", Number { number: 0. });
     assert_stringify("1e100", Number { number: 1e100 });
-}
\ No newline at end of file
+}
+
+#[test]
+fn stringify_pretty_nested() {
+    let value = myjson::json!({ "a": [1, { "b": null }, [], {}] });
+    assert_eq!("{\n  \"a\": [\n    1.0,\n    {\n      \"b\": null\n    },\n    [],\n    {}\n  ]\n}", myjson::stringify_pretty(&value));
+    assert_eq!("true", myjson::stringify_pretty(&True));
+}
+
+#[test]
+fn display() {
+    let value = myjson::json!({ "a": [1, "x"] });
+    assert_eq!(r#"{"a":[1.0,"x"]}"#, format!("{value}"));
+    assert_eq!("{\n  \"a\": [\n    1.0,\n    \"x\"\n  ]\n}", format!("{value:#}"));
+    assert_eq!("null", Null.to_string());
+}
+
+#[test]
+fn stringify_non_finite_numbers_as_null() {
+    let input = Array { data: vec![Number { number: f64::NAN }, Number { number: f64::INFINITY }, Number { number: f64::NEG_INFINITY }] };
+    assert_stringify("[null,null,null]", input.clone());
+    assert_eq!("[\n  null,\n  null,\n  null\n]", stringify_pretty(&input));
+    assert_eq!("[null,null,null]", input.to_string());
+}
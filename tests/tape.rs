@@ -0,0 +1,59 @@
+use myjson::{json, parse_bytes, parse_bytes_to_tape, ToJson};
+
+fn assert_round_trip(input: &str) {
+    let tape = parse_bytes_to_tape(input.as_bytes()).unwrap();
+    assert_eq!(parse_bytes(input.as_bytes()).unwrap(), tape.root().to_json(), "{input}");
+}
+
+#[test]
+fn matches_parse_bytes() {
+    assert_round_trip("null");
+    assert_round_trip(" true ");
+    assert_round_trip("-12.5e3");
+    assert_round_trip(r#""a\nbé""#);
+    assert_round_trip("[]");
+    assert_round_trip("{}");
+    assert_round_trip(r#"{"a": [1, {"b": [[], {}]}, "c"], "d": {"e": null}, "f": false}"#);
+    let citm_catalog = std::fs::read_to_string("data/citm_catalog.json").unwrap();
+    assert_round_trip(&citm_catalog);
+}
+
+#[test]
+fn invalid_documents() {
+    for input in ["", "[1,]", r#"{"a" 1}"#, "[1] 2", r#""\ud800""#, "[\"\u{1}\"]", "{1: 2}", "tru"] {
+        assert!(parse_bytes_to_tape(input.as_bytes()).is_err(), "{input}");
+    }
+}
+
+#[test]
+fn navigation() {
+    let tape = parse_bytes_to_tape(br#"{"name": "x", "tags": ["a", [1, 2], {"k": true}, null], "n": 1.5, "name": "y"}"#).unwrap();
+    let root = tape.root();
+    let object = root.as_object().unwrap();
+    assert_eq!(4, object.len());
+    assert_eq!(vec!["name", "tags", "n", "name"], object.iter().map(|(key, _)| key).collect::<Vec<_>>());
+    assert_eq!(Some("y"), root.get("name").and_then(|value| value.as_str()));
+    assert_eq!(Some(1.5), root.get("n").and_then(|value| value.as_f64()));
+    assert!(root.get("missing").is_none());
+    assert!(root.as_array().is_none());
+
+    let tags = root.get("tags").unwrap().as_array().unwrap();
+    assert_eq!(4, tags.len());
+    assert!(!tags.is_empty());
+    assert_eq!(Some("a"), tags.get(0).unwrap().as_str());
+    assert_eq!(json!([1, 2]), tags.get(1).unwrap().to_json());
+    assert_eq!(Some(true), tags.get(2).unwrap().get("k").unwrap().as_bool());
+    assert!(tags.get(3).unwrap().is_null());
+    assert!(tags.get(4).is_none());
+    assert_eq!(4, tags.into_iter().count());
+}
+
+#[test]
+fn empty_containers() {
+    let tape = parse_bytes_to_tape(b"[[], {}]").unwrap();
+    let array = tape.root().as_array().unwrap();
+    assert!(array.get(0).unwrap().as_array().unwrap().is_empty());
+    assert!(array.get(1).unwrap().as_object().unwrap().is_empty());
+    assert_eq!(0, array.get(1).unwrap().as_object().unwrap().len());
+    assert_eq!("Tape { root: [[],{}] }", format!("{tape:?}"));
+}
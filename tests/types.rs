@@ -0,0 +1,78 @@
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashSet};
+use std::hash::{Hash, Hasher};
+use myjson::{json, parse_bytes};
+use myjson::types::JSONValue;
+
+fn hash(value: &JSONValue) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn hash_consistent_with_eq() {
+    assert_eq!(json!(0.0), json!(-0.0));
+    assert_eq!(hash(&json!(0.0)), hash(&json!(-0.0)));
+    let a = parse_bytes(br#"{"a":1,"b":[true,{"c":null}],"d":"e"}"#).unwrap();
+    let b = parse_bytes(br#"{"d":"e","b":[true,{"c":null}],"a":1}"#).unwrap();
+    assert_eq!(a, b);
+    assert_eq!(hash(&a), hash(&b));
+    assert_ne!(hash(&json!([1, 2])), hash(&json!([2, 1])));
+    assert_ne!(hash(&json!(false)), hash(&json!(true)));
+}
+
+#[test]
+fn nan_agrees_with_ord_and_hash() {
+    let number = |number| JSONValue::Number { number };
+    let nan = number(f64::NAN);
+    assert_eq!(nan, nan.clone());
+    assert_eq!(Ordering::Equal, nan.cmp(&nan.clone()));
+    assert_eq!(hash(&nan), hash(&nan.clone()));
+    assert_ne!(nan, number(-f64::NAN));
+    assert_eq!(Ordering::Greater, nan.cmp(&number(f64::INFINITY)));
+    assert_eq!(Ordering::Less, number(-f64::NAN).cmp(&number(f64::NEG_INFINITY)));
+    let values = [nan.clone(), nan.clone(), number(f64::INFINITY), json!(1)];
+    assert_eq!(3, values.iter().cloned().collect::<HashSet<_>>().len());
+    assert_eq!(3, values.into_iter().collect::<BTreeSet<_>>().len());
+}
+
+#[test]
+fn dedupe() {
+    let values = vec![json!({ "a": 1 }), json!(-0.0), json!({ "a": 1.0 }), json!(0), json!("0"), json!(null)];
+    assert_eq!(4, values.iter().cloned().collect::<HashSet<_>>().len());
+    assert_eq!(4, values.into_iter().collect::<BTreeSet<_>>().len());
+}
+
+#[test]
+fn total_order() {
+    let ordered = vec![
+        json!(null),
+        json!(false),
+        json!(true),
+        json!(-1.5),
+        json!(0),
+        json!(2),
+        json!(""),
+        json!("a"),
+        json!("b"),
+        json!([]),
+        json!([1]),
+        json!([1, 2]),
+        json!([2]),
+        json!({}),
+        json!({ "a": 1 }),
+        json!({ "a": 1, "b": 0 }),
+        json!({ "a": 2 }),
+        json!({ "b": 0 }),
+    ];
+    for (i, a) in ordered.iter().enumerate() {
+        for (j, b) in ordered.iter().enumerate() {
+            assert_eq!(i.cmp(&j), a.cmp(b), "comparing {a} with {b}");
+        }
+    }
+    let mut shuffled: Vec<_> = ordered.iter().rev().cloned().collect();
+    shuffled.sort();
+    assert_eq!(ordered, shuffled);
+}